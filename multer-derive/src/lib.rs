@@ -0,0 +1,177 @@
+//! The `FromMultipart` derive macro of [multer](https://docs.rs/multer).
+//!
+//! This crate is re-exported by `multer` with its `derive` feature enabled,
+//! and isn't meant to be used directly.
+
+#![forbid(unsafe_code)]
+#![warn(rust_2018_idioms)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, GenericArgument, LitInt, LitStr, PathArguments, Type};
+
+/// Derives `multer::FromMultipart` for a struct with named fields, reading
+/// each field of the struct from the multipart field of the same name.
+///
+/// See the documentation of `multer::FromMultipart` for the supported field
+/// types and attributes.
+#[proc_macro_derive(FromMultipart, attributes(multer))]
+pub fn derive_from_multipart(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// How the values of the multipart fields of the same name are collected.
+enum Kind {
+    /// The last value is kept, and the field must be present.
+    Required,
+    /// The last value is kept, if any.
+    Optional,
+    /// All the values are kept.
+    Repeated,
+}
+
+struct FormField<'a> {
+    ident: &'a syn::Ident,
+    name: String,
+    kind: Kind,
+    ty: &'a Type,
+    spool: Option<LitInt>,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    &input.ident,
+                    "`FromMultipart` can only be derived for a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "`FromMultipart` can only be derived for a struct",
+            ))
+        }
+    };
+
+    let fields = fields.iter().map(parse_field).collect::<syn::Result<Vec<_>>>()?;
+
+    let vars: Vec<_> = fields.iter().map(|field| format_ident!("__{}", field.ident)).collect();
+    let inits = fields.iter().map(|field| {
+        let ty = field.ty;
+        match field.kind {
+            Kind::Required | Kind::Optional => quote!(::std::option::Option<#ty> = ::std::option::Option::None),
+            Kind::Repeated => quote!(::std::vec::Vec<#ty> = ::std::vec::Vec::new()),
+        }
+    });
+    let arms = fields.iter().zip(&vars).map(|(field, var)| {
+        let name = &field.name;
+        let ty = field.ty;
+        let read = match &field.spool {
+            Some(threshold) => quote!(::multer::SpooledFormFile::spool(field, #threshold).await?),
+            None => quote!(<#ty as ::multer::FromField>::from_field(field).await?),
+        };
+        let assign = match field.kind {
+            Kind::Required | Kind::Optional => quote!(#var = ::std::option::Option::Some(#read)),
+            Kind::Repeated => quote!(#var.push(#read)),
+        };
+
+        quote!(::std::option::Option::Some(#name) => #assign,)
+    });
+    let values = fields.iter().zip(&vars).map(|(field, var)| {
+        let ident = field.ident;
+        let name = &field.name;
+        match field.kind {
+            Kind::Required => quote! {
+                #ident: #var.ok_or_else(|| ::multer::__private::missing_field(#name))?
+            },
+            Kind::Optional | Kind::Repeated => quote!(#ident: #var),
+        }
+    });
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::multer::FromMultipart for #ident #ty_generics #where_clause {
+            fn from_multipart<'a, 'r: 'a>(
+                multipart: &'a mut ::multer::Multipart<'r>,
+            ) -> ::multer::__private::BoxFuture<'a, ::multer::Result<Self>> {
+                ::std::boxed::Box::pin(async move {
+                    #( let mut #vars: #inits; )*
+
+                    while let ::std::option::Option::Some(field) = multipart.next_field().await? {
+                        match field.name() {
+                            #( #arms )*
+                            _ => {}
+                        }
+                    }
+
+                    ::std::result::Result::Ok(#ident { #( #values, )* })
+                })
+            }
+        }
+    })
+}
+
+fn parse_field(field: &syn::Field) -> syn::Result<FormField<'_>> {
+    let ident = field.ident.as_ref().expect("named field");
+    let mut name = ident.unraw().to_string();
+    let mut spool = None;
+
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("multer")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                name = meta.value()?.parse::<LitStr>()?.value();
+                Ok(())
+            } else if meta.path.is_ident("spool") {
+                spool = Some(meta.value()?.parse::<LitInt>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unknown `multer` attribute, expected `rename` or `spool`"))
+            }
+        })?;
+    }
+
+    let (kind, ty) = match container(&field.ty) {
+        Some(("Option", ty)) => (Kind::Optional, ty),
+        Some(("Vec", ty)) => (Kind::Repeated, ty),
+        _ => (Kind::Required, &field.ty),
+    };
+
+    Ok(FormField {
+        ident,
+        name,
+        kind,
+        ty,
+        spool,
+    })
+}
+
+/// Splits an `Option<T>` or a `Vec<T>` into its name and `T`.
+fn container(ty: &Type) -> Option<(&'static str, &Type)> {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return None,
+    };
+
+    let segment = path.segments.last()?;
+    let name = match segment.ident.to_string().as_str() {
+        "Option" => "Option",
+        "Vec" => "Vec",
+        _ => return None,
+    };
+
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match args.args.first()? {
+            GenericArgument::Type(ty) => Some((name, ty)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
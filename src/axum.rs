@@ -0,0 +1,132 @@
+//! An [axum](https://docs.rs/axum) extractor parsing the request body with
+//! multer, so that handlers get multer's constraints and file name handling
+//! without writing any glue.
+//!
+//! The [`Constraints`] applied to the body are taken from the request
+//! extensions, e.g. added with axum's `Extension` layer, and default to
+//! [`Constraints::default()`] otherwise.
+//!
+//! # Optional
+//!
+//! This requires the optional `axum` feature to be enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use multer::axum::Multipart;
+//!
+//! async fn upload(mut multipart: Multipart) -> Result<String, String> {
+//!     let mut names = Vec::new();
+//!     while let Some(field) = multipart.next_field().await.map_err(|err| err.to_string())? {
+//!         names.extend(field.name().map(str::to_owned));
+//!     }
+//!
+//!     Ok(names.join(", "))
+//! }
+//! # let _ = upload;
+//! ```
+//!
+//! With constraints, given an axum `Router`:
+//!
+//! ```ignore
+//! use axum::{routing::post, Extension, Router};
+//! use multer::{Constraints, SizeLimit};
+//!
+//! let constraints = Constraints::new().size_limit(SizeLimit::new().per_field(1024 * 1024));
+//! let app: Router = Router::new()
+//!     .route("/upload", post(upload))
+//!     .layer(Extension(constraints));
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Deref, DerefMut};
+
+use axum_core::extract::{FromRequest, Request};
+use axum_core::response::{IntoResponse, Response};
+use http::StatusCode;
+
+use crate::constraints::Constraints;
+use crate::error::Error;
+use crate::request::RequestExt;
+
+/// An extractor parsing a `multipart/form-data` request body into a
+/// [`Multipart`](crate::Multipart).
+///
+/// It dereferences to the [`Multipart`](crate::Multipart), so that its fields
+/// can be read with [`next_field()`](crate::Multipart::next_field).
+///
+/// # Optional
+///
+/// This requires the optional `axum` feature to be enabled.
+#[derive(Debug)]
+pub struct Multipart {
+    inner: crate::Multipart<'static>,
+}
+
+impl Multipart {
+    /// Returns the underlying [`Multipart`](crate::Multipart).
+    pub fn into_inner(self) -> crate::Multipart<'static> {
+        self.inner
+    }
+}
+
+impl Deref for Multipart {
+    type Target = crate::Multipart<'static>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl DerefMut for Multipart {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.inner
+    }
+}
+
+impl<S: Send + Sync> FromRequest<S> for Multipart {
+    type Rejection = MultipartRejection;
+
+    async fn from_request(req: Request, _state: &S) -> Result<Self, Self::Rejection> {
+        let constraints = req.extensions().get::<Constraints>().cloned().unwrap_or_default();
+        let inner = req.into_multipart(constraints).map_err(MultipartRejection)?;
+
+        Ok(Multipart { inner })
+    }
+}
+
+/// The rejection of the [`Multipart`] extractor, when the request isn't a
+/// `multipart/form-data` request with a boundary.
+///
+/// It's turned into a `400 Bad Request` response.
+///
+/// # Optional
+///
+/// This requires the optional `axum` feature to be enabled.
+#[derive(Debug)]
+pub struct MultipartRejection(Error);
+
+impl MultipartRejection {
+    /// Returns the error the request was rejected with.
+    pub fn into_error(self) -> Error {
+        self.0
+    }
+}
+
+impl Display for MultipartRejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for MultipartRejection {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl IntoResponse for MultipartRejection {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, self.0.to_string()).into_response()
+    }
+}
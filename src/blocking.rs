@@ -0,0 +1,250 @@
+//! A blocking parser reading from a [`Read`] source, for programs which don't
+//! run an async runtime, e.g. CLI tools, tests and synchronous servers.
+//!
+//! # Examples
+//!
+//! ```
+//! use multer::blocking::Multipart;
+//!
+//! # fn run() -> multer::Result<()> {
+//! let data: &[u8] =
+//!     b"--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+//! let multipart = Multipart::new(data, "X-BOUNDARY");
+//!
+//! for field in multipart {
+//!     let field = field?;
+//!     let name = field.name().map(str::to_owned);
+//!     println!("{:?}: {}", name, field.text()?);
+//! }
+//! # Ok(())
+//! # }
+//! # run().unwrap();
+//! ```
+
+use std::future::Future;
+use std::io::{self, Read};
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+use bytes::{Buf, Bytes, BytesMut};
+use futures_util::stream;
+use http::header::HeaderMap;
+
+use crate::constraints::Constraints;
+
+/// The size of the chunks read from the source.
+const CHUNK_SIZE: usize = 8 * 1024;
+
+/// A blocking version of [`Multipart`](crate::Multipart), reading the body
+/// from a [`Read`] source and yielding its fields through an [`Iterator`].
+///
+/// Any previous [`Field`] must be dropped before getting the next one, as with
+/// [`Multipart::next_field()`](crate::Multipart::next_field).
+///
+/// The constraints relying on the Tokio timer, like
+/// `Constraints::deadline()`, can't be used, as no runtime drives it.
+#[derive(Debug)]
+pub struct Multipart<'r> {
+    inner: crate::Multipart<'r>,
+    done: bool,
+}
+
+impl<'r> Multipart<'r> {
+    /// Construct a new `Multipart` instance with the given [`Read`] source
+    /// and the boundary.
+    pub fn new<R, B>(reader: R, boundary: B) -> Self
+    where
+        R: Read + Send + 'r,
+        B: Into<String>,
+    {
+        Multipart::with_constraints(reader, boundary, Constraints::default())
+    }
+
+    /// Construct a new `Multipart` instance with the given [`Read`] source,
+    /// the boundary and the constraints.
+    pub fn with_constraints<R, B>(reader: R, boundary: B, constraints: Constraints) -> Self
+    where
+        R: Read + Send + 'r,
+        B: Into<String>,
+    {
+        let stream = stream::iter(read_chunks(reader));
+
+        Multipart {
+            inner: crate::Multipart::with_constraints(stream, boundary, constraints),
+            done: false,
+        }
+    }
+
+    /// Yields the next [`Field`] if available, blocking until its headers have
+    /// been read.
+    pub fn next_field(&mut self) -> crate::Result<Option<Field<'r>>> {
+        let field = block_on(self.inner.next_field())?;
+        Ok(field.map(|inner| Field {
+            inner,
+            pending: Bytes::new(),
+        }))
+    }
+}
+
+/// Yields the fields until the end of the body or the first error.
+impl<'r> Iterator for Multipart<'r> {
+    type Item = crate::Result<Field<'r>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let field = self.next_field().transpose();
+        self.done = !matches!(field, Some(Ok(_)));
+        field
+    }
+}
+
+/// A single field of a blocking [`Multipart`], whose data can be read
+/// through [`Read`].
+#[derive(Debug)]
+pub struct Field<'r> {
+    inner: crate::Field<'r>,
+    /// The data of the last chunk which hasn't been read yet.
+    pending: Bytes,
+}
+
+impl<'r> Field<'r> {
+    /// The field name found in the `Content-Disposition` header.
+    pub fn name(&self) -> Option<&str> {
+        self.inner.name()
+    }
+
+    /// The file name found in the `Content-Disposition` header.
+    pub fn file_name(&self) -> Option<&str> {
+        self.inner.file_name()
+    }
+
+    /// Get the content type of the field.
+    pub fn content_type(&self) -> Option<&mime::Mime> {
+        self.inner.content_type()
+    }
+
+    /// Get a map of headers as [`HeaderMap`].
+    pub fn headers(&self) -> &HeaderMap {
+        self.inner.headers()
+    }
+
+    /// Get the index of this field in order they appeared in the stream.
+    pub fn index(&self) -> usize {
+        self.inner.index()
+    }
+
+    /// Reads the next chunk of the field data, or [`None`] once it has been
+    /// exhausted.
+    pub fn chunk(&mut self) -> crate::Result<Option<Bytes>> {
+        match self.pending.is_empty() {
+            true => block_on(self.inner.chunk()),
+            false => Ok(Some(std::mem::take(&mut self.pending))),
+        }
+    }
+
+    /// Reads the rest of the field data.
+    pub fn bytes(mut self) -> crate::Result<Bytes> {
+        if self.pending.is_empty() {
+            return block_on(self.inner.bytes());
+        }
+
+        let mut buf = BytesMut::from(&self.pending[..]);
+        while let Some(bytes) = block_on(self.inner.chunk())? {
+            buf.extend_from_slice(&bytes);
+        }
+
+        Ok(buf.freeze())
+    }
+
+    /// Reads the field data as text, as [`Field::text()`](crate::Field::text).
+    ///
+    /// If part of the data has already been read, the rest is decoded as
+    /// UTF-8.
+    pub fn text(self) -> crate::Result<String> {
+        match self.pending.is_empty() {
+            true => block_on(self.inner.text()),
+            false => Ok(String::from_utf8_lossy(&self.bytes()?).into_owned()),
+        }
+    }
+
+    /// Returns the underlying async [`Field`](crate::Field), unless part of
+    /// its data has already been read.
+    pub fn into_inner(self) -> Option<crate::Field<'r>> {
+        self.pending.is_empty().then_some(self.inner)
+    }
+}
+
+impl Read for Field<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending.is_empty() {
+            match block_on(self.inner.chunk()) {
+                Ok(Some(bytes)) => self.pending = bytes,
+                Ok(None) => return Ok(0),
+                Err(err) => return Err(io::Error::other(err)),
+            }
+        }
+
+        let len = buf.len().min(self.pending.len());
+        buf[..len].copy_from_slice(&self.pending[..len]);
+        self.pending.advance(len);
+        Ok(len)
+    }
+}
+
+/// Reads the source in chunks, retrying interrupted reads.
+fn read_chunks<R: Read>(mut reader: R) -> impl Iterator<Item = io::Result<Bytes>> {
+    let mut done = false;
+
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+
+        let mut buf = vec![0; CHUNK_SIZE];
+        loop {
+            match reader.read(&mut buf) {
+                Ok(0) => {
+                    done = true;
+                    return None;
+                }
+                Ok(len) => {
+                    buf.truncate(len);
+                    return Some(Ok(buf.into()));
+                }
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    done = true;
+                    return Some(Err(err));
+                }
+            }
+        }
+    })
+}
+
+/// Wakes up the thread blocked on a future.
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Runs the future to completion on the current thread.
+fn block_on<F: Future>(future: F) -> F::Output {
+    futures_util::pin_mut!(future);
+
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
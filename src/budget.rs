@@ -0,0 +1,102 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A cap on the total number of bytes buffered by all the [`Multipart`]
+/// parsers sharing it, to bound the memory used by concurrent uploads.
+///
+/// Handles are cheap to clone and all clones share the same budget. A parser
+/// reserves room for each chunk it buffers and releases it once the data has
+/// been consumed, or when it's dropped. A chunk that doesn't fit in the
+/// remaining budget fails the parser with
+/// [`Error::MemoryBudgetExceeded`](crate::Error::MemoryBudgetExceeded).
+///
+/// Please refer
+/// [`Constraints::memory_budget()`](crate::Constraints::memory_budget) for more
+/// info.
+///
+/// # Examples
+///
+/// ```
+/// use multer::{Constraints, MemoryBudget};
+///
+/// // Shared by all the requests of the server.
+/// let budget = MemoryBudget::new(64 * 1024 * 1024);
+///
+/// // For each request.
+/// let constraints = Constraints::new().memory_budget(budget.clone());
+/// ```
+///
+/// [`Multipart`]: crate::Multipart
+#[derive(Debug, Clone)]
+pub struct MemoryBudget {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    limit: u64,
+    used: AtomicU64,
+}
+
+impl MemoryBudget {
+    /// Creates a budget of `limit` bytes.
+    pub fn new(limit: u64) -> MemoryBudget {
+        MemoryBudget {
+            inner: Arc::new(Inner {
+                limit,
+                used: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// The total number of bytes of the budget.
+    pub fn limit(&self) -> u64 {
+        self.inner.limit
+    }
+
+    /// The number of bytes currently buffered by the parsers sharing the
+    /// budget.
+    pub fn used(&self) -> u64 {
+        self.inner.used.load(Ordering::Acquire)
+    }
+
+    /// Reserves `size` bytes, failing if they don't fit in the remaining
+    /// budget.
+    pub(crate) fn try_reserve(&self, size: u64) -> crate::Result<()> {
+        let limit = self.inner.limit;
+        self.inner
+            .used
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |used| {
+                used.checked_add(size).filter(|used| *used <= limit)
+            })
+            .map(|_| ())
+            .map_err(|used| crate::Error::MemoryBudgetExceeded {
+                limit,
+                requested: used.saturating_add(size),
+            })
+    }
+
+    pub(crate) fn release(&self, size: u64) {
+        self.inner.used.fetch_sub(size, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_budget() {
+        let budget = MemoryBudget::new(10);
+        let shared = budget.clone();
+
+        budget.try_reserve(6).unwrap();
+        assert!(shared.try_reserve(5).is_err());
+        shared.try_reserve(4).unwrap();
+        assert_eq!(budget.used(), 10);
+
+        budget.release(6);
+        assert_eq!(shared.used(), 4);
+        shared.try_reserve(6).unwrap();
+    }
+}
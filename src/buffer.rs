@@ -1,65 +1,321 @@
+use std::collections::VecDeque;
 use std::fmt;
+use std::ops::Deref;
 use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use bytes::{Buf, Bytes, BytesMut};
 use futures_util::stream::Stream;
 
+use crate::budget::MemoryBudget;
+#[cfg(feature = "cancellation")]
+use crate::cancel::Cancellation;
 use crate::constants;
+#[cfg(feature = "tokio-time")]
+use crate::rate_limit::TokenBucket;
+use crate::throughput::MinThroughput;
+#[cfg(feature = "tokio-time")]
+use crate::timeout::{Deadline, IdleTimeout};
+
+/// The buffered bytes of the stream.
+///
+/// A chunk received into an empty buffer is kept as-is, so that data split off
+/// from it are zero-copy slices of the chunk. Only when bytes have to be
+/// appended to a non-empty buffer are they copied into an owned buffer.
+enum Buffer {
+    Shared(Bytes),
+    Owned(BytesMut),
+}
+
+impl Buffer {
+    fn extend(&mut self, data: Bytes) {
+        match self {
+            _ if self.is_empty() => *self = Buffer::Shared(data),
+            Buffer::Shared(bytes) => {
+                let mut buf = BytesMut::with_capacity(bytes.len() + data.len());
+                buf.extend_from_slice(bytes);
+                buf.extend_from_slice(&data);
+                *self = Buffer::Owned(buf);
+            }
+            Buffer::Owned(buf) => buf.extend_from_slice(&data),
+        }
+    }
+
+    fn split_to(&mut self, at: usize) -> Bytes {
+        match self {
+            Buffer::Shared(bytes) => bytes.split_to(at),
+            Buffer::Owned(buf) => buf.split_to(at).freeze(),
+        }
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        match self {
+            Buffer::Shared(bytes) => bytes.advance(cnt),
+            Buffer::Owned(buf) => buf.advance(cnt),
+        }
+    }
+
+    fn clear(&mut self) {
+        *self = Buffer::Shared(Bytes::new());
+    }
+}
+
+impl Deref for Buffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Buffer::Shared(bytes) => bytes,
+            Buffer::Owned(buf) => buf,
+        }
+    }
+}
 
 pub(crate) struct StreamBuffer<'r> {
     pub(crate) eof: bool,
-    pub(crate) buf: BytesMut,
+    buf: Buffer,
     pub(crate) stream: Pin<Box<dyn Stream<Item = Result<Bytes, crate::Error>> + Send + 'r>>,
     pub(crate) whole_stream_size_limit: u64,
     pub(crate) stream_size_counter: u64,
+    pub(crate) allow_lf_line_endings: bool,
+    pub(crate) discard: bool,
+    pub(crate) min_throughput: Option<MinThroughput>,
+    pub(crate) memory_budget: Option<MemoryBudget>,
+    // The number of bytes reserved from the memory budget.
+    reserved: u64,
+    #[cfg(feature = "tokio-time")]
+    pub(crate) deadline: Option<Deadline>,
+    #[cfg(feature = "tokio-time")]
+    pub(crate) read_timeout: Option<IdleTimeout>,
+    #[cfg(feature = "tokio-time")]
+    pub(crate) rate_limit: Option<TokenBucket>,
+    #[cfg(feature = "tokio-time")]
+    pub(crate) field_rate_limit: Option<TokenBucket>,
+    #[cfg(feature = "cancellation")]
+    pub(crate) cancellation: Option<Cancellation>,
+    pub(crate) raw: Option<RawCapture>,
+}
+
+/// The chunks received from the stream, kept until they have been consumed
+/// and taken with [`StreamBuffer::take_raw()`].
+#[derive(Debug, Default)]
+pub(crate) struct RawCapture {
+    chunks: VecDeque<Bytes>,
+    len: usize,
+}
+
+impl RawCapture {
+    /// Drops the last `cnt` bytes, which won't be consumed.
+    fn discard_back(&mut self, mut cnt: usize) {
+        self.len -= cnt;
+        while let Some(chunk) = self.chunks.back_mut() {
+            if chunk.len() > cnt {
+                chunk.truncate(chunk.len() - cnt);
+                break;
+            }
+
+            cnt -= chunk.len();
+            self.chunks.pop_back();
+        }
+    }
 }
 
 impl<'r> StreamBuffer<'r> {
-    pub fn new<S>(stream: S, whole_stream_size_limit: u64) -> Self
+    pub fn new<S>(stream: S, whole_stream_size_limit: u64, allow_lf_line_endings: bool) -> Self
     where
         S: Stream<Item = Result<Bytes, crate::Error>> + Send + 'r,
     {
         StreamBuffer {
             eof: false,
-            buf: BytesMut::new(),
+            buf: Buffer::Shared(Bytes::new()),
             stream: Box::pin(stream),
             whole_stream_size_limit,
             stream_size_counter: 0,
+            allow_lf_line_endings,
+            discard: false,
+            min_throughput: None,
+            memory_budget: None,
+            reserved: 0,
+            #[cfg(feature = "tokio-time")]
+            deadline: None,
+            #[cfg(feature = "tokio-time")]
+            read_timeout: None,
+            #[cfg(feature = "tokio-time")]
+            rate_limit: None,
+            #[cfg(feature = "tokio-time")]
+            field_rate_limit: None,
+            #[cfg(feature = "cancellation")]
+            cancellation: None,
+            raw: None,
         }
     }
 
     pub fn poll_stream(&mut self, cx: &mut Context<'_>) -> Result<(), crate::Error> {
+        #[cfg(feature = "cancellation")]
+        if let Some(cancellation) = &mut self.cancellation {
+            cancellation.poll(cx)?;
+        }
+
         if self.eof {
             return Ok(());
         }
 
+        #[cfg(feature = "tokio-time")]
+        if let Some(deadline) = &mut self.deadline {
+            deadline.poll(cx)?;
+        }
+
+        self.release_consumed();
+
         loop {
+            #[cfg(feature = "tokio-time")]
+            if self.poll_throttle(cx).is_pending() {
+                return Ok(());
+            }
+
             match self.stream.as_mut().poll_next(cx) {
                 Poll::Ready(Some(Ok(data))) => {
                     self.stream_size_counter += data.len() as u64;
 
+                    if self.discard {
+                        continue;
+                    }
+
                     if self.stream_size_counter > self.whole_stream_size_limit {
                         return Err(crate::Error::StreamSizeExceeded {
                             limit: self.whole_stream_size_limit,
                         });
                     }
 
-                    self.buf.extend_from_slice(&data)
+                    if let Some(throughput) = &mut self.min_throughput {
+                        throughput.on_data(data.len())?;
+                    }
+
+                    #[cfg(feature = "tokio-time")]
+                    if let Some(read_timeout) = &mut self.read_timeout {
+                        read_timeout.on_data();
+                    }
+
+                    #[cfg(feature = "tokio-time")]
+                    for bucket in self.rate_limit.iter_mut().chain(self.field_rate_limit.iter_mut()) {
+                        bucket.on_data(data.len());
+                    }
+
+                    if let Some(budget) = &self.memory_budget {
+                        budget.try_reserve(data.len() as u64)?;
+                        self.reserved += data.len() as u64;
+                    }
+
+                    if let Some(raw) = &mut self.raw {
+                        raw.len += data.len();
+                        raw.chunks.push_back(data.clone());
+                    }
+
+                    self.buf.extend(data)
                 }
                 Poll::Ready(Some(Err(err))) => return Err(err),
                 Poll::Ready(None) => {
                     self.eof = true;
                     return Ok(());
                 }
-                Poll::Pending => return Ok(()),
+                Poll::Pending => {
+                    if let Some(throughput) = &mut self.min_throughput {
+                        throughput.on_pending();
+                    }
+
+                    #[cfg(feature = "tokio-time")]
+                    if let Some(read_timeout) = &mut self.read_timeout {
+                        read_timeout.poll_pending(cx)?;
+                    }
+                    return Ok(());
+                }
             }
         }
     }
 
+    /// Discards the rest of the stream without buffering it, ignoring the
+    /// size limit of the whole stream.
+    pub fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), crate::Error>> {
+        self.discard = true;
+        if let Some(raw) = &mut self.raw {
+            raw.discard_back(self.buf.len());
+        }
+        self.buf.clear();
+        self.release_consumed();
+        self.poll_stream(cx)?;
+
+        match self.eof {
+            true => Poll::Ready(Ok(())),
+            false => Poll::Pending,
+        }
+    }
+
+    /// Waits for the rate limits to allow reading more data.
+    #[cfg(feature = "tokio-time")]
+    fn poll_throttle(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        for bucket in self.rate_limit.iter_mut().chain(self.field_rate_limit.iter_mut()) {
+            if bucket.poll_ready(cx).is_pending() {
+                return Poll::Pending;
+            }
+        }
+
+        Poll::Ready(())
+    }
+
+    /// Gives back to the memory budget the room of the data consumed since
+    /// the last call.
+    fn release_consumed(&mut self) {
+        if let Some(budget) = &self.memory_budget {
+            let consumed = self.reserved.saturating_sub(self.buf.len() as u64);
+            budget.release(consumed);
+            self.reserved -= consumed;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    /// Takes the raw bytes consumed since the last call, if they are
+    /// captured.
+    pub fn take_raw(&mut self) -> Bytes {
+        let raw = match &mut self.raw {
+            Some(raw) => raw,
+            None => return Bytes::new(),
+        };
+
+        let mut consumed = raw.len - self.buf.len();
+        raw.len -= consumed;
+
+        // Bytes consumed from a single chunk are returned without copying.
+        match raw.chunks.front_mut() {
+            Some(chunk) if chunk.len() > consumed => return chunk.split_to(consumed),
+            Some(chunk) if chunk.len() == consumed => return raw.chunks.pop_front().unwrap_or_default(),
+            _ => {}
+        }
+
+        let mut bytes = BytesMut::with_capacity(consumed);
+        while consumed > 0 {
+            let chunk = match raw.chunks.front_mut() {
+                Some(chunk) => chunk,
+                None => break,
+            };
+
+            let n = chunk.len().min(consumed);
+            bytes.extend_from_slice(&chunk.split_to(n));
+            consumed -= n;
+
+            if chunk.is_empty() {
+                raw.chunks.pop_front();
+            }
+        }
+
+        bytes.freeze()
+    }
+
     pub fn read_exact(&mut self, size: usize) -> Option<Bytes> {
         if size <= self.buf.len() {
-            Some(self.buf.split_to(size).freeze())
+            Some(self.buf.split_to(size))
         } else {
             None
         }
@@ -70,11 +326,38 @@ impl<'r> StreamBuffer<'r> {
     }
 
     pub fn read_until(&mut self, pattern: &[u8]) -> Option<Bytes> {
-        memchr::memmem::find(&self.buf, pattern).map(|idx| self.buf.split_to(idx + pattern.len()).freeze())
+        memchr::memmem::find(&self.buf, pattern).map(|idx| self.buf.split_to(idx + pattern.len()))
     }
 
     pub fn read_to(&mut self, pattern: &[u8]) -> Option<Bytes> {
-        memchr::memmem::find(&self.buf, pattern).map(|idx| self.buf.split_to(idx).freeze())
+        memchr::memmem::find(&self.buf, pattern).map(|idx| self.buf.split_to(idx))
+    }
+
+    /// Reads the header section up to and including the empty line ending it.
+    ///
+    /// With bare LF line endings allowed, any of the lines may end with LF
+    /// instead of CRLF.
+    pub fn read_header_section(&mut self) -> Option<Bytes> {
+        if !self.allow_lf_line_endings {
+            return self.read_until(constants::CRLF_CRLF.as_bytes());
+        }
+
+        let end = memchr::memchr_iter(b'\n', &self.buf).find_map(|idx| match &self.buf[(idx + 1)..] {
+            [b'\n', ..] => Some(idx + 2),
+            [b'\r', b'\n', ..] => Some(idx + 3),
+            _ => None,
+        })?;
+
+        Some(self.buf.split_to(end))
+    }
+
+    /// Reads the line ending following a boundary, which is either CRLF or,
+    /// if allowed, a bare LF.
+    pub fn read_line_ending(&mut self) -> Option<Bytes> {
+        match self.allow_lf_line_endings && self.buf.starts_with(constants::LF.as_bytes()) {
+            true => self.read_exact(constants::LF.len()),
+            false => self.read_exact(constants::CRLF.len()),
+        }
     }
 
     pub fn advance_past_transport_padding(&mut self) -> bool {
@@ -106,15 +389,21 @@ impl<'r> StreamBuffer<'r> {
         }
 
         let boundary_deriv = format!("{}{}{}", constants::CRLF, constants::BOUNDARY_EXT, boundary);
-        let b_len = boundary_deriv.len();
+        let delimiter = match self.allow_lf_line_endings {
+            true => &boundary_deriv.as_bytes()[constants::CR.len()..],
+            false => boundary_deriv.as_bytes(),
+        };
+        let d_len = delimiter.len();
 
-        match memchr::memmem::find(&self.buf, boundary_deriv.as_bytes()) {
+        match memchr::memmem::find(&self.buf, delimiter) {
             Some(idx) => {
                 trace!("new field found at {}", idx);
-                let bytes = self.buf.split_to(idx).freeze();
+                let end = self.line_ending_start(idx);
+                let bytes = self.buf.split_to(end);
 
-                // discard \r\n.
-                self.buf.advance(constants::CRLF.len());
+                // discard the line ending.
+                self.buf
+                    .advance(idx - end + d_len - constants::BOUNDARY_EXT.len() - boundary.len());
 
                 Ok(Some((true, bytes)))
             }
@@ -126,29 +415,28 @@ impl<'r> StreamBuffer<'r> {
             }
             None => {
                 let buf_len = self.buf.len();
-                let rem_boundary_part_max_len = b_len - 1;
-                let rem_boundary_part_idx = if buf_len >= rem_boundary_part_max_len {
-                    buf_len - rem_boundary_part_max_len
-                } else {
-                    0
-                };
+                let rem_boundary_part_max_len = d_len - 1;
+                let rem_boundary_part_idx = buf_len.saturating_sub(rem_boundary_part_max_len);
 
                 trace!("no new field found, not EOF, checking close");
                 let bytes = &self.buf[rem_boundary_part_idx..];
-                match memchr::memmem::rfind(bytes, constants::CR.as_bytes()) {
-                    Some(rel_idx) => {
-                        let idx = rel_idx + rem_boundary_part_idx;
-
-                        match memchr::memmem::find(boundary_deriv.as_bytes(), &self.buf[idx..]) {
-                            Some(_) => {
-                                let bytes = self.buf.split_to(idx).freeze();
-
-                                match bytes.is_empty() {
-                                    true => Ok(None),
-                                    false => Ok(Some((false, bytes))),
-                                }
-                            }
-                            None => Ok(Some((false, self.read_full_buf()))),
+                let idx = match memchr::memrchr(delimiter[0], bytes) {
+                    Some(rel_idx) if delimiter.starts_with(&bytes[rel_idx..]) => {
+                        Some(self.line_ending_start(rel_idx + rem_boundary_part_idx))
+                    }
+                    _ if self.allow_lf_line_endings && self.buf.ends_with(constants::CR.as_bytes()) => {
+                        Some(buf_len - constants::CR.len())
+                    }
+                    _ => None,
+                };
+
+                match idx {
+                    Some(idx) => {
+                        let bytes = self.buf.split_to(idx);
+
+                        match bytes.is_empty() {
+                            true => Ok(None),
+                            false => Ok(Some((false, bytes))),
                         }
                     }
                     None => Ok(Some((false, self.read_full_buf()))),
@@ -157,8 +445,25 @@ impl<'r> StreamBuffer<'r> {
         }
     }
 
+    /// Moves back the start of a bare LF delimiter match to include a
+    /// preceding CR.
+    fn line_ending_start(&self, idx: usize) -> usize {
+        match self.allow_lf_line_endings && idx > 0 && self.buf[idx - 1] == b'\r' {
+            true => idx - 1,
+            false => idx,
+        }
+    }
+
     pub fn read_full_buf(&mut self) -> Bytes {
-        self.buf.split_to(self.buf.len()).freeze()
+        self.buf.split_to(self.buf.len())
+    }
+}
+
+impl Drop for StreamBuffer<'_> {
+    fn drop(&mut self) {
+        if let Some(budget) = &self.memory_budget {
+            budget.release(self.reserved);
+        }
     }
 }
 
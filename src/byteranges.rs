@@ -0,0 +1,165 @@
+use bytes::Bytes;
+use futures_util::stream::Stream;
+use http::header::{self, HeaderMap};
+
+use crate::constraints::Constraints;
+use crate::error::Error;
+use crate::field::Field;
+use crate::multipart::Multipart;
+
+/// The byte range carried by a part of a `multipart/byteranges` response, as
+/// found in its [`Content-Range`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Range) header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContentRange {
+    start: u64,
+    end: u64,
+    complete_length: Option<u64>,
+}
+
+impl ContentRange {
+    /// Parses the `Content-Range` header, a `bytes <start>-<end>/<length>`
+    /// value where the complete length may be `*` if unknown.
+    pub(crate) fn parse(headers: &HeaderMap) -> Option<ContentRange> {
+        let value = headers.get(header::CONTENT_RANGE)?.to_str().ok()?.trim();
+
+        let (unit, range) = value.split_once(' ')?;
+        if !unit.eq_ignore_ascii_case("bytes") {
+            return None;
+        }
+
+        let (range, complete_length) = range.trim_start().split_once('/')?;
+        let (start, end) = range.split_once('-')?;
+        let start = start.parse::<u64>().ok()?;
+        let end = end.parse::<u64>().ok()?;
+        let complete_length = match complete_length {
+            "*" => None,
+            length => Some(length.parse::<u64>().ok()?),
+        };
+
+        if end < start || complete_length.is_some_and(|length| end >= length) {
+            return None;
+        }
+
+        Some(ContentRange {
+            start,
+            end,
+            complete_length,
+        })
+    }
+
+    /// The offset of the first byte of the range.
+    pub fn start(&self) -> u64 {
+        self.start
+    }
+
+    /// The offset of the last byte of the range, inclusive.
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+
+    /// The size of the complete representation, or `None` if the server
+    /// declared it unknown.
+    pub fn complete_length(&self) -> Option<u64> {
+        self.complete_length
+    }
+
+    /// The number of bytes in the range.
+    pub fn len(&self) -> u64 {
+        self.end - self.start + 1
+    }
+
+    /// Always `false`, as a byte range spans at least one byte.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+impl<'r> Multipart<'r> {
+    /// Construct a new `Multipart` instance to parse a `multipart/byteranges`
+    /// response with the given [`Bytes`] stream and the value of the
+    /// `Content-Type` header.
+    ///
+    /// The range of each part is available through
+    /// [`field.content_range()`](Field::content_range).
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the `Content-Type` is not `multipart/byteranges`
+    /// or has no `boundary` parameter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::Infallible;
+    ///
+    /// use bytes::Bytes;
+    /// use futures_util::stream::once;
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data =
+    ///     "--X-BOUNDARY\r\nContent-Type: text/plain\r\nContent-Range: bytes 0-3/12\r\n\r\nabcd\r\n\
+    ///     --X-BOUNDARY\r\nContent-Type: text/plain\r\nContent-Range: bytes 8-11/12\r\n\r\nijkl\r\n\
+    ///     --X-BOUNDARY--\r\n";
+    /// let stream = once(async move { Result::<Bytes, Infallible>::Ok(Bytes::from(data)) });
+    /// let mut multipart =
+    ///     Multipart::byteranges(stream, "multipart/byteranges; boundary=X-BOUNDARY").unwrap();
+    ///
+    /// while let Some(field) = multipart.next_field().await.unwrap() {
+    ///     let range = field.content_range().unwrap();
+    ///     println!("Range: {}-{}", range.start(), range.end());
+    ///     println!("Content: {:?}", field.bytes().await);
+    /// }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn byteranges<S, O, E, C>(stream: S, content_type: C) -> crate::Result<Self>
+    where
+        S: Stream<Item = Result<O, E>> + Send + 'r,
+        O: Into<Bytes> + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + 'r,
+        C: AsRef<str>,
+    {
+        Multipart::byteranges_with_constraints(stream, content_type, Constraints::default())
+    }
+
+    /// Construct a new `Multipart` instance to parse a `multipart/byteranges`
+    /// response with the given [`Bytes`] stream, the value of the
+    /// `Content-Type` header and the constraints.
+    ///
+    /// See [`Multipart::byteranges()`] for details.
+    pub fn byteranges_with_constraints<S, O, E, C>(
+        stream: S,
+        content_type: C,
+        constraints: Constraints,
+    ) -> crate::Result<Self>
+    where
+        S: Stream<Item = Result<O, E>> + Send + 'r,
+        O: Into<Bytes> + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + 'r,
+        C: AsRef<str>,
+    {
+        let m = content_type
+            .as_ref()
+            .parse::<mime::Mime>()
+            .map_err(Error::DecodeContentType)?;
+
+        if !(m.type_() == mime::MULTIPART && m.subtype() == "byteranges") {
+            return Err(Error::NoMultipart);
+        }
+
+        let boundary = m.get_param(mime::BOUNDARY).ok_or(Error::NoBoundary)?;
+        Ok(Multipart::with_constraints(stream, boundary.as_str(), constraints))
+    }
+}
+
+impl Field<'_> {
+    /// The byte range of the part found in the `Content-Range` header, as sent
+    /// in `multipart/byteranges` responses.
+    ///
+    /// Returns `None` if the header is missing, is not a `bytes` range, or is
+    /// malformed.
+    pub fn content_range(&self) -> Option<ContentRange> {
+        ContentRange::parse(self.headers())
+    }
+}
@@ -0,0 +1,36 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio_util::sync::{CancellationToken, WaitForCancellationFutureOwned};
+
+/// The token set with
+/// [`Constraints::cancellation_token()`](crate::Constraints::cancellation_token),
+/// aborting the parse once cancelled.
+#[derive(Debug)]
+pub(crate) struct Cancellation {
+    token: CancellationToken,
+    cancelled: Pin<Box<WaitForCancellationFutureOwned>>,
+}
+
+impl Cancellation {
+    pub fn new(token: CancellationToken) -> Cancellation {
+        Cancellation {
+            cancelled: Box::pin(token.clone().cancelled_owned()),
+            token,
+        }
+    }
+
+    /// Fails once the token has been cancelled, otherwise registers the task
+    /// to be woken up when it is.
+    pub fn poll(&mut self, cx: &mut Context<'_>) -> crate::Result<()> {
+        if self.token.is_cancelled() {
+            return Err(crate::Error::Cancelled);
+        }
+
+        match self.cancelled.as_mut().poll(cx) {
+            Poll::Ready(()) => Err(crate::Error::Cancelled),
+            Poll::Pending => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use crate::constraints::{
+    Constraints, DuplicateHeaderPolicy, ExtensionPolicy, FileContentTypePolicy, UnknownFieldPolicy,
+};
+use crate::size_limit::SizeLimit;
+
+/// A deserializable form of [`Constraints`], to load the rules from a
+/// configuration file instead of building them in code.
+///
+/// Every field is optional and named after the [`Constraints`] method it
+/// stands for, with durations given in milliseconds. Unknown fields are
+/// rejected, so that a typo doesn't silently drop a rule. The callbacks of
+/// [`Constraints::skip_malformed_parts()`], [`Constraints::validate()`],
+/// [`Constraints::validate_async()`] and [`Constraints::on_progress()`], the
+/// [`FieldInspector`](crate::FieldInspector) of [`Constraints::inspect()`], as
+/// well as a shared [`MemoryBudget`](crate::MemoryBudget), can't be configured
+/// this way, but may be added to the converted [`Constraints`].
+///
+/// # Optional
+///
+/// This requires the optional `serde` feature to be enabled.
+///
+/// # Examples
+///
+/// ```
+/// use multer::{Constraints, ConstraintsConfig};
+///
+/// let config: ConstraintsConfig = serde_json::from_str(
+///     r#"{
+///         "allowed_fields": ["name", "avatar"],
+///         "file_extensions_for": { "avatar": { "allow": ["png", "jpg"] } },
+///         "size_limit": {
+///             "whole_stream": 15728640,
+///             "for_field": { "name": 1024 }
+///         }
+///     }"#,
+/// )
+/// .unwrap();
+///
+/// let constraints = Constraints::from(config);
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[non_exhaustive]
+pub struct ConstraintsConfig {
+    pub size_limit: SizeLimitConfig,
+    pub allowed_fields: Option<Vec<String>>,
+    pub unknown_fields: UnknownFieldPolicy,
+    pub required_fields: Vec<String>,
+    pub reject_duplicate_fields: bool,
+    pub allow_duplicates_for: Vec<String>,
+    pub file_fields: Vec<String>,
+    pub truncate_fields: Vec<String>,
+    pub allowed_content_types: HashMap<String, Vec<String>>,
+    pub file_content_type: FileContentTypePolicy,
+    pub file_extensions: Option<ExtensionPolicy>,
+    pub file_extensions_for: HashMap<String, ExtensionPolicy>,
+    pub require_form_data: bool,
+    pub reject_unknown_transfer_encodings: bool,
+    pub strict_quoted_printable: bool,
+    pub allow_lf_line_endings: bool,
+    pub allow_folded_headers: bool,
+    pub reject_transport_padding: bool,
+    pub honor_charset_field: bool,
+    pub strict: bool,
+    pub duplicate_headers: DuplicateHeaderPolicy,
+    pub latin1_fallback: bool,
+    pub decode_encoded_words: bool,
+    #[cfg(feature = "unicode-normalization")]
+    pub normalize_nfc: bool,
+    pub positional_names: bool,
+    pub strip_utf8_bom: bool,
+    pub verify_content_length: bool,
+    #[cfg(feature = "content-encoding")]
+    pub decompress_content_encoding: bool,
+    #[cfg(feature = "content-encoding")]
+    pub decompressed_size_limit: Option<u64>,
+    pub max_depth: Option<usize>,
+    pub sniff_content_type: bool,
+    pub reject_empty_files: bool,
+    pub drain_on_violation: bool,
+    pub min_throughput: Option<MinThroughputConfig>,
+    #[cfg(feature = "tokio-time")]
+    pub deadline_ms: Option<u64>,
+    #[cfg(feature = "tokio-time")]
+    pub read_timeout_ms: Option<u64>,
+    #[cfg(feature = "tokio-time")]
+    pub rate_limit: Option<RateLimitConfig>,
+    #[cfg(feature = "tokio-time")]
+    pub rate_limit_for: HashMap<String, RateLimitConfig>,
+}
+
+/// A deserializable form of [`SizeLimit`], part of [`ConstraintsConfig`].
+///
+/// The limits which are not set keep their default value.
+///
+/// # Optional
+///
+/// This requires the optional `serde` feature to be enabled.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+#[non_exhaustive]
+pub struct SizeLimitConfig {
+    pub whole_stream: Option<u64>,
+    pub per_field: Option<u64>,
+    pub per_field_headers: Option<u64>,
+    pub preamble: Option<u64>,
+    pub epilogue: Option<u64>,
+    pub max_fields: Option<usize>,
+    pub max_files: Option<usize>,
+    pub file_name_bytes: Option<usize>,
+    pub file_name_chars: Option<usize>,
+    pub for_field: HashMap<String, u64>,
+    /// `[pattern, limit]` pairs, in order of precedence.
+    pub for_fields_matching: Vec<(String, u64)>,
+}
+
+/// The arguments of [`Constraints::min_throughput()`], part of
+/// [`ConstraintsConfig`].
+///
+/// # Optional
+///
+/// This requires the optional `serde` feature to be enabled.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct MinThroughputConfig {
+    pub min_bytes: u64,
+    pub window_ms: u64,
+}
+
+/// The arguments of [`Constraints::rate_limit()`], part of
+/// [`ConstraintsConfig`].
+///
+/// # Optional
+///
+/// This requires the optional `serde` and `tokio-time` features to be
+/// enabled.
+#[cfg(feature = "tokio-time")]
+#[cfg_attr(nightly, doc(cfg(feature = "tokio-time")))]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct RateLimitConfig {
+    pub bytes_per_sec: u64,
+    pub burst: u64,
+}
+
+impl From<SizeLimitConfig> for SizeLimit {
+    fn from(config: SizeLimitConfig) -> SizeLimit {
+        let mut size_limit = SizeLimit::new();
+
+        if let Some(limit) = config.whole_stream {
+            size_limit = size_limit.whole_stream(limit);
+        }
+        if let Some(limit) = config.per_field {
+            size_limit = size_limit.per_field(limit);
+        }
+        if let Some(limit) = config.per_field_headers {
+            size_limit = size_limit.per_field_headers(limit);
+        }
+        if let Some(limit) = config.preamble {
+            size_limit = size_limit.preamble(limit);
+        }
+        if let Some(limit) = config.epilogue {
+            size_limit = size_limit.epilogue(limit);
+        }
+        if let Some(limit) = config.max_fields {
+            size_limit = size_limit.max_fields(limit);
+        }
+        if let Some(limit) = config.max_files {
+            size_limit = size_limit.max_files(limit);
+        }
+        if let Some(limit) = config.file_name_bytes {
+            size_limit = size_limit.file_name_bytes(limit);
+        }
+        if let Some(limit) = config.file_name_chars {
+            size_limit = size_limit.file_name_chars(limit);
+        }
+        for (field_name, limit) in config.for_field {
+            size_limit = size_limit.for_field(field_name, limit);
+        }
+        for (pattern, limit) in config.for_fields_matching {
+            size_limit = size_limit.for_fields_matching(pattern, limit);
+        }
+
+        size_limit
+    }
+}
+
+impl From<ConstraintsConfig> for Constraints {
+    fn from(config: ConstraintsConfig) -> Constraints {
+        let mut constraints = Constraints::new()
+            .size_limit(config.size_limit.into())
+            .unknown_fields(config.unknown_fields)
+            .required_fields(config.required_fields)
+            .reject_duplicate_fields(config.reject_duplicate_fields)
+            .allow_duplicates_for(config.allow_duplicates_for)
+            .file_fields(config.file_fields)
+            .file_content_type(config.file_content_type)
+            .truncate_fields(config.truncate_fields)
+            .require_form_data(config.require_form_data)
+            .reject_unknown_transfer_encodings(config.reject_unknown_transfer_encodings)
+            .strict_quoted_printable(config.strict_quoted_printable)
+            .allow_lf_line_endings(config.allow_lf_line_endings)
+            .allow_folded_headers(config.allow_folded_headers)
+            .reject_transport_padding(config.reject_transport_padding)
+            .honor_charset_field(config.honor_charset_field)
+            .strict(config.strict)
+            .duplicate_headers(config.duplicate_headers)
+            .latin1_fallback(config.latin1_fallback)
+            .decode_encoded_words(config.decode_encoded_words)
+            .positional_names(config.positional_names)
+            .strip_utf8_bom(config.strip_utf8_bom)
+            .verify_content_length(config.verify_content_length)
+            .sniff_content_type(config.sniff_content_type)
+            .reject_empty_files(config.reject_empty_files)
+            .drain_on_violation(config.drain_on_violation);
+
+        if let Some(allowed_fields) = config.allowed_fields {
+            constraints = constraints.allowed_fields(allowed_fields);
+        }
+        for (field_name, content_types) in config.allowed_content_types {
+            constraints = constraints.allowed_content_types(field_name, content_types);
+        }
+        if let Some(policy) = config.file_extensions {
+            constraints = constraints.file_extensions(policy);
+        }
+        for (field_name, policy) in config.file_extensions_for {
+            constraints = constraints.file_extensions_for(field_name, policy);
+        }
+        if let Some(max_depth) = config.max_depth {
+            constraints = constraints.max_depth(max_depth);
+        }
+        if let Some(MinThroughputConfig { min_bytes, window_ms }) = config.min_throughput {
+            constraints = constraints.min_throughput(min_bytes, Duration::from_millis(window_ms));
+        }
+
+        #[cfg(feature = "unicode-normalization")]
+        {
+            constraints = constraints.normalize_nfc(config.normalize_nfc);
+        }
+
+        #[cfg(feature = "content-encoding")]
+        {
+            constraints = constraints.decompress_content_encoding(config.decompress_content_encoding);
+            if let Some(limit) = config.decompressed_size_limit {
+                constraints = constraints.decompressed_size_limit(limit);
+            }
+        }
+
+        #[cfg(feature = "tokio-time")]
+        {
+            if let Some(timeout) = config.deadline_ms {
+                constraints = constraints.deadline(Duration::from_millis(timeout));
+            }
+            if let Some(timeout) = config.read_timeout_ms {
+                constraints = constraints.read_timeout(Duration::from_millis(timeout));
+            }
+            if let Some(RateLimitConfig { bytes_per_sec, burst }) = config.rate_limit {
+                constraints = constraints.rate_limit(bytes_per_sec, burst);
+            }
+            for (field_name, RateLimitConfig { bytes_per_sec, burst }) in config.rate_limit_for {
+                constraints = constraints.rate_limit_for(field_name, bytes_per_sec, burst);
+            }
+        }
+
+        constraints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constraints_from_config() {
+        let config: ConstraintsConfig = serde_json::from_str(
+            r#"{
+                "allowed_fields": ["a", "files"],
+                "required_fields": ["a"],
+                "duplicate_headers": "reject",
+                "file_extensions": { "deny": ["exe"] },
+                "min_throughput": { "min_bytes": 1024, "window_ms": 5000 },
+                "size_limit": {
+                    "per_field": 100,
+                    "for_field": { "a": 10 },
+                    "for_fields_matching": [["files*", 1000]]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let constraints = Constraints::from(config);
+        assert!(constraints.is_it_allowed(Some("a")));
+        assert!(!constraints.is_it_allowed(Some("b")));
+        assert_eq!(constraints.required_fields, ["a"]);
+        assert_eq!(constraints.duplicate_headers, DuplicateHeaderPolicy::Reject);
+        assert!(!constraints.is_file_name_allowed(Some("files"), "a.exe"));
+        assert_eq!(constraints.min_throughput, Some((1024, Duration::from_secs(5))));
+        assert_eq!(constraints.size_limit.extract_size_limit_for(Some("a")), 10);
+        assert_eq!(constraints.size_limit.extract_size_limit_for(Some("files[0]")), 1000);
+        assert_eq!(constraints.size_limit.extract_size_limit_for(Some("b")), 100);
+    }
+
+    #[test]
+    fn test_config_rejects_unknown_fields() {
+        assert!(serde_json::from_str::<ConstraintsConfig>(r#"{ "allowed_field": ["a"] }"#).is_err());
+        assert!(serde_json::from_str::<ConstraintsConfig>(r#"{ "size_limit": { "per_fields": 1 } }"#).is_err());
+    }
+}
@@ -1,15 +1,28 @@
 use std::borrow::Cow;
 
-pub(crate) const DEFAULT_WHOLE_STREAM_SIZE_LIMIT: u64 = std::u64::MAX;
-pub(crate) const DEFAULT_PER_FIELD_SIZE_LIMIT: u64 = std::u64::MAX;
+use base64::Engine;
+use encoding_rs::{Encoding, UTF_8};
+
+use crate::transfer_encoding::BASE64;
+
+pub(crate) const DEFAULT_WHOLE_STREAM_SIZE_LIMIT: u64 = u64::MAX;
+pub(crate) const DEFAULT_PER_FIELD_SIZE_LIMIT: u64 = u64::MAX;
+pub(crate) const DEFAULT_PER_FIELD_HEADERS_SIZE_LIMIT: u64 = u64::MAX;
+pub(crate) const DEFAULT_MAX_FIELDS: usize = usize::MAX;
+pub(crate) const DEFAULT_MAX_FILES: usize = usize::MAX;
+pub(crate) const DEFAULT_FILE_NAME_LENGTH_LIMIT: usize = usize::MAX;
+pub(crate) const DEFAULT_PREAMBLE_SIZE_LIMIT: u64 = u64::MAX;
+pub(crate) const DEFAULT_EPILOGUE_SIZE_LIMIT: u64 = u64::MAX;
 
 pub(crate) const MAX_HEADERS: usize = 32;
+/// The maximum size of the data of a field read with `Field::parse()`.
+pub(crate) const MAX_PARSED_FIELD_SIZE: usize = 1024;
 pub(crate) const BOUNDARY_EXT: &str = "--";
 pub(crate) const CR: &str = "\r";
-#[allow(dead_code)]
 pub(crate) const LF: &str = "\n";
 pub(crate) const CRLF: &str = "\r\n";
 pub(crate) const CRLF_CRLF: &str = "\r\n\r\n";
+pub(crate) const UTF8_BOM: &[u8] = b"\xef\xbb\xbf";
 
 #[derive(PartialEq)]
 pub(crate) enum ContentDispositionAttr {
@@ -24,6 +37,13 @@ fn trim_ascii_ws_start(bytes: &[u8]) -> &[u8] {
         .map_or_else(|| &bytes[bytes.len()..], |i| &bytes[i..])
 }
 
+fn trim_ascii_ws_end(bytes: &[u8]) -> &[u8] {
+    bytes
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or_else(|| &bytes[..0], |i| &bytes[..=i])
+}
+
 fn trim_ascii_ws_then(bytes: &[u8], char: u8) -> Option<&[u8]> {
     match trim_ascii_ws_start(bytes) {
         [first, rest @ ..] if *first == char => Some(rest),
@@ -31,50 +51,471 @@ fn trim_ascii_ws_then(bytes: &[u8], char: u8) -> Option<&[u8]> {
     }
 }
 
-impl ContentDispositionAttr {
-    /// Extract ContentDisposition Attribute from header.
-    ///
-    /// Some older clients may not quote the name or filename, so we allow them,
-    /// but require them to be percent encoded. Only allocates if percent
-    /// decoding, and there are characters that need to be decoded.
-    pub fn extract_from<'h>(&self, mut header: &'h [u8]) -> Option<Cow<'h, str>> {
-        // TODO: The prefix should be matched case-insensitively.
-        let prefix = match self {
-            ContentDispositionAttr::Name => &b"name"[..],
-            ContentDispositionAttr::FileName => &b"filename"[..],
+fn strip_prefix_ignore_ascii_case<'a>(bytes: &'a [u8], prefix: &[u8]) -> Option<&'a [u8]> {
+    match bytes.get(..prefix.len()) {
+        Some(head) if head.eq_ignore_ascii_case(prefix) => Some(&bytes[prefix.len()..]),
+        _ => None,
+    }
+}
+
+/// A single `name=value` parameter of a header value.
+struct Param<'h> {
+    name: &'h [u8],
+    value: &'h [u8],
+    is_escaped: bool,
+}
+
+impl<'h> Param<'h> {
+    /// The value with quoted-pair escapes removed, i.e. with any `\X`
+    /// replaced by `X`.
+    fn unescaped(&self) -> Cow<'h, [u8]> {
+        match self.is_escaped {
+            true => {
+                let mut value = Vec::with_capacity(self.value.len());
+                let mut bytes = self.value.iter();
+                while let Some(b) = bytes.next() {
+                    match b {
+                        b'\\' => value.extend(bytes.next()),
+                        b => value.push(*b),
+                    }
+                }
+
+                value.into()
+            }
+            false => self.value.into(),
+        }
+    }
+}
+
+/// Iterates over the `;` separated parameters of a header value, skipping
+/// segments that are not parameters, such as the disposition type.
+struct Params<'h> {
+    rest: &'h [u8],
+}
+
+impl<'h> Params<'h> {
+    fn new(header: &'h [u8]) -> Self {
+        Params { rest: header }
+    }
+
+    fn skip_past_semicolon(&mut self) {
+        self.rest = match memchr::memchr(b';', self.rest) {
+            Some(i) => &self.rest[(i + 1)..],
+            None => &self.rest[self.rest.len()..],
         };
+    }
+}
+
+impl<'h> Iterator for Params<'h> {
+    type Item = Param<'h>;
 
-        while let Some(i) = memchr::memmem::find(header, prefix) {
-            // Check if we found a superstring of `prefix`; continue if so.
-            let suffix = &header[(i + prefix.len())..];
-            if i > 0 && !(header[i - 1].is_ascii_whitespace() || header[i - 1] == b';') {
-                header = suffix;
-                continue;
+    fn next(&mut self) -> Option<Param<'h>> {
+        loop {
+            let rest = trim_ascii_ws_start(self.rest);
+            if rest.is_empty() {
+                return None;
             }
 
-            // Now find and trim the `=`. Handle quoted strings first.
-            let rest = trim_ascii_ws_then(suffix, b'=')?;
-            let (bytes, is_escaped) = if let Some(rest) = trim_ascii_ws_then(rest, b'"') {
-                let (mut k, mut escaped) = (memchr::memchr(b'"', rest)?, false);
-                while k > 0 && rest[k - 1] == b'\\' {
+            let i = match memchr::memchr2(b'=', b';', rest) {
+                Some(i) if rest[i] == b'=' => i,
+                Some(i) => {
+                    self.rest = &rest[(i + 1)..];
+                    continue;
+                }
+                None => {
+                    self.rest = &rest[rest.len()..];
+                    return None;
+                }
+            };
+
+            let name = trim_ascii_ws_end(&rest[..i]);
+            let rest = &rest[(i + 1)..];
+
+            // Handle quoted strings first.
+            let (value, is_escaped) = if let Some(rest) = trim_ascii_ws_then(rest, b'"') {
+                // The closing quote is the first one not part of a quoted-pair.
+                let mut k = memchr::memchr2(b'"', b'\\', rest)?;
+                let mut escaped = false;
+                while rest[k] == b'\\' {
                     escaped = true;
-                    k = k + 1 + memchr::memchr(b'"', &rest[(k + 1)..])?;
+                    k = k + 2 + memchr::memchr2(b'"', b'\\', rest.get((k + 2)..)?)?;
                 }
 
+                self.rest = &rest[(k + 1)..];
                 (&rest[..k], escaped)
             } else {
                 let rest = trim_ascii_ws_start(rest);
                 let j = memchr::memchr2(b';', b' ', rest).unwrap_or(rest.len());
+                self.rest = &rest[j..];
                 (&rest[..j], false)
             };
 
-            return match std::str::from_utf8(bytes).ok()? {
-                name if is_escaped => Some(name.replace(r#"\""#, "\"").into()),
-                name => Some(name.into()),
-            };
+            self.skip_past_semicolon();
+            return Some(Param {
+                name,
+                value,
+                is_escaped,
+            });
+        }
+    }
+}
+
+/// Decodes `%XX` escapes, leaving malformed escapes as-is.
+pub(crate) fn percent_decode(bytes: &[u8]) -> Vec<u8> {
+    fn hex(b: u8) -> Option<u8> {
+        (b as char).to_digit(16).map(|d| d as u8)
+    }
+
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                (Some(hi), Some(lo)) => {
+                    decoded.push(hi << 4 | lo);
+                    i += 3;
+                    continue;
+                }
+                _ => decoded.push(b'%'),
+            },
+            b => decoded.push(b),
+        }
+
+        i += 1;
+    }
+
+    decoded
+}
+
+/// Encodes an RFC 5987 extended value `utf-8''...`, percent-encoding every
+/// byte which isn't an `attr-char`.
+pub(crate) fn encode_ext_value(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len() + 7);
+    encoded.push_str("utf-8''");
+
+    for b in value.bytes() {
+        match b {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => encoded.push(b as char),
+            b'!' | b'#' | b'$' | b'&' | b'+' | b'-' | b'.' | b'^' | b'_' | b'`' | b'|' | b'~' => {
+                encoded.push(b as char)
+            }
+            b => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+
+    encoded
+}
+
+/// Splits an RFC 2231 extended value `charset'language'value` into its charset
+/// and still percent-encoded value.
+fn split_ext_value(value: &[u8]) -> Option<(&[u8], &[u8])> {
+    let i = memchr::memchr(b'\'', value)?;
+    let j = i + 1 + memchr::memchr(b'\'', &value[(i + 1)..])?;
+    Some((&value[..i], &value[(j + 1)..]))
+}
+
+/// Decodes the bytes of an extended value in the declared charset, defaulting
+/// to UTF-8. Unknown charsets and malformed sequences yield `None`.
+fn decode_charset(charset: &[u8], bytes: Vec<u8>) -> Option<String> {
+    let encoding = match charset {
+        b"" => UTF_8,
+        charset => Encoding::for_label(charset)?,
+    };
+
+    if encoding == UTF_8 {
+        return String::from_utf8(bytes).ok();
+    }
+
+    encoding
+        .decode_without_bom_handling_and_without_replacement(&bytes)
+        .map(Cow::into_owned)
+}
+
+/// Decodes the RFC 2047 encoded-words in `value`, e.g.
+/// `=?UTF-8?B?5L2g5aW9LnR4dA==?=`, dropping the whitespace between adjacent
+/// ones. Malformed encoded-words are left as-is. Returns `None` if there are
+/// no encoded-words to decode.
+pub(crate) fn decode_encoded_words(value: &str) -> Option<String> {
+    let mut decoded = String::with_capacity(value.len());
+    let mut rest = value;
+    let mut after_word = false;
+    let mut any = false;
+
+    while let Some(start) = rest.find("=?") {
+        let (before, word) = rest.split_at(start);
+        match decode_encoded_word(word) {
+            Some((text, len)) => {
+                if !(after_word && before.chars().all(|ch| ch == ' ' || ch == '\t')) {
+                    decoded.push_str(before);
+                }
+                decoded.push_str(&text);
+                rest = &word[len..];
+                after_word = true;
+                any = true;
+            }
+            None => {
+                decoded.push_str(before);
+                decoded.push_str("=?");
+                rest = &word[2..];
+                after_word = false;
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    any.then_some(decoded)
+}
+
+/// Decodes the encoded-word `=?charset?encoding?text?=` at the start of `word`
+/// and returns it along with its length.
+fn decode_encoded_word(word: &str) -> Option<(String, usize)> {
+    let mut parts = word.get(2..)?.splitn(4, '?');
+    let charset = parts.next()?;
+    let encoding = parts.next()?;
+    let text = parts.next()?;
+    if !parts.next()?.starts_with('=') || text.contains(|ch: char| ch.is_ascii_whitespace()) {
+        return None;
+    }
+
+    let len = ["=?", charset, "?", encoding, "?", text, "?="]
+        .iter()
+        .map(|part| part.len())
+        .sum();
+
+    let bytes = match encoding {
+        "B" | "b" => BASE64.decode(text).ok()?,
+        "Q" | "q" => decode_q(text.as_bytes())?,
+        _ => return None,
+    };
+
+    // Drop the RFC 2231 language suffix, e.g. `UTF-8*en`.
+    let charset = charset.split('*').next().unwrap_or(charset);
+    Some((decode_charset(charset.as_bytes(), bytes)?, len))
+}
+
+/// Decodes the "Q" encoding of RFC 2047, where `_` stands for a space and
+/// `=XX` for an arbitrary byte.
+fn decode_q(text: &[u8]) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(text.len());
+    let mut i = 0;
+    while i < text.len() {
+        match text[i] {
+            b'_' => decoded.push(b' '),
+            b'=' => {
+                let hex = std::str::from_utf8(text.get((i + 1)..(i + 3))?).ok()?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                i += 2;
+            }
+            b => decoded.push(b),
+        }
+
+        i += 1;
+    }
+
+    Some(decoded)
+}
+
+/// Reassembles RFC 2231 continuation segments (`filename*0*`, `filename*1`,
+/// ...) into the full value and its declared charset, if any. The segments
+/// must be numbered sequentially from zero; only the first one may declare a
+/// charset.
+fn join_continuations<'h>(segments: &mut [(u32, bool, Param<'h>)]) -> Option<(&'h [u8], Vec<u8>)> {
+    segments.sort_by_key(|(idx, ..)| *idx);
+    if !matches!(segments.first(), Some((0, ..))) {
+        return None;
+    }
+
+    let mut charset = &b""[..];
+    let mut bytes = Vec::new();
+    for (expected, (idx, is_encoded, param)) in segments.iter().enumerate() {
+        if *idx as usize != expected {
+            break;
+        }
+
+        match is_encoded {
+            true if expected == 0 => {
+                let (cs, value) = split_ext_value(param.value)?;
+                charset = cs;
+                bytes.extend(percent_decode(value));
+            }
+            true => bytes.extend(percent_decode(param.value)),
+            false => bytes.extend_from_slice(&param.unescaped()),
+        }
+    }
+
+    Some((charset, bytes))
+}
+
+/// The occurrences of a parameter in a header value: the first plain and
+/// extended ones, and the continuation segments.
+struct ParamParts<'h> {
+    plain: Option<Param<'h>>,
+    extended: Option<Param<'h>>,
+    segments: Vec<(u32, bool, Param<'h>)>,
+}
+
+impl<'h> ParamParts<'h> {
+    fn collect(header: &'h [u8], name: &[u8]) -> Self {
+        let mut parts = ParamParts {
+            plain: None,
+            extended: None,
+            segments: Vec::new(),
+        };
+
+        for param in Params::new(header) {
+            match strip_prefix_ignore_ascii_case(param.name, name) {
+                Some(b"") if parts.plain.is_none() => parts.plain = Some(param),
+                Some(b"*") if parts.extended.is_none() => parts.extended = Some(param),
+                Some([b'*', section @ ..]) => {
+                    let (digits, is_encoded) = match section.strip_suffix(b"*") {
+                        Some(digits) => (digits, true),
+                        None => (section, false),
+                    };
+
+                    let idx = std::str::from_utf8(digits).ok().and_then(|d| d.parse::<u32>().ok());
+                    if let Some(idx) = idx {
+                        parts.segments.push((idx, is_encoded, param));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        parts
+    }
+}
+
+/// Extracts the parameter `name` from a header value.
+///
+/// Some older clients may not quote the value, so we allow them. RFC 2231
+/// extended values (`name*=utf-8''...`) and continuations (`name*0*=...;
+/// name*1*=...`) take precedence over the plain parameter. Only allocates if
+/// unescaping or decoding the value.
+fn extract_param<'h>(header: &'h [u8], name: &[u8]) -> Option<Cow<'h, str>> {
+    let ParamParts {
+        plain,
+        extended,
+        mut segments,
+    } = ParamParts::collect(header, name);
+
+    let extended = extended.and_then(|param| {
+        let (charset, value) = split_ext_value(param.value)?;
+        decode_charset(charset, percent_decode(value))
+    });
+
+    let continued = || {
+        let (charset, bytes) = join_continuations(&mut segments)?;
+        decode_charset(charset, bytes)
+    };
+
+    if let Some(value) = extended.or_else(continued) {
+        return Some(value.into());
+    }
+
+    let param = plain?;
+    match param.unescaped() {
+        Cow::Borrowed(bytes) => std::str::from_utf8(bytes).ok().map(Cow::Borrowed),
+        Cow::Owned(bytes) => String::from_utf8(bytes).ok().map(Cow::Owned),
+    }
+}
+
+/// Extracts the raw bytes of the parameter `name` from a header value, with
+/// the same precedence as [`extract_param()`]. The value is unescaped, and
+/// percent-decoded if extended, but not decoded from its charset.
+fn extract_param_bytes(header: &[u8], name: &[u8]) -> Option<Vec<u8>> {
+    let ParamParts {
+        plain,
+        extended,
+        mut segments,
+    } = ParamParts::collect(header, name);
+
+    let extended = extended.and_then(|param| Some(percent_decode(split_ext_value(param.value)?.1)));
+
+    extended
+        .or_else(|| join_continuations(&mut segments).map(|(_, bytes)| bytes))
+        .or_else(|| plain.map(|param| param.unescaped().into_owned()))
+}
+
+/// Extracts all parameters from a header value, with lowercased names and
+/// their values decoded as by [`extract_param()`], in order of appearance.
+pub(crate) fn extract_all_params(header: &[u8]) -> Vec<(String, String)> {
+    let mut params: Vec<(String, String)> = Vec::new();
+    for param in Params::new(header) {
+        let end = memchr::memchr(b'*', param.name).unwrap_or(param.name.len());
+        let name = match std::str::from_utf8(&param.name[..end]) {
+            Ok(name) if !name.is_empty() => name.to_ascii_lowercase(),
+            _ => continue,
+        };
+
+        if params.iter().any(|(n, _)| *n == name) {
+            continue;
+        }
+
+        if let Some(value) = extract_param(header, name.as_bytes()) {
+            params.push((name, value.into_owned()));
         }
+    }
+
+    params
+}
+
+impl ContentDispositionAttr {
+    /// Extract ContentDisposition Attribute from header, see
+    /// [`extract_param()`].
+    pub fn extract_from<'h>(&self, header: &'h [u8]) -> Option<Cow<'h, str>> {
+        let name = match self {
+            ContentDispositionAttr::Name => &b"name"[..],
+            ContentDispositionAttr::FileName => &b"filename"[..],
+        };
+
+        extract_param(header, name)
+    }
+
+    /// Extract the raw bytes of the ContentDisposition Attribute from header,
+    /// see [`extract_param_bytes()`].
+    pub fn extract_bytes_from(&self, header: &[u8]) -> Option<Vec<u8>> {
+        let name = match self {
+            ContentDispositionAttr::Name => &b"name"[..],
+            ContentDispositionAttr::FileName => &b"filename"[..],
+        };
+
+        extract_param_bytes(header, name)
+    }
 
-        None
+    /// Extract the plain ContentDisposition Attribute from header, decoding it
+    /// as ISO-8859-1, as sent by some older browsers.
+    pub fn extract_latin1_from(&self, header: &[u8]) -> Option<String> {
+        let name = match self {
+            ContentDispositionAttr::Name => &b"name"[..],
+            ContentDispositionAttr::FileName => &b"filename"[..],
+        };
+
+        let param = Params::new(header).find(|param| param.name.eq_ignore_ascii_case(name))?;
+        Some(param.unescaped().iter().map(|b| *b as char).collect())
+    }
+
+    /// Extract the plain ContentDisposition Attribute from header as it
+    /// appears in it, i.e. without unescaping quoted-pairs.
+    pub fn extract_literal_from<'h>(&self, header: &'h [u8]) -> Option<&'h [u8]> {
+        let name = match self {
+            ContentDispositionAttr::Name => &b"name"[..],
+            ContentDispositionAttr::FileName => &b"filename"[..],
+        };
+
+        Params::new(header)
+            .find(|param| param.name.eq_ignore_ascii_case(name))
+            .map(|param| param.value)
+    }
+
+    /// Whether the attribute is given as an RFC 2231 extended value or with
+    /// continuations, e.g. `filename*=...` or `filename*0=...`.
+    pub fn is_extended_in(&self, header: &[u8]) -> bool {
+        let prefix = match self {
+            ContentDispositionAttr::Name => &b"name*"[..],
+            ContentDispositionAttr::FileName => &b"filename*"[..],
+        };
+
+        Params::new(header).any(|param| strip_prefix_ignore_ascii_case(param.name, prefix).is_some())
     }
 }
 
@@ -82,6 +523,28 @@ impl ContentDispositionAttr {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_decode_encoded_words() {
+        assert_eq!(
+            decode_encoded_words("=?UTF-8?B?5L2g5aW9LnR4dA==?=").as_deref(),
+            Some("你好.txt")
+        );
+        assert_eq!(
+            decode_encoded_words("=?iso-8859-1?q?caf=E9_cr=E8me?= .txt").as_deref(),
+            Some("café crème .txt")
+        );
+        assert_eq!(
+            decode_encoded_words("=?UTF-8?Q?a?= \t=?UTF-8*en?Q?b?=.txt").as_deref(),
+            Some("ab.txt")
+        );
+        assert_eq!(
+            decode_encoded_words("x =?UTF-8?B?invalid base64?= =?utf-8?b?w6k=?=").as_deref(),
+            Some("x =?UTF-8?B?invalid base64?= é")
+        );
+        assert_eq!(decode_encoded_words("plain.txt"), None);
+        assert_eq!(decode_encoded_words("=?x-unknown?Q?a?="), None);
+    }
+
     #[test]
     fn test_content_disposition_name_only() {
         let val = br#"form-data; name="my_field""#;
@@ -220,4 +683,135 @@ mod tests {
         let name = ContentDispositionAttr::Name.extract_from(val);
         assert_eq!(name.unwrap(), r#"myfield"name"#);
     }
+
+    #[test]
+    fn test_content_disposition_escaped_backslash() {
+        let val = br#"form-data; name="file"; filename="C:\\Users\\a\;b.txt""#;
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert_eq!(filename.unwrap(), r"C:\Users\a;b.txt");
+
+        let val = br#"form-data; name="dir\\"; filename="a.txt""#;
+        let name = ContentDispositionAttr::Name.extract_from(val);
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert_eq!(name.unwrap(), r"dir\");
+        assert_eq!(filename.unwrap(), "a.txt");
+
+        let val = br#"form-data; name="a\\\"b""#;
+        let name = ContentDispositionAttr::Name.extract_from(val);
+        assert_eq!(name.unwrap(), r#"a\"b"#);
+    }
+
+    #[test]
+    fn test_content_disposition_latin1() {
+        let val = b"form-data; name=\"file\"; filename=\"caf\xe9.txt\"";
+        assert!(ContentDispositionAttr::FileName.extract_from(val).is_none());
+
+        let filename = ContentDispositionAttr::FileName.extract_latin1_from(val);
+        assert_eq!(filename.unwrap(), "café.txt");
+
+        let filename = ContentDispositionAttr::FileName.extract_bytes_from(val);
+        assert_eq!(filename.unwrap(), b"caf\xe9.txt");
+    }
+
+    #[test]
+    fn test_content_disposition_bytes() {
+        let val = br#"form-data; name="a\"b"; filename*=iso-8859-1''caf%E9.txt"#;
+        let name = ContentDispositionAttr::Name.extract_bytes_from(val);
+        let filename = ContentDispositionAttr::FileName.extract_bytes_from(val);
+        assert_eq!(name.unwrap(), br#"a"b"#);
+        assert_eq!(filename.unwrap(), b"caf\xe9.txt");
+
+        let val = br#"form-data; name="file"; filename*0="a"; filename*1*=%FF"#;
+        let filename = ContentDispositionAttr::FileName.extract_bytes_from(val);
+        assert_eq!(filename.unwrap(), b"a\xff");
+        assert!(ContentDispositionAttr::FileName.extract_from(val).is_none());
+    }
+
+    #[test]
+    fn test_content_disposition_extended_value() {
+        let val = br#"form-data; name="file"; filename*=utf-8''%E4%BD%A0%E5%A5%BD.txt"#;
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert_eq!(filename.unwrap(), "你好.txt");
+
+        let val = br#"form-data; name="file"; filename="fallback.txt"; filename*=UTF-8'en'a%20b.txt"#;
+        let name = ContentDispositionAttr::Name.extract_from(val);
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert_eq!(name.unwrap(), "file");
+        assert_eq!(filename.unwrap(), "a b.txt");
+
+        let val = br#"form-data; NAME="file"; FileName="a.txt""#;
+        let name = ContentDispositionAttr::Name.extract_from(val);
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert_eq!(name.unwrap(), "file");
+        assert_eq!(filename.unwrap(), "a.txt");
+    }
+
+    #[test]
+    fn test_content_disposition_continuations() {
+        let val = br#"form-data; name="file"; filename*0*=utf-8''%E4%BD%A0; filename*1*=%E5%A5%BD; filename*2=".txt""#;
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert_eq!(filename.unwrap(), "你好.txt");
+
+        let val = br#"form-data; name="file"; filename*1="-name.txt"; filename*0="a-very-long""#;
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert_eq!(filename.unwrap(), "a-very-long-name.txt");
+
+        let val = br#"form-data; name="file"; filename*0="a"; filename*2="c""#;
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert_eq!(filename.unwrap(), "a");
+
+        let val = br#"form-data; name="file"; filename*1="b""#;
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert!(filename.is_none());
+    }
+
+    #[test]
+    fn test_content_disposition_extended_value_charset() {
+        let val = br#"form-data; name*=iso-8859-1'de'Gr%FC%DFe; filename*=windows-1252''%80uro.txt"#;
+        let name = ContentDispositionAttr::Name.extract_from(val);
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert_eq!(name.unwrap(), "Grüße");
+        assert_eq!(filename.unwrap(), "€uro.txt");
+
+        let val = br#"form-data; filename*0*=iso-8859-1''caf%E9; filename*1=".txt""#;
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert_eq!(filename.unwrap(), "café.txt");
+
+        let val = br#"form-data; filename="fallback.txt"; filename*=x-unknown''abc"#;
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert_eq!(filename.unwrap(), "fallback.txt");
+
+        let val = br#"form-data; filename="fallback.txt"; filename*=utf-8''%FF.txt"#;
+        let filename = ContentDispositionAttr::FileName.extract_from(val);
+        assert_eq!(filename.unwrap(), "fallback.txt");
+    }
+
+    #[test]
+    fn test_encode_ext_value() {
+        assert_eq!(encode_ext_value("a b~%.txt"), "utf-8''a%20b~%25.txt");
+        assert_eq!(encode_ext_value("你好.txt"), "utf-8''%E4%BD%A0%E5%A5%BD.txt");
+
+        let val = format!("form-data; filename*={}", encode_ext_value("crème \"brûlée\"; 😀.txt"));
+        let filename = ContentDispositionAttr::FileName.extract_from(val.as_bytes());
+        assert_eq!(filename.unwrap(), "crème \"brûlée\"; 😀.txt");
+    }
+
+    #[test]
+    fn test_content_disposition_all_params() {
+        let val = br#"form-data; name="my_field"; FILENAME*=utf-8''a%20b.txt; filename="a.txt"; size=1024; creation-date="Wed, 12 Feb 1997 16:29:51 -0500"; title*0="a "; title*1="b""#;
+        let params = extract_all_params(val);
+        let params: Vec<_> = params.iter().map(|(n, v)| (n.as_str(), v.as_str())).collect();
+        assert_eq!(
+            params,
+            [
+                ("name", "my_field"),
+                ("filename", "a b.txt"),
+                ("size", "1024"),
+                ("creation-date", "Wed, 12 Feb 1997 16:29:51 -0500"),
+                ("title", "a b"),
+            ]
+        );
+
+        assert!(extract_all_params(b"form-data").is_empty());
+    }
 }
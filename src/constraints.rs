@@ -1,3 +1,17 @@
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::future::{BoxFuture, Future, FutureExt};
+use http::header::HeaderMap;
+#[cfg(feature = "cancellation")]
+use tokio_util::sync::CancellationToken;
+
+use crate::budget::MemoryBudget;
+use crate::error::Error;
+use crate::helpers;
+use crate::inspect::FieldInspector;
 use crate::size_limit::SizeLimit;
 
 /// Represents some rules to be applied on the stream and field's content size
@@ -45,10 +59,61 @@ use crate::size_limit::SizeLimit;
 /// # }
 /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
 /// ```
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct Constraints {
     pub(crate) size_limit: SizeLimit,
     pub(crate) allowed_fields: Option<Vec<String>>,
+    pub(crate) unknown_fields: UnknownFieldPolicy,
+    pub(crate) required_fields: Vec<String>,
+    pub(crate) reject_duplicate_fields: bool,
+    pub(crate) allowed_duplicate_fields: Vec<String>,
+    pub(crate) file_fields: Vec<String>,
+    pub(crate) truncate_fields: Vec<String>,
+    pub(crate) allowed_content_types: HashMap<String, Vec<String>>,
+    pub(crate) file_content_type: FileContentTypePolicy,
+    pub(crate) file_extensions: Option<ExtensionPolicy>,
+    pub(crate) file_extensions_map: HashMap<String, ExtensionPolicy>,
+    pub(crate) require_form_data: bool,
+    pub(crate) reject_unknown_transfer_encodings: bool,
+    pub(crate) strict_quoted_printable: bool,
+    pub(crate) allow_lf_line_endings: bool,
+    pub(crate) allow_folded_headers: bool,
+    pub(crate) reject_transport_padding: bool,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) min_throughput: Option<(u64, Duration)>,
+    pub(crate) memory_budget: Option<MemoryBudget>,
+    pub(crate) sniff_content_type: bool,
+    pub(crate) reject_empty_files: bool,
+    pub(crate) drain_on_violation: bool,
+    #[cfg(feature = "tokio-time")]
+    pub(crate) deadline: Option<Duration>,
+    #[cfg(feature = "tokio-time")]
+    pub(crate) read_timeout: Option<Duration>,
+    #[cfg(feature = "tokio-time")]
+    pub(crate) rate_limit: Option<(u64, u64)>,
+    #[cfg(feature = "tokio-time")]
+    pub(crate) rate_limit_map: HashMap<String, (u64, u64)>,
+    #[cfg(feature = "cancellation")]
+    pub(crate) cancellation: Option<CancellationToken>,
+    pub(crate) skip_malformed_parts: Option<MalformedPartHandler>,
+    pub(crate) validators: Vec<Validator>,
+    pub(crate) on_progress: Option<ProgressHandler>,
+    pub(crate) inspector: Option<Inspector>,
+    pub(crate) honor_charset_field: bool,
+    pub(crate) strict: bool,
+    pub(crate) duplicate_headers: DuplicateHeaderPolicy,
+    pub(crate) latin1_fallback: bool,
+    pub(crate) decode_encoded_words: bool,
+    #[cfg(feature = "unicode-normalization")]
+    pub(crate) normalize_nfc: bool,
+    pub(crate) positional_names: bool,
+    pub(crate) strip_utf8_bom: bool,
+    pub(crate) verify_content_length: bool,
+    pub(crate) capture_raw: bool,
+    #[cfg(feature = "content-encoding")]
+    pub(crate) decompress_content_encoding: bool,
+    #[cfg(feature = "content-encoding")]
+    pub(crate) decompressed_size_limit: Option<u64>,
 }
 
 impl Constraints {
@@ -58,24 +123,762 @@ impl Constraints {
     }
 
     /// Applies rules on field's content length.
-    pub fn size_limit(self, size_limit: SizeLimit) -> Constraints {
-        Constraints {
-            size_limit,
-            allowed_fields: self.allowed_fields,
-        }
+    pub fn size_limit(mut self, size_limit: SizeLimit) -> Constraints {
+        self.size_limit = size_limit;
+        self
     }
 
     /// Specify which fields should be allowed, for any unknown field, the
-    /// [`next_field`](crate::Multipart::next_field) will throw an error.
-    pub fn allowed_fields<N: Into<String>>(self, allowed_fields: Vec<N>) -> Constraints {
+    /// [`next_field`](crate::Multipart::next_field) will throw an error,
+    /// unless set otherwise with [`unknown_fields`](Self::unknown_fields).
+    pub fn allowed_fields<N: Into<String>>(mut self, allowed_fields: Vec<N>) -> Constraints {
         let allowed_fields = allowed_fields.into_iter().map(|item| item.into()).collect();
+        self.allowed_fields = Some(allowed_fields);
+        self
+    }
+
+    /// Sets how a field not listed with
+    /// [`allowed_fields`](Self::allowed_fields) is handled, see
+    /// [`UnknownFieldPolicy`].
+    pub fn unknown_fields(mut self, policy: UnknownFieldPolicy) -> Constraints {
+        self.unknown_fields = policy;
+        self
+    }
+
+    /// Specify which fields must be present. If any of them is missing by the
+    /// end of the stream, [`next_field`](crate::Multipart::next_field) fails
+    /// with [`Error::MissingRequiredFields`](crate::Error::MissingRequiredFields)
+    /// listing them instead of returning `None`.
+    pub fn required_fields<N: Into<String>>(mut self, required_fields: Vec<N>) -> Constraints {
+        self.required_fields = required_fields.into_iter().map(|item| item.into()).collect();
+        self
+    }
+
+    /// Reject a field whose name has already been used by a previous field
+    /// with [`Error::DuplicateField`](crate::Error::DuplicateField), unless
+    /// allowed with [`allow_duplicates_for`](Self::allow_duplicates_for).
+    ///
+    /// Applications often keep either the first or the last of the repeated
+    /// values, which opens the door to parameter pollution when the same form
+    /// is validated and consumed by different layers.
+    pub fn reject_duplicate_fields(mut self, reject: bool) -> Constraints {
+        self.reject_duplicate_fields = reject;
+        self
+    }
+
+    /// Specify which fields may be repeated, e.g. the field of a multi-file
+    /// upload, while [`reject_duplicate_fields`](Self::reject_duplicate_fields)
+    /// is set.
+    pub fn allow_duplicates_for<N: Into<String>>(mut self, field_names: Vec<N>) -> Constraints {
+        self.allowed_duplicate_fields = field_names.into_iter().map(|item| item.into()).collect();
+        self
+    }
+
+    /// Specify which fields are cut at their size limit instead of failing
+    /// with [`Error::FieldSizeExceeded`](crate::Error::FieldSizeExceeded),
+    /// e.g. log or clipboard contents for which partial data are acceptable.
+    ///
+    /// The data beyond the limit are discarded and the field is flagged with
+    /// [`Field::is_truncated()`](crate::Field::is_truncated). The limit applies
+    /// to the data as received, before any transfer decoding.
+    pub fn truncate_fields<N: Into<String>>(mut self, field_names: Vec<N>) -> Constraints {
+        self.truncate_fields = field_names.into_iter().map(|item| item.into()).collect();
+        self
+    }
+
+    /// Specify which fields must be file uploads, i.e. carry a `filename`
+    /// parameter in their `Content-Disposition` header and a `Content-Type`.
+    ///
+    /// Such a field sent as a plain text part fails with
+    /// [`Error::NotAFile`](crate::Error::NotAFile) before any of its data is
+    /// read.
+    pub fn file_fields<N: Into<String>>(mut self, field_names: Vec<N>) -> Constraints {
+        self.file_fields = field_names.into_iter().map(|item| item.into()).collect();
+        self
+    }
+
+    /// Specify which content types are allowed for a specific field, e.g.
+    /// `image/png` and `image/jpeg`, or all subtypes of a type with `image/*`.
+    ///
+    /// A field with any other `Content-Type`, or none, fails with
+    /// [`Error::ContentTypeNotAllowed`](crate::Error::ContentTypeNotAllowed)
+    /// before any of its data is read. Parameters such as `charset` are
+    /// ignored.
+    pub fn allowed_content_types<N: Into<String>, T: Into<String>>(
+        mut self,
+        field_name: N,
+        content_types: Vec<T>,
+    ) -> Constraints {
+        let content_types = content_types
+            .into_iter()
+            .map(|item| item.into().trim().to_ascii_lowercase())
+            .collect();
+        self.allowed_content_types.insert(field_name.into(), content_types);
+        self
+    }
+
+    /// Sets whether fields carrying a file name must declare a
+    /// `Content-Type`, see [`FileContentTypePolicy`].
+    ///
+    /// A field without one fails with
+    /// [`Error::MissingFileContentType`](crate::Error::MissingFileContentType)
+    /// before any of its data is read.
+    pub fn file_content_type(mut self, policy: FileContentTypePolicy) -> Constraints {
+        self.file_content_type = policy;
+        self
+    }
+
+    /// Sets which file name extensions are accepted for all fields carrying a
+    /// file name, see [`ExtensionPolicy`].
+    ///
+    /// A field with a rejected file name fails with
+    /// [`Error::FileExtensionNotAllowed`](crate::Error::FileExtensionNotAllowed)
+    /// before any of its data is read.
+    pub fn file_extensions(mut self, policy: ExtensionPolicy) -> Constraints {
+        self.file_extensions = Some(policy);
+        self
+    }
+
+    /// Sets which file name extensions are accepted for a specific field, it
+    /// overrides the [`file_extensions`](Self::file_extensions) policy for
+    /// this field.
+    pub fn file_extensions_for<N: Into<String>>(mut self, field_name: N, policy: ExtensionPolicy) -> Constraints {
+        self.file_extensions_map.insert(field_name.into(), policy);
+        self
+    }
+
+    /// Reject fields whose `Content-Disposition` type is not `form-data`, e.g.
+    /// `attachment` or `inline`, or which lack the header, as required by
+    /// RFC 7578.
+    ///
+    /// Such fields fail with
+    /// [`Error::InvalidDispositionType`](crate::Error::InvalidDispositionType).
+    pub fn require_form_data(mut self, require: bool) -> Constraints {
+        self.require_form_data = require;
+        self
+    }
+
+    /// Reject fields declaring a `Content-Transfer-Encoding` other than
+    /// `7bit`, `8bit`, `binary`, `base64` and `quoted-printable`, instead of
+    /// passing their data through undecoded.
+    ///
+    /// Fields with a known transfer encoding are always decoded.
+    pub fn reject_unknown_transfer_encodings(mut self, reject: bool) -> Constraints {
+        self.reject_unknown_transfer_encodings = reject;
+        self
+    }
+
+    /// Reject malformed escape sequences in `quoted-printable` encoded fields.
+    ///
+    /// By default, these are passed through literally, as recommended by
+    /// RFC 2045.
+    pub fn strict_quoted_printable(mut self, strict: bool) -> Constraints {
+        self.strict_quoted_printable = strict;
+        self
+    }
+
+    /// Accept boundaries and headers terminated by a bare `\n`, as sent by
+    /// some embedded and scripting clients, in addition to `\r\n`.
+    ///
+    /// By default, only `\r\n` line endings are recognized, as required by
+    /// RFC 7578.
+    pub fn allow_lf_line_endings(mut self, allow: bool) -> Constraints {
+        self.allow_lf_line_endings = allow;
+        self
+    }
+
+    /// Accept field headers folded across several lines, where a line starting
+    /// with a space or tab continues the previous one (`obs-fold`), as sent by
+    /// some legacy MIME producers.
+    ///
+    /// Folded headers are unfolded by replacing each line break and the
+    /// leading whitespace of the continuation line with a single space. By
+    /// default, they fail to parse.
+    pub fn allow_folded_headers(mut self, allow: bool) -> Constraints {
+        self.allow_folded_headers = allow;
+        self
+    }
+
+    /// Reject boundary delimiter lines followed by whitespace before the line
+    /// break (transport padding).
+    ///
+    /// RFC 2046 allows transport padding, as added by some mail gateways, so
+    /// by default it is skipped. Such boundaries fail with
+    /// [`Error::UnexpectedTransportPadding`](crate::Error::UnexpectedTransportPadding)
+    /// if this is set.
+    pub fn reject_transport_padding(mut self, reject: bool) -> Constraints {
+        self.reject_transport_padding = reject;
+        self
+    }
+
+    /// Track the value of a `_charset_` field, as described in RFC 7578
+    /// section 4.6, and use it as the default charset of the
+    /// [`field.text()`](crate::Field::text) calls for the subsequent fields.
+    ///
+    /// The `charset` parameter of a field's `Content-Type` still takes
+    /// precedence. The tracked value is available from
+    /// [`multipart.form_charset()`](crate::Multipart::form_charset).
+    pub fn honor_charset_field(mut self, honor: bool) -> Constraints {
+        self.honor_charset_field = honor;
+        self
+    }
+
+    /// Reject payloads that are technically invalid according to RFC 7578,
+    /// which are otherwise accepted for compatibility with lenient clients.
+    ///
+    /// In strict mode, [`next_field()`](crate::Multipart::next_field) fails
+    /// with [`Error::StrictViolation`](crate::Error::StrictViolation) for
+    /// parts with a missing `Content-Disposition` header, a disposition type
+    /// other than `form-data`, a missing `name` parameter or a `filename*`
+    /// parameter. A boundary not conforming to the RFC 2046 grammar is
+    /// rejected with [`Error::InvalidBoundary`](crate::Error::InvalidBoundary).
+    pub fn strict(mut self, strict: bool) -> Constraints {
+        self.strict = strict;
+        self
+    }
+
+    /// Sets how a field repeating its `Content-Disposition`, `Content-Type` or
+    /// `Content-Transfer-Encoding` header is handled, see
+    /// [`DuplicateHeaderPolicy`].
+    pub fn duplicate_headers(mut self, policy: DuplicateHeaderPolicy) -> Constraints {
+        self.duplicate_headers = policy;
+        self
+    }
+
+    /// Decode the `name` and `filename` parameters of the
+    /// `Content-Disposition` header as ISO-8859-1 if they are not valid UTF-8,
+    /// as sent by some older browsers, instead of ignoring them.
+    pub fn latin1_fallback(mut self, fallback: bool) -> Constraints {
+        self.latin1_fallback = fallback;
+        self
+    }
+
+    /// Decode RFC 2047 encoded-words in the `Content-Disposition` parameter
+    /// values, e.g. `filename="=?UTF-8?B?5L2g5aW9LnR4dA==?="` as sent by some
+    /// Java clients, instead of passing them through literally.
+    ///
+    /// Malformed encoded-words and those in unknown charsets are left as-is.
+    pub fn decode_encoded_words(mut self, decode: bool) -> Constraints {
+        self.decode_encoded_words = decode;
+        self
+    }
+
+    /// Normalize the decoded field names and file names to Unicode
+    /// Normalization Form C, so that names submitted in decomposed form, as
+    /// by macOS clients, compare equal to the expected ones.
+    ///
+    /// This also applies to the names matched against
+    /// [`allowed_fields()`](Self::allowed_fields) and
+    /// [`SizeLimit::for_field()`], but not to
+    /// [`field.name_bytes()`](crate::Field::name_bytes) and
+    /// [`field.file_name_bytes()`](crate::Field::file_name_bytes).
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `unicode-normalization` feature to be
+    /// enabled.
+    #[cfg(feature = "unicode-normalization")]
+    #[cfg_attr(nightly, doc(cfg(feature = "unicode-normalization")))]
+    pub fn normalize_nfc(mut self, normalize: bool) -> Constraints {
+        self.normalize_nfc = normalize;
+        self
+    }
+
+    /// Name fields lacking a `name` parameter after their
+    /// [`index()`](crate::Field::index), e.g. `part-3`, so they remain
+    /// addressable through [`field.name()`](crate::Field::name),
+    /// [`allowed_fields()`](Self::allowed_fields) and
+    /// [`SizeLimit::for_field()`].
+    ///
+    /// The synthesized name is not reflected in
+    /// [`field.name_bytes()`](crate::Field::name_bytes), and may clash with the
+    /// name of another field sent by the client.
+    pub fn positional_names(mut self, positional: bool) -> Constraints {
+        self.positional_names = positional;
+        self
+    }
+
+    /// Strip a leading UTF-8 byte order mark from the field data, as
+    /// prepended to text parts by some Windows clients.
+    ///
+    /// [`field.text()`](crate::Field::text) always strips the BOM, but
+    /// without this, it is passed on by [`field.bytes()`](crate::Field::bytes),
+    /// [`field.chunk()`](crate::Field::chunk) and `field.json()`, where
+    /// downstream parsers may choke on it.
+    pub fn strip_utf8_bom(mut self, strip: bool) -> Constraints {
+        self.strip_utf8_bom = strip;
+        self
+    }
+
+    /// Check the size of the data of each field declaring a `Content-Length`
+    /// header against it, and fail with
+    /// [`Error::ContentLengthMismatch`](crate::Error::ContentLengthMismatch)
+    /// if they differ.
+    ///
+    /// Data exceeding the declared length is rejected as soon as it is read,
+    /// so the length can be trusted to pre-allocate storage.
+    pub fn verify_content_length(mut self, verify: bool) -> Constraints {
+        self.verify_content_length = verify;
+        self
+    }
+
+    /// Keep the raw bytes of the body as they are consumed by the parser, to
+    /// be taken with [`Multipart::take_raw()`](crate::Multipart::take_raw) and
+    /// [`Field::take_raw()`](crate::Field::take_raw), e.g. to forward the body
+    /// byte for byte after inspecting its fields.
+    ///
+    /// The raw bytes are kept until they are taken. Bytes discarded while
+    /// draining the stream, with [`Multipart::drain()`](crate::Multipart::drain)
+    /// or [`drain_on_violation()`](Self::drain_on_violation), aren't captured.
+    pub fn capture_raw(mut self, capture: bool) -> Constraints {
+        self.capture_raw = capture;
+        self
+    }
+
+    /// Decompress the data of fields declaring a `Content-Encoding` of `gzip`,
+    /// `deflate` or `br` on the fly. Fields with any other encoding are passed
+    /// through as is.
+    ///
+    /// The field size limits apply to the compressed data, use
+    /// [`decompressed_size_limit()`](Self::decompressed_size_limit) to bound
+    /// the decompressed size.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `content-encoding` feature to be enabled.
+    #[cfg(feature = "content-encoding")]
+    #[cfg_attr(nightly, doc(cfg(feature = "content-encoding")))]
+    pub fn decompress_content_encoding(mut self, decompress: bool) -> Constraints {
+        self.decompress_content_encoding = decompress;
+        self
+    }
+
+    /// Sets size limit for the decompressed data of each field to guard
+    /// against decompression bombs, see
+    /// [`decompress_content_encoding()`](Self::decompress_content_encoding).
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `content-encoding` feature to be enabled.
+    #[cfg(feature = "content-encoding")]
+    #[cfg_attr(nightly, doc(cfg(feature = "content-encoding")))]
+    pub fn decompressed_size_limit(mut self, limit: u64) -> Constraints {
+        self.decompressed_size_limit = Some(limit);
+        self
+    }
+
+    /// Sets how deeply multipart bodies may be nested in fields, see
+    /// [`Field::into_nested_multipart()`](crate::Field::into_nested_multipart).
+    ///
+    /// The top-level body is at depth 0, so a limit of 0 rejects any nested
+    /// body. The limit carries over to the nested bodies, where the lower of
+    /// their own and the inherited limit applies, so it can't be lifted by
+    /// parsing a nested body with other constraints.
+    pub fn max_depth(mut self, max_depth: usize) -> Constraints {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Reject fields carrying a file name but no data with
+    /// [`Error::EmptyFile`](crate::Error::EmptyFile), as submitted by browsers
+    /// for file inputs left empty.
+    ///
+    /// This is only known once the end of the field is reached, when its data
+    /// is read or it's skipped by calling
+    /// [`next_field()`](crate::Multipart::next_field).
+    pub fn reject_empty_files(mut self, reject: bool) -> Constraints {
+        self.reject_empty_files = reject;
+        self
+    }
+
+    /// Check that the data of each file field starts with the signature of
+    /// its declared `Content-Type`, e.g. the PNG magic bytes for `image/png`,
+    /// failing with
+    /// [`Error::ContentTypeMismatch`](crate::Error::ContentTypeMismatch)
+    /// otherwise.
+    ///
+    /// Signatures are known for PNG, JPEG, GIF, WebP, PDF, ZIP and gzip
+    /// files. Fields of other types aren't checked. The first bytes of a
+    /// field are held back until there are enough of them to be checked.
+    pub fn sniff_content_type(mut self, sniff: bool) -> Constraints {
+        self.sniff_content_type = sniff;
+        self
+    }
+
+    /// Drain the rest of the stream after a constraint is violated, e.g. a
+    /// size limit is exceeded, so that the server can still reply with a
+    /// proper status code instead of resetting the connection.
+    ///
+    /// The violation is returned as usual, and can be retrieved later with
+    /// [`Multipart::violation()`](crate::Multipart::violation). The following
+    /// call to [`next_field()`](crate::Multipart::next_field) then discards
+    /// the remaining data without buffering or parsing it, ignoring the size
+    /// limit of the whole stream, and returns `None`.
+    pub fn drain_on_violation(mut self, drain: bool) -> Constraints {
+        self.drain_on_violation = drain;
+        self
+    }
+
+    /// Share a [`MemoryBudget`] with other parsers, to cap the total number of
+    /// bytes buffered across all of them rather than per request.
+    ///
+    /// Once the budget is exhausted, parsing fails with
+    /// [`Error::MemoryBudgetExceeded`](crate::Error::MemoryBudgetExceeded).
+    /// Only the data held by the parser count, not the chunks already yielded
+    /// to the application.
+    pub fn memory_budget(mut self, budget: MemoryBudget) -> Constraints {
+        self.memory_budget = Some(budget);
+        self
+    }
+
+    /// Abort parsing with [`Error::TooSlow`](crate::Error::TooSlow) if fewer
+    /// than `min_bytes` are received per `window`, so that a client trickling
+    /// the body can't hold the connection for hours.
+    ///
+    /// Only the time spent waiting for the stream to yield data is measured,
+    /// not the time the application takes to process the fields. The rate is
+    /// checked as chunks arrive, so a stream that stops yielding altogether
+    /// must be caught with a timeout.
+    pub fn min_throughput(mut self, min_bytes: u64, window: Duration) -> Constraints {
+        self.min_throughput = Some((min_bytes, window));
+        self
+    }
+
+    /// Abort parsing with
+    /// [`Error::DeadlineExceeded`](crate::Error::DeadlineExceeded) once the
+    /// given time has passed since the creation of the
+    /// [`Multipart`](crate::Multipart), instead of wrapping every call to
+    /// [`next_field()`](crate::Multipart::next_field) and
+    /// [`chunk()`](crate::Field::chunk) in a timeout.
+    ///
+    /// The deadline only applies while data is still expected from the
+    /// stream, so the fields of a body that has been received in full can be
+    /// read past it.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-time` feature to be enabled, and the
+    /// parser to run within a Tokio runtime.
+    #[cfg(feature = "tokio-time")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-time")))]
+    pub fn deadline(mut self, timeout: Duration) -> Constraints {
+        self.deadline = Some(timeout);
+        self
+    }
+
+    /// Abort parsing with [`Error::ReadTimeout`](crate::Error::ReadTimeout)
+    /// if the stream yields no data for the given time while it's awaited,
+    /// e.g. between two chunks of a field.
+    ///
+    /// Unlike [`deadline`](Self::deadline), this detects a stalled upload
+    /// without putting a bound on the duration of long uploads that keep
+    /// making progress.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-time` feature to be enabled, and the
+    /// parser to run within a Tokio runtime.
+    #[cfg(feature = "tokio-time")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-time")))]
+    pub fn read_timeout(mut self, timeout: Duration) -> Constraints {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Throttle reading the stream to `bytes_per_sec` on average, allowing
+    /// bursts of up to `burst` bytes, to slow down abusive uploaders without
+    /// rejecting them.
+    ///
+    /// Reading is paused while the rate is exceeded, which in turn applies
+    /// backpressure to the client through the underlying connection.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-time` feature to be enabled, and the
+    /// parser to run within a Tokio runtime.
+    #[cfg(feature = "tokio-time")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-time")))]
+    pub fn rate_limit(mut self, bytes_per_sec: u64, burst: u64) -> Constraints {
+        self.rate_limit = Some((bytes_per_sec, burst));
+        self
+    }
+
+    /// Throttle reading the data of a specific field, e.g. a large file
+    /// upload, in addition to the [`rate_limit`](Self::rate_limit) of the
+    /// whole stream.
+    ///
+    /// The rate applies to the data received from the stream while the field
+    /// is being read, and starts afresh with a full burst for each field of
+    /// that name.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-time` feature to be enabled, and the
+    /// parser to run within a Tokio runtime.
+    #[cfg(feature = "tokio-time")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-time")))]
+    pub fn rate_limit_for<N: Into<String>>(mut self, field_name: N, bytes_per_sec: u64, burst: u64) -> Constraints {
+        self.rate_limit_map.insert(field_name.into(), (bytes_per_sec, burst));
+        self
+    }
 
-        Constraints {
-            size_limit: self.size_limit,
-            allowed_fields: Some(allowed_fields),
+    /// Abort parsing with [`Error::Cancelled`](crate::Error::Cancelled) once
+    /// the given token is cancelled, e.g. when the client has disconnected.
+    ///
+    /// Pending calls to [`next_field()`](crate::Multipart::next_field) and
+    /// [`chunk()`](crate::Field::chunk) are woken up and fail as soon as the
+    /// token is cancelled, as do all the later ones, even if the data is
+    /// already buffered.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `cancellation` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use futures_util::stream;
+    /// use multer::{Constraints, Multipart};
+    /// use tokio_util::sync::CancellationToken;
+    ///
+    /// # async fn run() {
+    /// let token = CancellationToken::new();
+    /// let stream = stream::pending::<Result<Bytes, std::io::Error>>();
+    /// let constraints = Constraints::new().cancellation_token(token.clone());
+    /// let mut multipart = Multipart::with_constraints(stream, "X-BOUNDARY", constraints);
+    ///
+    /// tokio::spawn(async move { token.cancel() });
+    /// assert!(matches!(multipart.next_field().await, Err(multer::Error::Cancelled)));
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    #[cfg(feature = "cancellation")]
+    #[cfg_attr(nightly, doc(cfg(feature = "cancellation")))]
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Constraints {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Skip parts with an unparseable header block or a malformed boundary
+    /// delimiter, and resume parsing at the next boundary, instead of failing.
+    ///
+    /// The error that caused a part to be skipped is passed to `on_skip`, e.g.
+    /// to log it. Errors that can't be recovered from, like exceeded limits or
+    /// a truncated stream, are still returned by
+    /// [`next_field()`](crate::Multipart::next_field).
+    ///
+    /// This is meant for tools that must salvage whatever they can from
+    /// damaged bodies, as skipped parts are silently lost to the application.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Constraints;
+    ///
+    /// let constraints =
+    ///     Constraints::new().skip_malformed_parts(|err| eprintln!("skipping part: {}", err));
+    /// ```
+    pub fn skip_malformed_parts<F>(mut self, on_skip: F) -> Constraints
+    where
+        F: Fn(Error) + Send + Sync + 'static,
+    {
+        self.skip_malformed_parts = Some(MalformedPartHandler(Arc::new(on_skip)));
+        self
+    }
+
+    /// Adds a validator which is run with the field name, file name,
+    /// `Content-Type` and headers of each field once its headers are parsed,
+    /// before its data is streamed.
+    ///
+    /// An error returned by the validator fails
+    /// [`next_field()`](crate::Multipart::next_field) with
+    /// [`Error::ValidationFailed`](crate::Error::ValidationFailed), which
+    /// carries it as its source. Validators run in the order they were added,
+    /// after the other constraints have been checked. See
+    /// [`validate_async()`](Self::validate_async) for a validator which has to
+    /// wait, e.g. for a database lookup.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Constraints;
+    ///
+    /// let constraints =
+    ///     Constraints::new().validate(|field_name, file_name, _content_type, _headers| {
+    ///         match (field_name, file_name) {
+    ///             (Some("avatar"), None) => Err("the avatar must be uploaded as a file"),
+    ///             _ => Ok(()),
+    ///         }
+    ///     });
+    /// ```
+    pub fn validate<F, E>(mut self, validator: F) -> Constraints
+    where
+        F: Fn(Option<&str>, Option<&str>, Option<&mime::Mime>, &HeaderMap) -> Result<(), E> + Send + Sync + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        self.validators.push(Validator::Sync(Arc::new(
+            move |field_name, file_name, content_type, headers| {
+                validator(field_name, file_name, content_type, headers).map_err(Into::into)
+            },
+        )));
+        self
+    }
+
+    /// Adds an asynchronous validator, which is run like the ones added with
+    /// [`validate()`](Self::validate), but returns a future.
+    ///
+    /// The future is awaited by [`next_field()`](crate::Multipart::next_field)
+    /// before the field is yielded, so none of its data is streamed until all
+    /// the validators have passed. It has to own the data it needs, e.g. a
+    /// copy of the field name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{Constraints, Multipart};
+    ///
+    /// async fn is_quota_exceeded(_field_name: &str) -> bool {
+    ///     false
+    /// }
+    ///
+    /// # async fn run() {
+    /// let constraints = Constraints::new().validate_async(|field_name, _file_name, _content_type, _headers| {
+    ///     let field_name = field_name.map(str::to_owned);
+    ///     async move {
+    ///         match field_name {
+    ///             Some(name) if is_quota_exceeded(&name).await => Err("the upload quota is exceeded"),
+    ///             _ => Ok(()),
+    ///         }
+    ///     }
+    /// });
+    ///
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert_eq!(field.text().await.unwrap(), "abcd");
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn validate_async<F, Fut, E>(mut self, validator: F) -> Constraints
+    where
+        F: Fn(Option<&str>, Option<&str>, Option<&mime::Mime>, &HeaderMap) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        self.validators.push(Validator::Async(Arc::new(
+            move |field_name, file_name, content_type, headers| {
+                validator(field_name, file_name, content_type, headers)
+                    .map(|result| result.map_err(Into::into))
+                    .boxed()
+            },
+        )));
+        self
+    }
+
+    /// Sets a callback invoked as the data of the fields is read, with the
+    /// field name, the number of bytes read from the field so far and the
+    /// total number of bytes of the body consumed so far, e.g. to report the
+    /// progress of an upload to the client.
+    ///
+    /// The byte counts are taken on the wire, i.e. before any
+    /// `Content-Transfer-Encoding` or `Content-Encoding` is decoded. The data
+    /// of skipped fields is reported too, with their name, and so is the data
+    /// discarded by [`Multipart::drain()`](crate::Multipart::drain), without
+    /// a field name. The callback runs on the task reading the stream, so it
+    /// should not block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{Constraints, Multipart};
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let constraints = Constraints::new().on_progress(|field_name, field_bytes, total_bytes| {
+    ///     println!("{:?}: {} bytes ({} in total)", field_name, field_bytes, total_bytes);
+    /// });
+    /// let mut multipart = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+    ///
+    /// while let Some(field) = multipart.next_field().await.unwrap() {
+    ///     field.bytes().await.unwrap();
+    /// }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn on_progress<F>(mut self, callback: F) -> Constraints
+    where
+        F: Fn(Option<&str>, u64, u64) + Send + Sync + 'static,
+    {
+        self.on_progress = Some(ProgressHandler(Arc::new(callback)));
+        self
+    }
+
+    /// Sets a [`FieldInspector`] receiving each chunk of each field as it
+    /// streams through, e.g. to feed a virus scanner or a checksum service,
+    /// and which may veto the field or the whole request.
+    ///
+    /// A vetoed field fails with
+    /// [`Error::FieldRejected`](crate::Error::FieldRejected), a vetoed request
+    /// with [`Error::RequestRejected`](crate::Error::RequestRejected), after
+    /// which [`next_field()`](crate::Multipart::next_field) returns `None`.
+    pub fn inspect<I: FieldInspector + 'static>(mut self, inspector: I) -> Constraints {
+        self.inspector = Some(Inspector(Arc::new(inspector)));
+        self
+    }
+
+    pub(crate) fn is_content_type_allowed(&self, field: Option<&str>, content_type: Option<&mime::Mime>) -> bool {
+        let allowed = match field.and_then(|field| self.allowed_content_types.get(field)) {
+            Some(allowed) => allowed,
+            None => return true,
+        };
+
+        let content_type = match content_type {
+            Some(content_type) => content_type,
+            None => return false,
+        };
+
+        allowed.iter().any(|item| match item.split_once('/') {
+            Some(("*", "*")) => true,
+            Some((type_, "*")) => content_type.type_() == type_,
+            _ => content_type.essence_str() == item,
+        })
+    }
+
+    pub(crate) fn is_file_content_type_allowed(&self, content_type: Option<&mime::Mime>) -> bool {
+        match self.file_content_type {
+            FileContentTypePolicy::Optional => true,
+            FileContentTypePolicy::Required => content_type.is_some(),
+            FileContentTypePolicy::Specific => {
+                content_type.is_some_and(|content_type| content_type.essence_str() != mime::APPLICATION_OCTET_STREAM)
+            }
+        }
+    }
+
+    pub(crate) fn is_file_name_allowed(&self, field: Option<&str>, file_name: &str) -> bool {
+        let policy = field
+            .and_then(|field| self.file_extensions_map.get(field))
+            .or(self.file_extensions.as_ref());
+
+        match policy {
+            Some(policy) => policy.is_allowed(file_name),
+            None => true,
         }
     }
 
+    pub(crate) fn is_file_field(&self, field: &str) -> bool {
+        self.file_fields.iter().any(|item| item == field)
+    }
+
+    pub(crate) fn is_truncated_field(&self, field: &str) -> bool {
+        self.truncate_fields.iter().any(|item| item == field)
+    }
+
+    pub(crate) fn is_duplicate_allowed(&self, field: &str) -> bool {
+        !self.reject_duplicate_fields || self.allowed_duplicate_fields.iter().any(|item| item == field)
+    }
+
     pub(crate) fn is_it_allowed(&self, field: Option<&str>) -> bool {
         if let Some(ref allowed_fields) = self.allowed_fields {
             field
@@ -86,3 +889,189 @@ impl Constraints {
         }
     }
 }
+
+/// Whether fields carrying a file name must declare a `Content-Type`.
+///
+/// Please refer [`Constraints::file_content_type()`] for more info.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum FileContentTypePolicy {
+    /// Accept files without a `Content-Type`. This is the default.
+    #[default]
+    Optional,
+    /// Require a valid `Content-Type`.
+    Required,
+    /// Require a `Content-Type` other than the generic
+    /// `application/octet-stream`.
+    Specific,
+}
+
+/// How a field not listed with [`Constraints::allowed_fields()`] is handled.
+///
+/// Please refer [`Constraints::unknown_fields()`] for more info.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum UnknownFieldPolicy {
+    /// Skip the field and its data silently.
+    Ignore,
+    /// Skip the field and its data, logging a warning if the optional `log`
+    /// feature is enabled.
+    SkipAndWarn,
+    /// Fail with [`Error::UnknownField`](crate::Error::UnknownField). This is
+    /// the default.
+    #[default]
+    Reject,
+}
+
+/// How a field repeating one of the headers that determine how it is parsed,
+/// i.e. `Content-Disposition`, `Content-Type` and `Content-Transfer-Encoding`,
+/// is handled.
+///
+/// As intermediaries may pick a different occurrence than the application,
+/// duplicates of these headers are a known request smuggling vector.
+///
+/// Please refer [`Constraints::duplicate_headers()`] for more info.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum DuplicateHeaderPolicy {
+    /// Use the first occurrence and ignore the others.
+    FirstWins,
+    /// Use the last occurrence and ignore the others. This is the default.
+    #[default]
+    LastWins,
+    /// Fail with [`Error::DuplicateHeader`](crate::Error::DuplicateHeader).
+    Reject,
+}
+
+/// Which file name extensions are accepted, matched case-insensitively and
+/// with or without the leading dot, e.g. `png` or `.png`.
+///
+/// Please refer [`Constraints::file_extensions()`] for more info.
+///
+/// # Examples
+///
+/// ```
+/// use multer::{Constraints, ExtensionPolicy};
+///
+/// let constraints = Constraints::new()
+///     .file_extensions(ExtensionPolicy::deny(vec!["exe", "php"]))
+///     .file_extensions_for("avatar", ExtensionPolicy::allow(vec!["png", "jpg", "jpeg"]));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize), serde(rename_all = "snake_case"))]
+pub enum ExtensionPolicy {
+    /// Accept only file names whose last extension is in the list.
+    Allow(Vec<String>),
+    /// Reject file names with any extension in the list, so that e.g.
+    /// `shell.php.jpg` is rejected for `php`.
+    Deny(Vec<String>),
+}
+
+impl ExtensionPolicy {
+    /// Accept only file names whose last extension is in the list.
+    pub fn allow<N: Into<String>>(extensions: Vec<N>) -> ExtensionPolicy {
+        ExtensionPolicy::Allow(extensions.into_iter().map(Into::into).collect())
+    }
+
+    /// Reject file names with any extension in the list.
+    pub fn deny<N: Into<String>>(extensions: Vec<N>) -> ExtensionPolicy {
+        ExtensionPolicy::Deny(extensions.into_iter().map(Into::into).collect())
+    }
+
+    fn is_allowed(&self, file_name: &str) -> bool {
+        let file_name = helpers::strip_path(file_name).unwrap_or_default();
+        let contains = |list: &[String], extension: &str| {
+            list.iter()
+                .any(|item| item.trim_start_matches('.').eq_ignore_ascii_case(extension))
+        };
+
+        match self {
+            ExtensionPolicy::Allow(list) => file_name
+                .rsplit_once('.')
+                .is_some_and(|(_, extension)| contains(list, extension)),
+            ExtensionPolicy::Deny(list) => !file_name.split('.').skip(1).any(|extension| contains(list, extension)),
+        }
+    }
+}
+
+/// The callback notified of the parts skipped with
+/// [`Constraints::skip_malformed_parts()`].
+#[derive(Clone)]
+pub(crate) struct MalformedPartHandler(pub(crate) Arc<dyn Fn(Error) + Send + Sync>);
+
+impl Debug for MalformedPartHandler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("MalformedPartHandler")
+    }
+}
+
+type ProgressFn = dyn Fn(Option<&str>, u64, u64) + Send + Sync;
+
+/// The callback notified of the data read, set with
+/// [`Constraints::on_progress()`].
+#[derive(Clone)]
+pub(crate) struct ProgressHandler(pub(crate) Arc<ProgressFn>);
+
+impl Debug for ProgressHandler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressHandler")
+    }
+}
+
+/// A progress report recorded while the parsing state is locked, and passed
+/// to the [`ProgressHandler`] once it is released.
+#[derive(Debug)]
+pub(crate) struct Progress {
+    pub(crate) handler: ProgressHandler,
+    pub(crate) field_name: Option<String>,
+    pub(crate) field_bytes: u64,
+    pub(crate) total_bytes: u64,
+}
+
+impl Progress {
+    pub(crate) fn report(self) {
+        (self.handler.0)(self.field_name.as_deref(), self.field_bytes, self.total_bytes);
+    }
+}
+
+/// The hook inspecting the data of the fields, set with
+/// [`Constraints::inspect()`].
+#[derive(Clone)]
+pub(crate) struct Inspector(pub(crate) Arc<dyn FieldInspector>);
+
+impl Debug for Inspector {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Inspector")
+    }
+}
+
+type ValidatorFn = dyn Fn(
+        Option<&str>,
+        Option<&str>,
+        Option<&mime::Mime>,
+        &HeaderMap,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+    + Send
+    + Sync;
+
+type AsyncValidatorFn = dyn Fn(
+        Option<&str>,
+        Option<&str>,
+        Option<&mime::Mime>,
+        &HeaderMap,
+    ) -> BoxFuture<'static, Result<(), Box<dyn std::error::Error + Send + Sync>>>
+    + Send
+    + Sync;
+
+/// A user-supplied check run on the headers of each field.
+#[derive(Clone)]
+pub(crate) enum Validator {
+    Sync(Arc<ValidatorFn>),
+    Async(Arc<AsyncValidatorFn>),
+}
+
+impl Debug for Validator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("Validator")
+    }
+}
@@ -1,25 +1,105 @@
 use http::header::{self, HeaderMap};
 
-use crate::constants::ContentDispositionAttr;
+use crate::constants::{self, ContentDispositionAttr};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(crate) struct ContentDisposition {
+    pub(crate) disposition_type: Option<String>,
     pub(crate) field_name: Option<String>,
     pub(crate) file_name: Option<String>,
+    pub(crate) field_name_bytes: Option<Vec<u8>>,
+    pub(crate) file_name_bytes: Option<Vec<u8>>,
+    pub(crate) has_extended_file_name: bool,
+    pub(crate) has_positional_name: bool,
+    pub(crate) params: Vec<(String, String)>,
 }
 
 impl ContentDisposition {
-    pub fn parse(headers: &HeaderMap) -> ContentDisposition {
+    /// Parses the `Content-Disposition` header, decoding the `name` and
+    /// `filename` parameters as ISO-8859-1 if they are not valid UTF-8 and
+    /// `latin1_fallback` is set.
+    pub fn parse(headers: &HeaderMap, latin1_fallback: bool) -> ContentDisposition {
         let content_disposition = headers.get(header::CONTENT_DISPOSITION).map(|val| val.as_bytes());
 
-        let field_name = content_disposition
-            .and_then(|val| ContentDispositionAttr::Name.extract_from(val))
-            .map(|attr| attr.into_owned());
+        let disposition_type = content_disposition.and_then(parse_disposition_type);
 
-        let file_name = content_disposition
-            .and_then(|val| ContentDispositionAttr::FileName.extract_from(val))
-            .map(|attr| attr.into_owned());
+        let extract = |attr: ContentDispositionAttr| {
+            let val = content_disposition?;
+            match attr.extract_from(val) {
+                Some(value) => Some(value.into_owned()),
+                None if latin1_fallback => attr.extract_latin1_from(val),
+                None => None,
+            }
+        };
 
-        ContentDisposition { field_name, file_name }
+        let field_name = extract(ContentDispositionAttr::Name);
+        let file_name = extract(ContentDispositionAttr::FileName);
+
+        let field_name_bytes = content_disposition.and_then(|val| ContentDispositionAttr::Name.extract_bytes_from(val));
+        let file_name_bytes =
+            content_disposition.and_then(|val| ContentDispositionAttr::FileName.extract_bytes_from(val));
+
+        let has_extended_file_name =
+            content_disposition.is_some_and(|val| ContentDispositionAttr::FileName.is_extended_in(val));
+
+        let params = content_disposition
+            .map(constants::extract_all_params)
+            .unwrap_or_default();
+
+        ContentDisposition {
+            disposition_type,
+            field_name,
+            file_name,
+            field_name_bytes,
+            file_name_bytes,
+            has_extended_file_name,
+            has_positional_name: false,
+            params,
+        }
+    }
+
+    /// Decodes the RFC 2047 encoded-words in the parameter values.
+    pub fn decode_encoded_words(&mut self) {
+        let values = self
+            .field_name
+            .iter_mut()
+            .chain(self.file_name.iter_mut())
+            .chain(self.params.iter_mut().map(|(_, value)| value));
+
+        for value in values {
+            if let Some(decoded) = constants::decode_encoded_words(value) {
+                *value = decoded;
+            }
+        }
+    }
+
+    /// Normalizes the field name and file name to Unicode Normalization Form
+    /// C.
+    #[cfg(feature = "unicode-normalization")]
+    pub fn normalize_nfc(&mut self) {
+        use unicode_normalization::UnicodeNormalization;
+
+        for value in self.field_name.iter_mut().chain(self.file_name.iter_mut()) {
+            *value = value.nfc().collect();
+        }
+    }
+
+    /// Names the field after its position, e.g. `part-3`, if it has no name.
+    pub fn name_by_position(&mut self, idx: usize) {
+        if self.field_name.is_none() {
+            self.field_name = Some(format!("part-{}", idx));
+            self.has_positional_name = true;
+        }
+    }
+}
+
+/// The lowercased disposition type preceding the parameters, e.g. `form-data`.
+fn parse_disposition_type(header: &[u8]) -> Option<String> {
+    let end = memchr::memchr(b';', header).unwrap_or(header.len());
+    let disposition_type = std::str::from_utf8(&header[..end]).ok()?.trim();
+
+    match disposition_type.is_empty() || disposition_type.contains('=') {
+        true => None,
+        false => Some(disposition_type.to_ascii_lowercase()),
     }
 }
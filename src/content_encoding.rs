@@ -0,0 +1,219 @@
+use std::fmt::{self, Debug, Formatter};
+use std::io::{self, Write};
+
+use brotli_decompressor::DecompressorWriter;
+use bytes::Bytes;
+use flate2::write::{GzDecoder, ZlibDecoder};
+use http::header::{self, HeaderMap};
+
+/// The size of the internal buffer of the brotli decoder.
+const BROTLI_BUFFER_SIZE: usize = 4096;
+
+/// The `Content-Encoding` declared by a part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum ContentEncoding {
+    /// No header, or `identity`.
+    Identity,
+    Gzip,
+    Deflate,
+    Brotli,
+    Unknown(String),
+}
+
+impl ContentEncoding {
+    pub fn parse(headers: &HeaderMap) -> ContentEncoding {
+        let value = match headers.get(header::CONTENT_ENCODING) {
+            Some(value) => value,
+            None => return ContentEncoding::Identity,
+        };
+
+        let value = String::from_utf8_lossy(value.as_bytes());
+        let value = value.trim();
+        if value.is_empty() || value.eq_ignore_ascii_case("identity") {
+            ContentEncoding::Identity
+        } else if value.eq_ignore_ascii_case("gzip") || value.eq_ignore_ascii_case("x-gzip") {
+            ContentEncoding::Gzip
+        } else if value.eq_ignore_ascii_case("deflate") {
+            ContentEncoding::Deflate
+        } else if value.eq_ignore_ascii_case("br") {
+            ContentEncoding::Brotli
+        } else {
+            ContentEncoding::Unknown(value.to_owned())
+        }
+    }
+
+    /// Returns the decoder for the encoding, which fails once more than `limit`
+    /// bytes have been decompressed.
+    pub fn decoder(&self, limit: u64) -> Option<ContentDecoder> {
+        let sink = LimitedSink {
+            buf: Vec::new(),
+            remaining: limit,
+            exceeded: false,
+        };
+
+        let inner = match self {
+            ContentEncoding::Gzip => Inner::Gzip(GzDecoder::new(sink)),
+            ContentEncoding::Deflate => Inner::Deflate(ZlibDecoder::new(sink)),
+            ContentEncoding::Brotli => Inner::Brotli(Box::new(DecompressorWriter::new(sink, BROTLI_BUFFER_SIZE))),
+            ContentEncoding::Identity | ContentEncoding::Unknown(_) => return None,
+        };
+
+        Some(ContentDecoder { inner, limit })
+    }
+}
+
+/// The reasons decompressing a chunk may fail.
+#[derive(Debug)]
+pub(crate) enum ContentDecodeError {
+    /// More than the given number of bytes were decompressed.
+    SizeExceeded(u64),
+    Invalid(io::Error),
+}
+
+/// Incrementally decompresses the chunks of a content-encoded field.
+pub(crate) struct ContentDecoder {
+    inner: Inner,
+    limit: u64,
+}
+
+enum Inner {
+    Gzip(GzDecoder<LimitedSink>),
+    Deflate(ZlibDecoder<LimitedSink>),
+    Brotli(Box<DecompressorWriter<LimitedSink>>),
+}
+
+impl ContentDecoder {
+    /// Decompresses the next chunk of data. With `last` set, the compressed
+    /// stream is expected to end with this chunk.
+    pub fn decode(&mut self, chunk: &[u8], last: bool) -> Result<Bytes, ContentDecodeError> {
+        let result = match &mut self.inner {
+            Inner::Gzip(decoder) => decoder
+                .write_all(chunk)
+                .and_then(|_| if last { decoder.try_finish() } else { Ok(()) }),
+            Inner::Deflate(decoder) => {
+                decoder
+                    .write_all(chunk)
+                    .and_then(|_| if last { decoder.try_finish() } else { Ok(()) })
+            }
+            Inner::Brotli(decoder) => decoder
+                .write_all(chunk)
+                .and_then(|_| if last { decoder.close() } else { Ok(()) }),
+        };
+
+        let sink = match &mut self.inner {
+            Inner::Gzip(decoder) => decoder.get_mut(),
+            Inner::Deflate(decoder) => decoder.get_mut(),
+            Inner::Brotli(decoder) => decoder.get_mut(),
+        };
+
+        match result {
+            Ok(()) => Ok(std::mem::take(&mut sink.buf).into()),
+            Err(_) if sink.exceeded => Err(ContentDecodeError::SizeExceeded(self.limit)),
+            Err(err) => Err(ContentDecodeError::Invalid(err)),
+        }
+    }
+}
+
+impl Debug for ContentDecoder {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let encoding = match self.inner {
+            Inner::Gzip(_) => "gzip",
+            Inner::Deflate(_) => "deflate",
+            Inner::Brotli(_) => "br",
+        };
+
+        f.debug_struct("ContentDecoder")
+            .field("encoding", &encoding)
+            .field("limit", &self.limit)
+            .finish()
+    }
+}
+
+/// Collects the decompressed data, refusing to take more than `remaining`
+/// bytes so that a decompression bomb is cut short while it is being expanded.
+struct LimitedSink {
+    buf: Vec<u8>,
+    remaining: u64,
+    exceeded: bool,
+}
+
+impl Write for LimitedSink {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        if data.len() as u64 > self.remaining {
+            self.exceeded = true;
+            return Err(io::Error::other("decompressed size limit exceeded"));
+        }
+
+        self.remaining -= data.len() as u64;
+        self.buf.extend_from_slice(data);
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_parse_content_encoding() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(ContentEncoding::parse(&headers), ContentEncoding::Identity);
+
+        for (value, expected) in [
+            ("gzip", ContentEncoding::Gzip),
+            (" X-GZIP ", ContentEncoding::Gzip),
+            ("deflate", ContentEncoding::Deflate),
+            ("br", ContentEncoding::Brotli),
+            ("identity", ContentEncoding::Identity),
+            ("zstd", ContentEncoding::Unknown("zstd".to_owned())),
+        ] {
+            headers.insert(header::CONTENT_ENCODING, value.parse().unwrap());
+            assert_eq!(ContentEncoding::parse(&headers), expected);
+        }
+    }
+
+    #[test]
+    fn test_decode_gzip_in_chunks() {
+        let data = b"hello world ".repeat(100);
+        let compressed = gzip(&data);
+
+        let mut decoder = ContentEncoding::Gzip.decoder(u64::MAX).unwrap();
+        let mut out = Vec::new();
+        let mut chunks = compressed.chunks(7).peekable();
+        while let Some(chunk) = chunks.next() {
+            out.extend_from_slice(&decoder.decode(chunk, chunks.peek().is_none()).unwrap());
+        }
+
+        assert_eq!(out, data);
+    }
+
+    #[test]
+    fn test_decode_size_limit() {
+        let compressed = gzip(&[0; 1 << 20]);
+
+        let mut decoder = ContentEncoding::Gzip.decoder(1024).unwrap();
+        assert!(matches!(
+            decoder.decode(&compressed, true),
+            Err(ContentDecodeError::SizeExceeded(1024))
+        ));
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        let mut decoder = ContentEncoding::Brotli.decoder(u64::MAX).unwrap();
+        assert!(matches!(
+            decoder.decode(b"not brotli at all", true),
+            Err(ContentDecodeError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,720 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::path::PathBuf;
+#[cfg(feature = "tokio-fs")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "tokio-fs")]
+use std::sync::Arc;
+
+use bytes::Bytes;
+use serde::de::value::SeqDeserializer;
+use serde::de::{self, DeserializeOwned, DeserializeSeed, IntoDeserializer, MapAccess, Visitor};
+use serde::{forward_to_deserialize_any, Deserialize, Deserializer};
+
+use crate::error::Error;
+use crate::field::Field;
+use crate::form_file::FormFile;
+#[cfg(feature = "tokio-fs")]
+use crate::form_file::SpooledFormFile;
+use crate::multipart::Multipart;
+#[cfg(feature = "tokio-fs")]
+use crate::spool::SpooledField;
+
+const FILE_NAME: &str = "file_name";
+const CONTENT_TYPE: &str = "content_type";
+const DATA: &str = "data";
+const PATH: &str = "path";
+
+impl<'de> Deserialize<'de> for FormFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let file = FileFields::deserialize(deserializer)?;
+        if file.path.is_some() {
+            return Err(de::Error::custom("file data was spilled to a temporary file"));
+        }
+
+        Ok(FormFile {
+            file_name: file.file_name,
+            content_type: file.content_type,
+            data: file.data,
+        })
+    }
+}
+
+#[cfg(feature = "tokio-fs")]
+impl<'de> Deserialize<'de> for SpooledFormFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let file = FileFields::deserialize(deserializer)?;
+
+        Ok(SpooledFormFile {
+            file_name: file.file_name,
+            content_type: file.content_type,
+            data: match file.path {
+                Some(path) => SpooledField::File(path),
+                None => SpooledField::Memory(file.data),
+            },
+        })
+    }
+}
+
+/// The entries of the map a file field is deserialized from.
+struct FileFields {
+    file_name: Option<String>,
+    content_type: Option<mime::Mime>,
+    data: Bytes,
+    path: Option<PathBuf>,
+}
+
+impl<'de> Deserialize<'de> for FileFields {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FileData(Vec<u8>);
+
+        impl<'de> Deserialize<'de> for FileData {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                deserializer.deserialize_byte_buf(FileDataVisitor)
+            }
+        }
+
+        struct FileDataVisitor;
+
+        impl<'de> Visitor<'de> for FileDataVisitor {
+            type Value = FileData;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("file data")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<FileData, E> {
+                Ok(FileData(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<FileData, E> {
+                Ok(FileData(v))
+            }
+        }
+
+        struct FileFieldsVisitor;
+
+        impl<'de> Visitor<'de> for FileFieldsVisitor {
+            type Value = FileFields;
+
+            fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                f.write_str("a file field")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<FileFields, A::Error> {
+                let mut file = FileFields {
+                    file_name: None,
+                    content_type: None,
+                    data: Bytes::new(),
+                    path: None,
+                };
+
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        FILE_NAME => file.file_name = Some(map.next_value()?),
+                        CONTENT_TYPE => {
+                            let content_type: String = map.next_value()?;
+                            file.content_type = Some(content_type.parse().map_err(de::Error::custom)?);
+                        }
+                        DATA => file.data = map.next_value::<FileData>()?.0.into(),
+                        PATH => file.path = Some(map.next_value()?),
+                        _ => map.next_value::<de::IgnoredAny>().map(drop)?,
+                    }
+                }
+
+                Ok(file)
+            }
+        }
+
+        deserializer.deserialize_struct("FormFile", &[FILE_NAME, CONTENT_TYPE, DATA, PATH], FileFieldsVisitor)
+    }
+}
+
+impl<'r> Multipart<'r> {
+    /// Reads all the fields and deserializes them into `T`, e.g. a struct
+    /// deriving [`Deserialize`].
+    ///
+    /// Each field is deserialized from the text of the field of the same
+    /// name, parsed for numbers and booleans, or from its data for a
+    /// [`FormFile`]. A field of a sequence type collects all the fields of the
+    /// same name, and the last one is used otherwise. Fields without a name
+    /// are ignored.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `serde` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{FormFile, Multipart};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Upload {
+    ///     title: String,
+    ///     tags: Vec<String>,
+    ///     public: Option<bool>,
+    ///     file: FormFile,
+    /// }
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nHoliday\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tags\"\r\n\r\nbeach\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tags\"\r\n\r\nsun\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\r\nabcd\r\n\
+    ///     --X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let upload: Upload = multipart.deserialize().await.unwrap();
+    /// assert_eq!(upload.title, "Holiday");
+    /// assert_eq!(upload.tags, ["beach", "sun"]);
+    /// assert_eq!(upload.public, None);
+    /// assert_eq!(upload.file.file_name(), Some("a.txt"));
+    /// assert_eq!(upload.file.data(), "abcd");
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::DecodeForm`] if the fields can't be
+    /// deserialized into `T`.
+    #[cfg_attr(nightly, doc(cfg(feature = "serde")))]
+    pub async fn deserialize<T: DeserializeOwned>(&mut self) -> crate::Result<T> {
+        let mut fields = Fields::default();
+        self.read_fields(&mut fields).await?;
+        fields.deserialize()
+    }
+
+    /// Same as [`deserialize()`](Self::deserialize), but the data of each file
+    /// field is spooled with [`Field::spool()`]: it is spilled to a temporary
+    /// file if it exceeds `threshold` bytes.
+    ///
+    /// File fields are deserialized into a [`SpooledFormFile`] then, a
+    /// [`FormFile`] only accepts the files kept in memory.
+    ///
+    /// The temporary files of the fields which are not deserialized into `T`
+    /// are removed, and so are all of them if this method fails.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `serde` and `tokio-fs` features to be
+    /// enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{Multipart, SpooledField, SpooledFormFile};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Upload {
+    ///     title: String,
+    ///     file: SpooledFormFile,
+    /// }
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nHoliday\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\r\nabcd\r\n\
+    ///     --X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let upload: Upload = multipart.deserialize_spooled(2).await.unwrap();
+    /// assert_eq!(upload.title, "Holiday");
+    ///
+    /// if let SpooledField::File(path) = upload.file.into_data() {
+    ///     assert_eq!(tokio::fs::read(&path).await.unwrap(), b"abcd");
+    ///     tokio::fs::remove_file(path).await.unwrap();
+    /// }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::DecodeForm`] if the fields can't be
+    /// deserialized into `T`.
+    #[cfg(feature = "tokio-fs")]
+    #[cfg_attr(nightly, doc(cfg(all(feature = "serde", feature = "tokio-fs"))))]
+    pub async fn deserialize_spooled<T: DeserializeOwned>(&mut self, threshold: usize) -> crate::Result<T> {
+        let mut fields = Fields {
+            spool_threshold: Some(threshold),
+            ..Fields::default()
+        };
+
+        let result = self.read_fields(&mut fields).await;
+        let spooled = std::mem::take(&mut fields.spooled);
+        let result = result.and_then(|()| fields.deserialize());
+
+        for (path, claimed) in spooled {
+            if result.is_err() || !claimed.load(Ordering::Relaxed) {
+                let _ = tokio::fs::remove_file(path).await;
+            }
+        }
+
+        result
+    }
+
+    async fn read_fields(&mut self, fields: &mut Fields) -> crate::Result<()> {
+        while let Some(field) = self.next_field().await? {
+            let name = match field.name() {
+                Some(name) => name.to_owned(),
+                None => continue,
+            };
+
+            let value = match field.file_name() {
+                Some(file_name) => {
+                    let file = FormFile {
+                        file_name: Some(file_name.to_owned()),
+                        content_type: field.content_type().cloned(),
+                        data: Bytes::new(),
+                    };
+                    fields.read_file(field, file).await?
+                }
+                None => Value::Text(field.text().await?),
+            };
+
+            fields.push(name, value);
+        }
+
+        Ok(())
+    }
+}
+
+/// The values of the fields read, grouped by name in the order the names
+/// first appeared.
+#[derive(Default)]
+struct Fields {
+    values: Vec<(String, Vec<Value>)>,
+    index: HashMap<String, usize>,
+    #[cfg(feature = "tokio-fs")]
+    spool_threshold: Option<usize>,
+    /// The temporary files the file fields were spilled to, with whether they
+    /// were deserialized.
+    #[cfg(feature = "tokio-fs")]
+    spooled: Vec<(PathBuf, Arc<AtomicBool>)>,
+}
+
+impl Fields {
+    fn push(&mut self, name: String, value: Value) {
+        match self.index.get(&name) {
+            Some(&idx) => self.values[idx].1.push(value),
+            None => {
+                self.index.insert(name.clone(), self.values.len());
+                self.values.push((name, vec![value]));
+            }
+        }
+    }
+
+    #[cfg(not(feature = "tokio-fs"))]
+    async fn read_file(&mut self, field: Field<'_>, file: FormFile) -> crate::Result<Value> {
+        Ok(Value::File(FormFile {
+            data: field.bytes().await?,
+            ..file
+        }))
+    }
+
+    #[cfg(feature = "tokio-fs")]
+    async fn read_file(&mut self, field: Field<'_>, file: FormFile) -> crate::Result<Value> {
+        let threshold = match self.spool_threshold {
+            Some(threshold) => threshold,
+            None => {
+                return Ok(Value::File(FormFile {
+                    data: field.bytes().await?,
+                    ..file
+                }))
+            }
+        };
+
+        match field.spool(threshold).await? {
+            SpooledField::Memory(data) => Ok(Value::File(FormFile { data, ..file })),
+            SpooledField::File(path) => {
+                let claimed = Arc::new(AtomicBool::new(false));
+                self.spooled.push((path.clone(), Arc::clone(&claimed)));
+                Ok(Value::Spooled(file, path, claimed))
+            }
+        }
+    }
+
+    fn deserialize<T: DeserializeOwned>(self) -> crate::Result<T> {
+        let form = FormAccess {
+            fields: self.values.into_iter(),
+            field_name: None,
+        };
+        T::deserialize(FormDeserializer(form)).map_err(|err| Error::DecodeForm {
+            field_name: err.field_name,
+            reason: err.reason,
+        })
+    }
+}
+
+/// The error raised while deserializing a form.
+#[derive(Debug)]
+struct DeError {
+    field_name: Option<String>,
+    reason: String,
+}
+
+impl Display for DeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.reason)
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: Display>(msg: T) -> Self {
+        DeError {
+            field_name: None,
+            reason: msg.to_string(),
+        }
+    }
+}
+
+/// The value of a single field.
+enum Value {
+    Text(String),
+    File(FormFile),
+    /// A file field spilled to the temporary file at this path, with whether
+    /// the path was deserialized.
+    #[cfg(feature = "tokio-fs")]
+    Spooled(FormFile, PathBuf, Arc<AtomicBool>),
+}
+
+struct FormDeserializer(FormAccess);
+
+impl<'de> Deserializer<'de> for FormDeserializer {
+    type Error = DeError;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_map(self.0)
+    }
+}
+
+/// The fields of the form, with the values of the fields of the same name.
+struct FormAccess {
+    fields: std::vec::IntoIter<(String, Vec<Value>)>,
+    field_name: Option<String>,
+}
+
+impl<'de> MapAccess<'de> for FormAccess {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, DeError> {
+        let name = match self.fields.as_slice().first() {
+            Some((name, _)) => name,
+            None => return Ok(None),
+        };
+
+        self.field_name = Some(name.clone());
+        seed.deserialize(name.as_str().into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DeError> {
+        let (_, values) = self
+            .fields
+            .next()
+            .ok_or_else(|| de::Error::custom("value is missing"))?;
+
+        seed.deserialize(Values(values)).map_err(|mut err| {
+            err.field_name = err.field_name.or_else(|| self.field_name.take());
+            err
+        })
+    }
+}
+
+/// The values of the fields of the same name.
+struct Values(Vec<Value>);
+
+impl Values {
+    fn last(mut self) -> Result<Value, DeError> {
+        self.0.pop().ok_or_else(|| de::Error::custom("value is missing"))
+    }
+}
+
+macro_rules! forward_to_last {
+    ($($method:ident)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                self.last()?.$method(visitor)
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Values {
+    type Error = DeError;
+
+    forward_to_last! {
+        deserialize_any deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32
+        deserialize_i64 deserialize_i128 deserialize_u8 deserialize_u16 deserialize_u32
+        deserialize_u64 deserialize_u128 deserialize_f32 deserialize_f64 deserialize_char
+        deserialize_str deserialize_string deserialize_bytes deserialize_byte_buf
+        deserialize_unit deserialize_map deserialize_identifier deserialize_ignored_any
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(self, name: &'static str, visitor: V) -> Result<V::Value, DeError> {
+        self.last()?.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _: &'static str, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_seq(SeqDeserializer::new(self.0.into_iter()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _: usize, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.last()?.deserialize_struct(name, fields, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.last()?.deserialize_enum(name, variants, visitor)
+    }
+}
+
+impl<'de> IntoDeserializer<'de, DeError> for Value {
+    type Deserializer = Value;
+
+    fn into_deserializer(self) -> Value {
+        self
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+                match self {
+                    Value::Text(text) => visitor.$visit(text.parse().map_err(de::Error::custom)?),
+                    file => file.deserialize_any(visitor),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = DeError;
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_i128 => visit_i128,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_u128 => visit_u128,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    forward_to_deserialize_any! {
+        str string unit unit_struct tuple tuple_struct map struct identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self {
+            Value::Text(text) => visitor.visit_string(text),
+            Value::File(file) => visitor.visit_map(FileAccess::new(
+                file.file_name,
+                file.content_type,
+                DATA,
+                FileEntry::Data(file.data),
+            )),
+            #[cfg(feature = "tokio-fs")]
+            Value::Spooled(file, path, claimed) => visitor.visit_map(FileAccess::new(
+                file.file_name,
+                file.content_type,
+                PATH,
+                FileEntry::Path(path, claimed),
+            )),
+        }
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self {
+            Value::Text(text) => visitor.visit_byte_buf(text.into_bytes()),
+            Value::File(file) => visitor.visit_byte_buf(file.data.into()),
+            #[cfg(feature = "tokio-fs")]
+            Value::Spooled(..) => Err(de::Error::custom("file data was spilled to a temporary file")),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(self, _: &'static str, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_seq(SeqDeserializer::new(std::iter::once(self)))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _: &'static str,
+        _: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        match self {
+            Value::Text(text) => visitor.visit_enum(text.into_deserializer()),
+            file => file.deserialize_any(visitor),
+        }
+    }
+}
+
+/// An entry of the map a [`FormFile`] is deserialized from.
+enum FileEntry {
+    Text(String),
+    Data(Bytes),
+    #[cfg(feature = "tokio-fs")]
+    Path(PathBuf, Arc<AtomicBool>),
+}
+
+struct FileAccess {
+    entries: std::vec::IntoIter<(&'static str, FileEntry)>,
+    value: Option<FileEntry>,
+}
+
+impl FileAccess {
+    fn new(
+        file_name: Option<String>,
+        content_type: Option<mime::Mime>,
+        data_key: &'static str,
+        data: FileEntry,
+    ) -> FileAccess {
+        let mut entries = Vec::with_capacity(3);
+        if let Some(file_name) = file_name {
+            entries.push((FILE_NAME, FileEntry::Text(file_name)));
+        }
+        if let Some(content_type) = content_type {
+            entries.push((CONTENT_TYPE, FileEntry::Text(content_type.to_string())));
+        }
+        entries.push((data_key, data));
+
+        FileAccess {
+            entries: entries.into_iter(),
+            value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for FileAccess {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, DeError> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DeError> {
+        match self.value.take() {
+            Some(FileEntry::Text(text)) => seed.deserialize(text.into_deserializer()),
+            Some(FileEntry::Data(data)) => seed.deserialize(DataDeserializer(data)),
+            #[cfg(feature = "tokio-fs")]
+            Some(FileEntry::Path(path, claimed)) => seed.deserialize(PathDeserializer(path, claimed)),
+            None => Err(de::Error::custom("value is missing")),
+        }
+    }
+}
+
+/// The data of a [`FormFile`].
+struct DataDeserializer(Bytes);
+
+impl<'de> Deserializer<'de> for DataDeserializer {
+    type Error = DeError;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_byte_buf(self.0.into())
+    }
+}
+
+/// The path of the temporary file a file field was spilled to, which is
+/// marked as claimed unless it is ignored.
+#[cfg(feature = "tokio-fs")]
+struct PathDeserializer(PathBuf, Arc<AtomicBool>);
+
+#[cfg(feature = "tokio-fs")]
+impl<'de> Deserializer<'de> for PathDeserializer {
+    type Error = DeError;
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        let path = self
+            .0
+            .into_os_string()
+            .into_string()
+            .map_err(|path| de::Error::custom(format!("invalid temporary file path {:?}", path)))?;
+
+        self.1.store(true, Ordering::Relaxed);
+        visitor.visit_string(path)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+}
@@ -1,5 +1,7 @@
 use std::fmt::{self, Debug, Display, Formatter};
 
+use crate::violation::{Constraint, ConstraintViolation};
+
 type BoxError = Box<dyn std::error::Error + Send + Sync>;
 
 /// A set of errors that can occur during parsing multipart stream and in other
@@ -10,6 +12,60 @@ pub enum Error {
     /// [`constraints`](crate::Constraints::allowed_fields) are added.
     UnknownField { field_name: Option<String> },
 
+    /// A field repeats the name of a previous field while
+    /// [`Constraints::reject_duplicate_fields()`](crate::Constraints::reject_duplicate_fields)
+    /// is set.
+    DuplicateField { field_name: String },
+
+    /// A field declared with
+    /// [`Constraints::file_fields()`](crate::Constraints::file_fields) lacks
+    /// a `filename` parameter or a `Content-Type`.
+    NotAFile { field_name: String },
+
+    /// The stream ended without the fields required with
+    /// [`Constraints::required_fields()`](crate::Constraints::required_fields).
+    MissingRequiredFields { field_names: Vec<String> },
+
+    /// The data of a file field doesn't start with the signature of its
+    /// declared `Content-Type` while
+    /// [`Constraints::sniff_content_type()`](crate::Constraints::sniff_content_type)
+    /// is set.
+    ContentTypeMismatch {
+        field_name: Option<String>,
+        declared: String,
+        detected: Option<String>,
+    },
+
+    /// A field carrying a file name lacks a `Content-Type` required by the
+    /// [`FileContentTypePolicy`](crate::FileContentTypePolicy) set with
+    /// [`Constraints::file_content_type()`](crate::Constraints::file_content_type),
+    /// or declares `application/octet-stream` if it's not accepted.
+    MissingFileContentType { field_name: Option<String> },
+
+    /// A field's `Content-Type` is not one of those allowed with
+    /// [`Constraints::allowed_content_types()`](crate::Constraints::allowed_content_types).
+    ContentTypeNotAllowed {
+        field_name: Option<String>,
+        content_type: Option<String>,
+    },
+
+    /// A field's file name has an extension rejected by the
+    /// [`ExtensionPolicy`](crate::ExtensionPolicy) set with
+    /// [`Constraints::file_extensions()`](crate::Constraints::file_extensions).
+    FileExtensionNotAllowed {
+        field_name: Option<String>,
+        file_name: String,
+    },
+
+    /// A field's `Content-Disposition` type is not `form-data`, or the header
+    /// is missing, while
+    /// [`Constraints::require_form_data()`](crate::Constraints::require_form_data)
+    /// is set.
+    InvalidDispositionType {
+        field_name: Option<String>,
+        disposition_type: Option<String>,
+    },
+
     /// The field data is found incomplete.
     IncompleteFieldData { field_name: Option<String> },
 
@@ -27,15 +83,164 @@ pub enum Error {
     /// [`HeaderValue`](http::header::HeaderValue) type.
     DecodeHeaderValue { value: Vec<u8>, cause: BoxError },
 
+    /// A field repeats a `Content-Disposition`, `Content-Type` or
+    /// `Content-Transfer-Encoding` header while
+    /// [`DuplicateHeaderPolicy::Reject`](crate::DuplicateHeaderPolicy::Reject)
+    /// is set.
+    DuplicateHeader { name: String },
+
+    /// A field declares a `Content-Transfer-Encoding` that can't be decoded
+    /// while [`Constraints::reject_unknown_transfer_encodings()`](crate::Constraints::reject_unknown_transfer_encodings)
+    /// is set.
+    UnknownTransferEncoding {
+        field_name: Option<String>,
+        encoding: String,
+    },
+
+    /// Failed to decode the field data according to its
+    /// `Content-Transfer-Encoding`.
+    DecodeTransferEncoding {
+        field_name: Option<String>,
+        cause: BoxError,
+    },
+
+    /// Failed to decompress the field data according to its
+    /// `Content-Encoding`.
+    #[cfg(feature = "content-encoding")]
+    #[cfg_attr(nightly, doc(cfg(feature = "content-encoding")))]
+    DecodeContentEncoding {
+        field_name: Option<String>,
+        cause: BoxError,
+    },
+
+    /// The decompressed field data exceeded the limit set with
+    /// [`Constraints::decompressed_size_limit()`](crate::Constraints::decompressed_size_limit).
+    #[cfg(feature = "content-encoding")]
+    #[cfg_attr(nightly, doc(cfg(feature = "content-encoding")))]
+    DecompressedSizeExceeded { limit: u64, field_name: Option<String> },
+
+    /// A validator added with
+    /// [`Constraints::validate()`](crate::Constraints::validate) rejected a
+    /// field.
+    ValidationFailed {
+        field_name: Option<String>,
+        cause: BoxError,
+    },
+
+    /// A [`FieldInspector`](crate::FieldInspector) vetoed a field with
+    /// [`Veto::Field`](crate::Veto::Field).
+    FieldRejected {
+        field_name: Option<String>,
+        cause: BoxError,
+    },
+
+    /// A [`FieldInspector`](crate::FieldInspector) vetoed the whole request
+    /// with [`Veto::Request`](crate::Veto::Request).
+    RequestRejected {
+        field_name: Option<String>,
+        cause: BoxError,
+    },
+
+    /// Failed to parse the field data in
+    /// [`field.parse()`](crate::Field::parse).
+    ParseFailed {
+        field_name: Option<String>,
+        cause: BoxError,
+    },
+
+    /// The multipart body is technically invalid according to RFC 7578, while
+    /// [`Constraints::strict()`](crate::Constraints::strict) is set.
+    StrictViolation { field_name: Option<String>, reason: String },
+
+    /// A boundary delimiter line is followed by whitespace while
+    /// [`Constraints::reject_transport_padding()`](crate::Constraints::reject_transport_padding)
+    /// is set.
+    UnexpectedTransportPadding,
+
+    /// A nested multipart body would exceed the depth limit set with
+    /// [`Constraints::max_depth()`](crate::Constraints::max_depth).
+    NestingDepthExceeded { depth: usize, limit: usize },
+
     /// Multipart stream is incomplete.
     IncompleteStream,
 
+    /// The size of the field data differs from its `Content-Length` header
+    /// while [`Constraints::verify_content_length()`](crate::Constraints::verify_content_length)
+    /// is set. The actual size is as far as it was read when the mismatch was
+    /// detected.
+    ContentLengthMismatch {
+        field_name: Option<String>,
+        expected: u64,
+        actual: u64,
+    },
+
+    /// The stream has more fields than allowed with
+    /// [`SizeLimit::max_fields()`](crate::SizeLimit::max_fields).
+    FieldCountExceeded { limit: usize },
+
+    /// The stream has more fields carrying a file name than allowed with
+    /// [`SizeLimit::max_files()`](crate::SizeLimit::max_files).
+    FileCountExceeded { limit: usize },
+
+    /// A file name is longer than allowed with
+    /// [`SizeLimit::file_name_bytes()`](crate::SizeLimit::file_name_bytes) or
+    /// [`SizeLimit::file_name_chars()`](crate::SizeLimit::file_name_chars).
+    FileNameTooLong { limit: usize, field_name: Option<String> },
+
+    /// A field carrying a file name has no data while
+    /// [`Constraints::reject_empty_files()`](crate::Constraints::reject_empty_files)
+    /// is set.
+    EmptyFile { field_name: Option<String> },
+
     /// The incoming field size exceeded the maximum limit.
     FieldSizeExceeded { limit: u64, field_name: Option<String> },
 
+    /// The header section of an incoming field exceeded the maximum limit.
+    HeadersSizeExceeded { limit: u64 },
+
     /// The incoming stream size exceeded the maximum limit.
     StreamSizeExceeded { limit: u64 },
 
+    /// The preamble is larger than allowed with
+    /// [`SizeLimit::preamble()`](crate::SizeLimit::preamble).
+    PreambleSizeExceeded { limit: u64 },
+
+    /// The epilogue is larger than allowed with
+    /// [`SizeLimit::epilogue()`](crate::SizeLimit::epilogue), with the size of
+    /// the epilogue read so far.
+    EpilogueSizeExceeded { limit: u64, size: u64 },
+
+    /// The [`MemoryBudget`](crate::MemoryBudget) set with
+    /// [`Constraints::memory_budget()`](crate::Constraints::memory_budget) has
+    /// no room left for the next chunk of the stream, with the number of bytes
+    /// the budget would have been used up to.
+    MemoryBudgetExceeded { limit: u64, requested: u64 },
+
+    /// The stream delivered data slower than allowed with
+    /// [`Constraints::min_throughput()`](crate::Constraints::min_throughput).
+    TooSlow {
+        min_bytes: u64,
+        window: std::time::Duration,
+    },
+
+    /// The stream has not been read in full within the deadline set with
+    /// [`Constraints::deadline()`](crate::Constraints::deadline).
+    #[cfg(feature = "tokio-time")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-time")))]
+    DeadlineExceeded { timeout: std::time::Duration },
+
+    /// The stream yielded no data for longer than allowed with
+    /// [`Constraints::read_timeout()`](crate::Constraints::read_timeout).
+    #[cfg(feature = "tokio-time")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-time")))]
+    ReadTimeout { timeout: std::time::Duration },
+
+    /// The parse was aborted with the token set with
+    /// [`Constraints::cancellation_token()`](crate::Constraints::cancellation_token).
+    #[cfg(feature = "cancellation")]
+    #[cfg_attr(nightly, doc(cfg(feature = "cancellation")))]
+    Cancelled,
+
     /// Stream read failed.
     StreamReadFailed(BoxError),
 
@@ -51,11 +256,268 @@ pub enum Error {
     /// No boundary found in `Content-Type` header.
     NoBoundary,
 
+    /// The boundary doesn't conform to the RFC 2046 grammar, with the
+    /// offending character and its byte position, or `None` if the boundary
+    /// is empty or longer than 70 characters.
+    InvalidBoundary {
+        boundary: String,
+        character: Option<char>,
+        position: Option<usize>,
+    },
+
+    /// Failed to write the field data to its destination.
+    WriteFailed(std::io::Error),
+
+    /// The sink given to [`field.forward_to()`](crate::Field::forward_to)
+    /// failed to accept the field data.
+    #[cfg(feature = "sink")]
+    #[cfg_attr(nightly, doc(cfg(feature = "sink")))]
+    ForwardFailed(BoxError),
+
+    /// A field required by an S3 browser-based `POST` upload is missing or
+    /// appears after the `file` field, in
+    /// [`multipart.s3_post_form()`](crate::Multipart::s3_post_form).
+    MissingS3PostField { field_name: String },
+
+    /// The root part of a `multipart/related` body could not be found in
+    /// [`related.parts()`](crate::RelatedMultipart::parts), either because no
+    /// part carries the `Content-ID` given by the `start` parameter or because
+    /// the body has no parts.
+    MissingRelatedRoot { content_id: Option<String> },
+
     /// Failed to decode the field data as `JSON` in
     /// [`field.json()`](crate::Field::json) method.
     #[cfg(feature = "json")]
     #[cfg_attr(nightly, doc(cfg(feature = "json")))]
     DecodeJson(serde_json::Error),
+
+    /// Failed to deserialize the fields in `multipart.deserialize()` or into
+    /// a struct deriving `FromMultipart`, with the name of the offending field
+    /// if known.
+    #[cfg(any(feature = "serde", feature = "derive"))]
+    #[cfg_attr(nightly, doc(cfg(any(feature = "serde", feature = "derive"))))]
+    DecodeForm { field_name: Option<String>, reason: String },
+}
+
+impl Error {
+    /// The details of the violated constraint, if the error is caused by a
+    /// rule of [`Constraints`](crate::Constraints) or a limit of
+    /// [`SizeLimit`](crate::SizeLimit), as opposed to a malformed or failing
+    /// stream.
+    pub fn violation(&self) -> Option<ConstraintViolation> {
+        let violation = match self {
+            Error::StreamSizeExceeded { limit } => {
+                ConstraintViolation::new(Constraint::WholeStreamSize).with_limit(*limit)
+            }
+            Error::FieldSizeExceeded { limit, field_name } => ConstraintViolation::new(Constraint::FieldSize)
+                .with_field_name(field_name.as_deref())
+                .with_limit(*limit),
+            Error::HeadersSizeExceeded { limit } => {
+                ConstraintViolation::new(Constraint::FieldHeadersSize).with_limit(*limit)
+            }
+            Error::PreambleSizeExceeded { limit } => {
+                ConstraintViolation::new(Constraint::PreambleSize).with_limit(*limit)
+            }
+            Error::EpilogueSizeExceeded { limit, size } => ConstraintViolation::new(Constraint::EpilogueSize)
+                .with_limit(*limit)
+                .with_observed(*size),
+            Error::MemoryBudgetExceeded { limit, requested } => ConstraintViolation::new(Constraint::MemoryBudget)
+                .with_limit(*limit)
+                .with_observed(*requested),
+            Error::FieldCountExceeded { limit } => ConstraintViolation::new(Constraint::FieldCount)
+                .with_limit(*limit)
+                .with_observed(limit.saturating_add(1)),
+            Error::FileCountExceeded { limit } => ConstraintViolation::new(Constraint::FileCount)
+                .with_limit(*limit)
+                .with_observed(limit.saturating_add(1)),
+            Error::FileNameTooLong { limit, field_name } => ConstraintViolation::new(Constraint::FileNameLength)
+                .with_field_name(field_name.as_deref())
+                .with_limit(*limit),
+            Error::EmptyFile { field_name } => {
+                ConstraintViolation::new(Constraint::EmptyFiles).with_field_name(field_name.as_deref())
+            }
+            #[cfg(feature = "content-encoding")]
+            Error::DecompressedSizeExceeded { limit, field_name } => {
+                ConstraintViolation::new(Constraint::DecompressedSize)
+                    .with_field_name(field_name.as_deref())
+                    .with_limit(*limit)
+            }
+            Error::ContentLengthMismatch {
+                field_name,
+                expected,
+                actual,
+            } => ConstraintViolation::new(Constraint::ContentLength)
+                .with_field_name(field_name.as_deref())
+                .with_limit(*expected)
+                .with_observed(*actual),
+            Error::NestingDepthExceeded { depth, limit } => ConstraintViolation::new(Constraint::NestingDepth)
+                .with_limit(*limit)
+                .with_observed(*depth),
+            Error::TooSlow { min_bytes, .. } => {
+                ConstraintViolation::new(Constraint::MinThroughput).with_limit(*min_bytes)
+            }
+            #[cfg(feature = "tokio-time")]
+            Error::DeadlineExceeded { timeout } => {
+                ConstraintViolation::new(Constraint::Deadline).with_limit(timeout.as_millis())
+            }
+            #[cfg(feature = "tokio-time")]
+            Error::ReadTimeout { timeout } => {
+                ConstraintViolation::new(Constraint::ReadTimeout).with_limit(timeout.as_millis())
+            }
+            Error::UnknownField { field_name } => {
+                ConstraintViolation::new(Constraint::AllowedFields).with_field_name(field_name.as_deref())
+            }
+            Error::MissingRequiredFields { .. } => ConstraintViolation::new(Constraint::RequiredFields),
+            Error::DuplicateField { field_name } => {
+                ConstraintViolation::new(Constraint::DuplicateFields).with_field_name(Some(field_name))
+            }
+            Error::NotAFile { field_name } => {
+                ConstraintViolation::new(Constraint::FileFields).with_field_name(Some(field_name))
+            }
+            Error::MissingFileContentType { field_name } => {
+                ConstraintViolation::new(Constraint::FileContentType).with_field_name(field_name.as_deref())
+            }
+            Error::ContentTypeNotAllowed { field_name, .. } => {
+                ConstraintViolation::new(Constraint::AllowedContentTypes).with_field_name(field_name.as_deref())
+            }
+            Error::ContentTypeMismatch { field_name, .. } => {
+                ConstraintViolation::new(Constraint::ContentTypeSignature).with_field_name(field_name.as_deref())
+            }
+            Error::FileExtensionNotAllowed { field_name, .. } => {
+                ConstraintViolation::new(Constraint::FileExtensions).with_field_name(field_name.as_deref())
+            }
+            Error::InvalidDispositionType { field_name, .. } => {
+                ConstraintViolation::new(Constraint::FormData).with_field_name(field_name.as_deref())
+            }
+            Error::ValidationFailed { field_name, .. } => {
+                ConstraintViolation::new(Constraint::Validator).with_field_name(field_name.as_deref())
+            }
+            Error::FieldRejected { field_name, .. } | Error::RequestRejected { field_name, .. } => {
+                ConstraintViolation::new(Constraint::Inspector).with_field_name(field_name.as_deref())
+            }
+            _ => return None,
+        };
+
+        Some(violation)
+    }
+
+    /// Copies the error if it's the violation of a limit or a rule set with
+    /// [`Constraints`](crate::Constraints), i.e. if it has a
+    /// [`violation()`](Self::violation), as opposed to a malformed or failing
+    /// stream.
+    ///
+    /// The cause of an error raised by user code is copied as its message.
+    pub(crate) fn clone_violation(&self) -> Option<Error> {
+        let err = match self {
+            Error::UnknownField { field_name } => Error::UnknownField {
+                field_name: field_name.clone(),
+            },
+            Error::DuplicateField { field_name } => Error::DuplicateField {
+                field_name: field_name.clone(),
+            },
+            Error::NotAFile { field_name } => Error::NotAFile {
+                field_name: field_name.clone(),
+            },
+            Error::ContentTypeMismatch {
+                field_name,
+                declared,
+                detected,
+            } => Error::ContentTypeMismatch {
+                field_name: field_name.clone(),
+                declared: declared.clone(),
+                detected: detected.clone(),
+            },
+            Error::MissingFileContentType { field_name } => Error::MissingFileContentType {
+                field_name: field_name.clone(),
+            },
+            Error::ContentTypeNotAllowed {
+                field_name,
+                content_type,
+            } => Error::ContentTypeNotAllowed {
+                field_name: field_name.clone(),
+                content_type: content_type.clone(),
+            },
+            Error::FileExtensionNotAllowed { field_name, file_name } => Error::FileExtensionNotAllowed {
+                field_name: field_name.clone(),
+                file_name: file_name.clone(),
+            },
+            #[cfg(feature = "content-encoding")]
+            Error::DecompressedSizeExceeded { limit, field_name } => Error::DecompressedSizeExceeded {
+                limit: *limit,
+                field_name: field_name.clone(),
+            },
+            Error::FieldCountExceeded { limit } => Error::FieldCountExceeded { limit: *limit },
+            Error::FileCountExceeded { limit } => Error::FileCountExceeded { limit: *limit },
+            Error::FileNameTooLong { limit, field_name } => Error::FileNameTooLong {
+                limit: *limit,
+                field_name: field_name.clone(),
+            },
+            Error::EmptyFile { field_name } => Error::EmptyFile {
+                field_name: field_name.clone(),
+            },
+            Error::FieldSizeExceeded { limit, field_name } => Error::FieldSizeExceeded {
+                limit: *limit,
+                field_name: field_name.clone(),
+            },
+            Error::HeadersSizeExceeded { limit } => Error::HeadersSizeExceeded { limit: *limit },
+            Error::StreamSizeExceeded { limit } => Error::StreamSizeExceeded { limit: *limit },
+            Error::PreambleSizeExceeded { limit } => Error::PreambleSizeExceeded { limit: *limit },
+            Error::EpilogueSizeExceeded { limit, size } => Error::EpilogueSizeExceeded {
+                limit: *limit,
+                size: *size,
+            },
+            Error::MemoryBudgetExceeded { limit, requested } => Error::MemoryBudgetExceeded {
+                limit: *limit,
+                requested: *requested,
+            },
+            Error::ContentLengthMismatch {
+                field_name,
+                expected,
+                actual,
+            } => Error::ContentLengthMismatch {
+                field_name: field_name.clone(),
+                expected: *expected,
+                actual: *actual,
+            },
+            Error::NestingDepthExceeded { depth, limit } => Error::NestingDepthExceeded {
+                depth: *depth,
+                limit: *limit,
+            },
+            Error::TooSlow { min_bytes, window } => Error::TooSlow {
+                min_bytes: *min_bytes,
+                window: *window,
+            },
+            #[cfg(feature = "tokio-time")]
+            Error::DeadlineExceeded { timeout } => Error::DeadlineExceeded { timeout: *timeout },
+            #[cfg(feature = "tokio-time")]
+            Error::ReadTimeout { timeout } => Error::ReadTimeout { timeout: *timeout },
+            Error::MissingRequiredFields { field_names } => Error::MissingRequiredFields {
+                field_names: field_names.clone(),
+            },
+            Error::InvalidDispositionType {
+                field_name,
+                disposition_type,
+            } => Error::InvalidDispositionType {
+                field_name: field_name.clone(),
+                disposition_type: disposition_type.clone(),
+            },
+            Error::ValidationFailed { field_name, cause } => Error::ValidationFailed {
+                field_name: field_name.clone(),
+                cause: cause.to_string().into(),
+            },
+            Error::FieldRejected { field_name, cause } => Error::FieldRejected {
+                field_name: field_name.clone(),
+                cause: cause.to_string().into(),
+            },
+            Error::RequestRejected { field_name, cause } => Error::RequestRejected {
+                field_name: field_name.clone(),
+                cause: cause.to_string().into(),
+            },
+            _ => return None,
+        };
+
+        Some(err)
+    }
 }
 
 impl Debug for Error {
@@ -71,6 +533,61 @@ impl Display for Error {
                 let name = field_name.as_deref().unwrap_or("<unknown>");
                 write!(f, "unknown field received: {:?}", name)
             }
+            Error::DuplicateField { field_name } => {
+                write!(f, "duplicate field received: {:?}", field_name)
+            }
+            Error::NotAFile { field_name } => {
+                write!(
+                    f,
+                    "field {:?} is not a file with a file name and a content type",
+                    field_name
+                )
+            }
+            Error::ContentTypeMismatch {
+                field_name,
+                declared,
+                detected,
+            } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "field {:?} is not of its declared type {:?}", name, declared)?;
+                match detected {
+                    Some(detected) => write!(f, ", it looks like {:?}", detected),
+                    None => Ok(()),
+                }
+            }
+            Error::MissingRequiredFields { field_names } => {
+                write!(f, "missing required fields: {:?}", field_names)
+            }
+            Error::MissingFileContentType { field_name } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "file field {:?} has no specific content type", name)
+            }
+            Error::ContentTypeNotAllowed {
+                field_name,
+                content_type,
+            } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                let content_type = content_type.as_deref().unwrap_or("<none>");
+                write!(f, "field {:?} has disallowed content type: {:?}", name, content_type)
+            }
+            Error::FileExtensionNotAllowed { field_name, file_name } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "field {:?} has disallowed file extension: {:?}", name, file_name)
+            }
+            Error::InvalidDispositionType {
+                field_name,
+                disposition_type: Some(disposition_type),
+            } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(
+                    f,
+                    "field {:?} has disposition type {:?}, expected \"form-data\"",
+                    name, disposition_type
+                )
+            }
+            Error::InvalidDispositionType { .. } => {
+                write!(f, "field has no Content-Disposition header")
+            }
             Error::IncompleteFieldData { field_name } => {
                 let name = field_name.as_deref().unwrap_or("<unknown>");
                 write!(f, "field {:?} received with incomplete data", name)
@@ -81,23 +598,159 @@ impl Display for Error {
             Error::DecodeHeaderValue { .. } => {
                 write!(f, "failed to decode field's raw header value")
             }
+            Error::DuplicateHeader { name } => {
+                write!(f, "field has duplicate header: {:?}", name)
+            }
+            Error::UnknownTransferEncoding { field_name, encoding } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "field {:?} has unknown transfer encoding: {:?}", name, encoding)
+            }
+            Error::DecodeTransferEncoding { field_name, .. } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "failed to decode field {:?} transfer encoding", name)
+            }
+            #[cfg(feature = "content-encoding")]
+            Error::DecodeContentEncoding { field_name, .. } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "failed to decode field {:?} content encoding", name)
+            }
+            #[cfg(feature = "content-encoding")]
+            Error::DecompressedSizeExceeded { limit, field_name } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(
+                    f,
+                    "field {:?} exceeded the decompressed size limit: {} bytes",
+                    name, limit
+                )
+            }
+            Error::ValidationFailed { field_name, cause } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "field {:?} failed validation: {}", name, cause)
+            }
+            Error::FieldRejected { field_name, cause } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "field {:?} was rejected: {}", name, cause)
+            }
+            Error::RequestRejected { field_name, cause } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "request was rejected at field {:?}: {}", name, cause)
+            }
+            Error::ParseFailed { field_name, cause } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "failed to parse field {:?}: {}", name, cause)
+            }
+            Error::StrictViolation {
+                field_name: Some(name),
+                reason,
+            } => {
+                write!(f, "field {:?} violates RFC 7578: {}", name, reason)
+            }
+            Error::StrictViolation { reason, .. } => {
+                write!(f, "multipart body violates RFC 7578: {}", reason)
+            }
             Error::FieldSizeExceeded { limit, field_name } => {
                 let name = field_name.as_deref().unwrap_or("<unknown>");
                 write!(f, "field {:?} exceeded the size limit: {} bytes", name, limit)
             }
+            Error::ContentLengthMismatch {
+                field_name,
+                expected,
+                actual,
+            } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(
+                    f,
+                    "field {:?} size does not match its Content-Length: expected {} bytes, got {}",
+                    name, expected, actual
+                )
+            }
+            Error::NestingDepthExceeded { depth, limit } => {
+                write!(f, "nested multipart depth {} exceeded the limit: {}", depth, limit)
+            }
+            Error::FieldCountExceeded { limit } => {
+                write!(f, "stream exceeded the field count limit: {}", limit)
+            }
+            Error::FileCountExceeded { limit } => {
+                write!(f, "stream exceeded the file count limit: {}", limit)
+            }
+            Error::FileNameTooLong { limit, field_name } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "file name of field {:?} exceeded the length limit: {}", name, limit)
+            }
+            Error::EmptyFile { field_name } => {
+                let name = field_name.as_deref().unwrap_or("<unknown>");
+                write!(f, "field {:?} has an empty file", name)
+            }
+            Error::HeadersSizeExceeded { limit } => {
+                write!(f, "field headers exceeded the size limit: {} bytes", limit)
+            }
+            Error::PreambleSizeExceeded { limit } => {
+                write!(f, "preamble exceeded the size limit: {} bytes", limit)
+            }
+            Error::EpilogueSizeExceeded { limit, .. } => {
+                write!(f, "epilogue exceeded the size limit: {} bytes", limit)
+            }
+            Error::MemoryBudgetExceeded { limit, .. } => {
+                write!(f, "shared memory budget exhausted: {} bytes", limit)
+            }
+            Error::TooSlow { min_bytes, window } => {
+                write!(f, "stream is slower than {} bytes per {:?}", min_bytes, window)
+            }
+            #[cfg(feature = "tokio-time")]
+            Error::DeadlineExceeded { timeout } => {
+                write!(f, "stream was not read within the deadline of {:?}", timeout)
+            }
+            #[cfg(feature = "tokio-time")]
+            Error::ReadTimeout { timeout } => {
+                write!(f, "stream yielded no data for {:?}", timeout)
+            }
+            #[cfg(feature = "cancellation")]
+            Error::Cancelled => write!(f, "parsing was cancelled"),
             Error::StreamSizeExceeded { limit } => {
                 write!(f, "stream size exceeded limit: {} bytes", limit)
             }
+            Error::MissingS3PostField { field_name } => {
+                write!(f, "S3 POST form is missing field {:?} before the file", field_name)
+            }
+            Error::MissingRelatedRoot { content_id } => {
+                let id = content_id.as_deref().unwrap_or("<first>");
+                write!(f, "multipart/related root part not found: {:?}", id)
+            }
+            Error::InvalidBoundary {
+                boundary,
+                character: Some(character),
+                position: Some(position),
+            } => {
+                write!(
+                    f,
+                    "invalid character {:?} at position {} in boundary {:?}",
+                    character, position, boundary
+                )
+            }
+            Error::InvalidBoundary { boundary, .. } => {
+                write!(f, "boundary {:?} is not 1 to 70 characters long", boundary)
+            }
             Error::ReadHeaderFailed(_) => write!(f, "failed to read headers"),
             Error::StreamReadFailed(_) => write!(f, "failed to read stream"),
+            Error::WriteFailed(_) => write!(f, "failed to write field data"),
+            #[cfg(feature = "sink")]
+            Error::ForwardFailed(_) => write!(f, "failed to forward field data"),
             Error::DecodeContentType(_) => write!(f, "failed to decode Content-Type"),
             Error::IncompleteHeaders => write!(f, "failed to read field complete headers"),
             Error::IncompleteStream => write!(f, "incomplete multipart stream"),
+            Error::UnexpectedTransportPadding => write!(f, "boundary delimiter is followed by transport padding"),
             Error::LockFailure => write!(f, "failed to lock multipart state"),
             Error::NoMultipart => write!(f, "Content-Type is not multipart/form-data"),
             Error::NoBoundary => write!(f, "multipart boundary not found in Content-Type"),
             #[cfg(feature = "json")]
             Error::DecodeJson(_) => write!(f, "failed to decode field data as JSON"),
+            #[cfg(any(feature = "serde", feature = "derive"))]
+            Error::DecodeForm {
+                field_name: Some(name),
+                reason,
+            } => write!(f, "failed to deserialize field {:?}: {}", name, reason),
+            #[cfg(any(feature = "serde", feature = "derive"))]
+            Error::DecodeForm { reason, .. } => write!(f, "failed to deserialize form: {}", reason),
         }
     }
 }
@@ -108,19 +761,65 @@ impl std::error::Error for Error {
             Error::ReadHeaderFailed(e) => Some(e),
             Error::DecodeHeaderName { cause, .. } => Some(cause.as_ref()),
             Error::DecodeHeaderValue { cause, .. } => Some(cause.as_ref()),
+            Error::DecodeTransferEncoding { cause, .. } => Some(cause.as_ref()),
             Error::StreamReadFailed(e) => Some(e.as_ref()),
+            Error::WriteFailed(e) => Some(e),
+            #[cfg(feature = "sink")]
+            Error::ForwardFailed(e) => Some(e.as_ref()),
             Error::DecodeContentType(e) => Some(e),
+            Error::ValidationFailed { cause, .. } => Some(cause.as_ref()),
+            Error::FieldRejected { cause, .. } => Some(cause.as_ref()),
+            Error::RequestRejected { cause, .. } => Some(cause.as_ref()),
+            Error::ParseFailed { cause, .. } => Some(cause.as_ref()),
+            #[cfg(feature = "content-encoding")]
+            Error::DecodeContentEncoding { cause, .. } => Some(cause.as_ref()),
             #[cfg(feature = "json")]
             Error::DecodeJson(e) => Some(e),
             Error::UnknownField { .. }
+            | Error::DuplicateField { .. }
+            | Error::NotAFile { .. }
+            | Error::MissingRequiredFields { .. }
+            | Error::MissingFileContentType { .. }
+            | Error::ContentTypeNotAllowed { .. }
+            | Error::ContentTypeMismatch { .. }
+            | Error::FileExtensionNotAllowed { .. }
+            | Error::InvalidDispositionType { .. }
             | Error::IncompleteFieldData { .. }
             | Error::IncompleteHeaders
             | Error::IncompleteStream
+            | Error::UnexpectedTransportPadding
+            | Error::DuplicateHeader { .. }
+            | Error::UnknownTransferEncoding { .. }
+            | Error::StrictViolation { .. }
             | Error::FieldSizeExceeded { .. }
+            | Error::ContentLengthMismatch { .. }
+            | Error::HeadersSizeExceeded { .. }
+            | Error::FieldCountExceeded { .. }
+            | Error::FileCountExceeded { .. }
+            | Error::FileNameTooLong { .. }
+            | Error::EmptyFile { .. }
+            | Error::NestingDepthExceeded { .. }
             | Error::StreamSizeExceeded { .. }
+            | Error::PreambleSizeExceeded { .. }
+            | Error::EpilogueSizeExceeded { .. }
+            | Error::MemoryBudgetExceeded { .. }
+            | Error::TooSlow { .. }
             | Error::LockFailure
             | Error::NoMultipart
-            | Error::NoBoundary => None,
+            | Error::NoBoundary
+            | Error::InvalidBoundary { .. }
+            | Error::MissingS3PostField { .. }
+            | Error::MissingRelatedRoot { .. } => None,
+            #[cfg(feature = "content-encoding")]
+            Error::DecompressedSizeExceeded { .. } => None,
+            #[cfg(feature = "tokio-time")]
+            Error::DeadlineExceeded { .. } => None,
+            #[cfg(feature = "tokio-time")]
+            Error::ReadTimeout { .. } => None,
+            #[cfg(feature = "cancellation")]
+            Error::Cancelled => None,
+            #[cfg(any(feature = "serde", feature = "derive"))]
+            Error::DecodeForm { .. } => None,
         }
     }
 }
@@ -132,3 +831,114 @@ impl PartialEq for Error {
 }
 
 impl Eq for Error {}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_clone_violation() {
+        let name = || Some("a".to_owned());
+        let errors = vec![
+            Error::UnknownField { field_name: name() },
+            Error::DuplicateField {
+                field_name: "a".to_owned(),
+            },
+            Error::NotAFile {
+                field_name: "a".to_owned(),
+            },
+            Error::MissingRequiredFields {
+                field_names: vec!["a".to_owned()],
+            },
+            Error::ContentTypeMismatch {
+                field_name: name(),
+                declared: "image/png".to_owned(),
+                detected: None,
+            },
+            Error::MissingFileContentType { field_name: name() },
+            Error::ContentTypeNotAllowed {
+                field_name: name(),
+                content_type: None,
+            },
+            Error::FileExtensionNotAllowed {
+                field_name: name(),
+                file_name: "a.exe".to_owned(),
+            },
+            Error::InvalidDispositionType {
+                field_name: name(),
+                disposition_type: None,
+            },
+            #[cfg(feature = "content-encoding")]
+            Error::DecompressedSizeExceeded {
+                limit: 1,
+                field_name: name(),
+            },
+            Error::ValidationFailed {
+                field_name: name(),
+                cause: "invalid".into(),
+            },
+            Error::FieldRejected {
+                field_name: name(),
+                cause: "rejected".into(),
+            },
+            Error::RequestRejected {
+                field_name: name(),
+                cause: "rejected".into(),
+            },
+            Error::NestingDepthExceeded { depth: 2, limit: 1 },
+            Error::ContentLengthMismatch {
+                field_name: name(),
+                expected: 1,
+                actual: 2,
+            },
+            Error::FieldCountExceeded { limit: 1 },
+            Error::FileCountExceeded { limit: 1 },
+            Error::FileNameTooLong {
+                limit: 1,
+                field_name: name(),
+            },
+            Error::EmptyFile { field_name: name() },
+            Error::FieldSizeExceeded {
+                limit: 1,
+                field_name: name(),
+            },
+            Error::HeadersSizeExceeded { limit: 1 },
+            Error::StreamSizeExceeded { limit: 1 },
+            Error::PreambleSizeExceeded { limit: 1 },
+            Error::EpilogueSizeExceeded { limit: 1, size: 2 },
+            Error::MemoryBudgetExceeded { limit: 1, requested: 2 },
+            Error::TooSlow {
+                min_bytes: 1,
+                window: Duration::from_secs(1),
+            },
+            #[cfg(feature = "tokio-time")]
+            Error::DeadlineExceeded {
+                timeout: Duration::from_secs(1),
+            },
+            #[cfg(feature = "tokio-time")]
+            Error::ReadTimeout {
+                timeout: Duration::from_secs(1),
+            },
+            // Not violations.
+            Error::IncompleteStream,
+            Error::IncompleteHeaders,
+            Error::StreamReadFailed("failed".into()),
+            Error::LockFailure,
+            Error::ParseFailed {
+                field_name: name(),
+                cause: "failed".into(),
+            },
+        ];
+
+        for err in errors {
+            let copy = err.clone_violation();
+            assert_eq!(copy.is_some(), err.violation().is_some(), "{}", err);
+            if let Some(copy) = copy {
+                assert_eq!(copy, err);
+                assert_eq!(copy.violation(), err.violation());
+            }
+        }
+    }
+}
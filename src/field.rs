@@ -1,18 +1,28 @@
 use std::pin::Pin;
+use std::str::FromStr;
 use std::sync::Arc;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, Waker};
 
 use bytes::{Bytes, BytesMut};
 use encoding_rs::{Encoding, UTF_8};
 use futures_util::stream::{Stream, TryStreamExt};
-use http::header::HeaderMap;
+use http::header::{self, HeaderMap};
 #[cfg(feature = "json")]
 use serde::de::DeserializeOwned;
 use spin::mutex::spin::SpinMutex as Mutex;
 
+use crate::constants::ContentDispositionAttr;
+use crate::constraints::{Constraints, Inspector};
 use crate::content_disposition::ContentDisposition;
-use crate::multipart::{MultipartState, StreamingStage};
-use crate::{helpers, Error};
+#[cfg(feature = "content-encoding")]
+use crate::content_encoding::{ContentDecodeError, ContentDecoder};
+use crate::inspect::Veto;
+use crate::multipart::{report_progress, MultipartState, StreamingStage};
+#[cfg(any(feature = "tokio-io", feature = "futures-io"))]
+use crate::reader::FieldReader;
+use crate::sniff::ContentSniffer;
+use crate::transfer_encoding::TransferDecoder;
+use crate::{constants, helpers, Error, Multipart};
 
 /// A single field in a multipart stream.
 ///
@@ -54,10 +64,20 @@ use crate::{helpers, Error};
 pub struct Field<'r> {
     state: Arc<Mutex<MultipartState<'r>>>,
     done: bool,
+    truncated: bool,
     headers: HeaderMap,
     content_disposition: ContentDisposition,
     content_type: Option<mime::Mime>,
     idx: usize,
+    decoder: Option<TransferDecoder>,
+    bom_prefix: Option<Vec<u8>>,
+    sniffer: Option<ContentSniffer>,
+    inspector: Option<Inspector>,
+    #[cfg(feature = "content-encoding")]
+    content_decoder: Option<ContentDecoder>,
+    // Dropped after `state`, so that the waiting task is woken once the
+    // state has been released.
+    _release: FieldRelease,
 }
 
 impl<'r> Field<'r> {
@@ -66,8 +86,11 @@ impl<'r> Field<'r> {
         headers: HeaderMap,
         idx: usize,
         content_disposition: ContentDisposition,
+        decoder: Option<TransferDecoder>,
+        strip_bom: bool,
     ) -> Self {
         let content_type = helpers::parse_content_type(&headers);
+        let release = FieldRelease(state.lock().field_waker.clone());
         Field {
             state,
             headers,
@@ -75,10 +98,37 @@ impl<'r> Field<'r> {
             content_type,
             idx,
             done: false,
+            truncated: false,
+            decoder,
+            bom_prefix: strip_bom.then(Vec::new),
+            sniffer: None,
+            inspector: None,
+            #[cfg(feature = "content-encoding")]
+            content_decoder: None,
+            _release: release,
         }
     }
 
+    pub(crate) fn with_content_sniffer(mut self, sniffer: Option<ContentSniffer>) -> Self {
+        self.sniffer = sniffer;
+        self
+    }
+
+    pub(crate) fn with_inspector(mut self, inspector: Option<Inspector>) -> Self {
+        self.inspector = inspector;
+        self
+    }
+
+    #[cfg(feature = "content-encoding")]
+    pub(crate) fn with_content_decoder(mut self, content_decoder: Option<ContentDecoder>) -> Self {
+        self.content_decoder = content_decoder;
+        self
+    }
+
     /// The field name found in the [`Content-Disposition`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Disposition) header.
+    ///
+    /// With [`Constraints::positional_names()`] set, a field lacking a name
+    /// is named after its position instead, e.g. `part-3`.
     pub fn name(&self) -> Option<&str> {
         self.content_disposition.field_name.as_deref()
     }
@@ -88,11 +138,244 @@ impl<'r> Field<'r> {
         self.content_disposition.file_name.as_deref()
     }
 
+    /// The [`file_name()`](Self::file_name) without any directory components
+    /// or drive letter, e.g. `photo.jpg` for `C:\Users\me\photo.jpg` as sent
+    /// by old Internet Explorer versions and some WebViews.
+    ///
+    /// Returns `None` if no file name remains, e.g. for `C:\` or `..`. The
+    /// result is not otherwise sanitized and should still be validated before
+    /// being used as a path.
+    pub fn file_name_sanitized(&self) -> Option<&str> {
+        let file_name = self.file_name()?;
+
+        // Windows paths are usually sent with unescaped backslashes, which are
+        // lost when unescaping the quoted value, so look for them in the
+        // literal value.
+        let literal = self
+            .headers
+            .get(header::CONTENT_DISPOSITION)
+            .and_then(|val| ContentDispositionAttr::FileName.extract_literal_from(val.as_bytes()))
+            .and_then(|literal| std::str::from_utf8(literal).ok());
+
+        match literal {
+            Some(literal) if literal.contains('\\') && !self.content_disposition.has_extended_file_name => {
+                helpers::strip_path(literal)
+            }
+            _ => helpers::strip_path(file_name),
+        }
+    }
+
+    /// The disposition type found in the [`Content-Disposition`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Disposition) header,
+    /// lowercased, e.g. `form-data`, `attachment` or `inline`.
+    pub fn disposition_type(&self) -> Option<&str> {
+        self.content_disposition.disposition_type.as_deref()
+    }
+
+    /// The raw bytes of the field name found in the [`Content-Disposition`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Disposition) header.
+    ///
+    /// Unlike [`name()`](Self::name), this is available even if the name is
+    /// not valid in its declared charset, so that applications can apply their
+    /// own decoding. Quoted-pairs are unescaped and RFC 2231 extended values
+    /// are percent-decoded.
+    pub fn name_bytes(&self) -> Option<&[u8]> {
+        self.content_disposition.field_name_bytes.as_deref()
+    }
+
+    /// The raw bytes of the file name found in the [`Content-Disposition`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Disposition) header.
+    ///
+    /// See [`name_bytes()`](Self::name_bytes) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data: &[u8] = b"--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; \
+    ///     filename=\"caf\xe9.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert_eq!(field.file_name(), None);
+    /// assert_eq!(field.file_name_bytes(), Some(&b"caf\xe9.txt"[..]));
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn file_name_bytes(&self) -> Option<&[u8]> {
+        self.content_disposition.file_name_bytes.as_deref()
+    }
+
+    /// All parameters found in the [`Content-Disposition`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Disposition) header
+    /// as `(name, value)` pairs in order of appearance, e.g. the
+    /// `creation-date`, `modification-date` and `size` parameters some
+    /// clients send along with `name` and `filename`.
+    ///
+    /// Parameter names are lowercased, and RFC 2231 extended values and
+    /// continuations are decoded and listed under their plain name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; \
+    ///     filename=\"a.txt\"; size=4\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert_eq!(field.disposition_param("size"), Some("4"));
+    ///
+    /// for (name, value) in field.disposition_params() {
+    ///     println!("{}: {}", name, value);
+    /// }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn disposition_params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.content_disposition
+            .params
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Get a parameter of the [`Content-Disposition`](https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Content-Disposition) header by
+    /// its case-insensitive name. See
+    /// [`disposition_params()`](Self::disposition_params) for details.
+    pub fn disposition_param(&self, name: &str) -> Option<&str> {
+        self.disposition_params()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value)
+    }
+
     /// Get the content type of the field.
     pub fn content_type(&self) -> Option<&mime::Mime> {
         self.content_type.as_ref()
     }
 
+    /// The size of the field data found in the part's `Content-Length`
+    /// header, if any.
+    ///
+    /// The length is as sent on the wire, i.e. before any
+    /// `Content-Transfer-Encoding` is decoded. It is not checked against the
+    /// actual data unless
+    /// [`Constraints::verify_content_length()`](crate::Constraints::verify_content_length)
+    /// is set.
+    pub fn content_length(&self) -> Option<u64> {
+        helpers::parse_content_length(&self.headers)
+    }
+
+    /// The bounds on the size of the data left to read from the field, as a
+    /// `(lower, upper)` tuple like [`Iterator::size_hint()`], e.g. to allocate
+    /// a buffer up front or to pick between memory and disk storage.
+    ///
+    /// The bounds are derived from the part's `Content-Length` header, and from
+    /// the remaining per-field and whole stream size limits. Unless
+    /// [`Constraints::verify_content_length()`](crate::Constraints::verify_content_length)
+    /// is set, the `Content-Length` header is only trusted as a hint. When the
+    /// data is decoded, the header only gives an upper bound, or no bound at
+    /// all for a `Content-Encoding`.
+    ///
+    /// Note that this differs from [`Stream::size_hint()`], which counts
+    /// chunks rather than bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{Constraints, Multipart, SizeLimit};
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\nContent-Length: 4\r\n\r\nabcd\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nefgh\r\n--X-BOUNDARY--\r\n";
+    /// let constraints = Constraints::new().size_limit(SizeLimit::new().per_field(1024));
+    /// let mut multipart = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert_eq!(field.size_hint(), (4, Some(4)));
+    /// field.bytes().await.unwrap();
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert_eq!(field.size_hint(), (0, Some(1024)));
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn size_hint(&self) -> (u64, Option<u64>) {
+        if self.done {
+            return (0, Some(0));
+        }
+
+        #[cfg(feature = "content-encoding")]
+        if self.content_decoder.is_some() {
+            return (0, None);
+        }
+
+        let state = match self.state.try_lock() {
+            Some(state) => state,
+            None => return (0, None),
+        };
+
+        let received = state.curr_field_size_counter;
+        let stream_left = state
+            .buffer
+            .whole_stream_size_limit
+            .saturating_sub(state.buffer.stream_size_counter)
+            .saturating_add(state.buffer.len() as u64);
+        let mut upper = state.curr_field_size_limit.saturating_sub(received).min(stream_left);
+
+        let content_length = self.content_length().map(|len| len.saturating_sub(received));
+        if let Some(len) = content_length {
+            upper = upper.min(len);
+        }
+
+        // Decoding a transfer encoding or stripping a BOM only shrinks the data.
+        let lower = match self.decoder.is_none() && self.bom_prefix.is_none() {
+            true => content_length.unwrap_or(0).min(upper),
+            false => 0,
+        };
+
+        (lower, Some(upper))
+    }
+
+    /// Whether the data of the field has been cut at its size limit, as set
+    /// with [`Constraints::truncate_fields()`](crate::Constraints::truncate_fields).
+    ///
+    /// This is only known once the limit is reached, so it should be checked
+    /// after the data has been read.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Whether the field is the last part of the body, i.e. is followed by the
+    /// closing boundary.
+    ///
+    /// This is only known once the data of the field has been read to its end
+    /// and the boundary following it has been received, and is `None` before.
+    pub fn is_last(&self) -> Option<bool> {
+        let mut state = self.state.try_lock()?;
+        match state.stage {
+            StreamingStage::Eof => Some(true),
+            StreamingStage::ReadingBoundary if self.done => {
+                let ext_len = constants::BOUNDARY_EXT.len();
+                let boundary_len = ext_len + state.boundary.len();
+                let bytes = state.buffer.peek_exact(boundary_len + ext_len)?;
+                Some(&bytes[boundary_len..] == constants::BOUNDARY_EXT.as_bytes())
+            }
+            _ => None,
+        }
+    }
+
+    /// Takes the raw bytes of the field data consumed by the parser since the
+    /// last call, as received before any decoding, if
+    /// [`Constraints::capture_raw()`] is set, and an empty [`Bytes`] otherwise.
+    ///
+    /// Please refer [`Multipart::take_raw()`] for more info.
+    pub fn take_raw(&self) -> Bytes {
+        self.state
+            .try_lock()
+            .map(|mut state| state.buffer.take_raw())
+            .unwrap_or_default()
+    }
+
     /// Get a map of headers as [`HeaderMap`].
     pub fn headers(&self) -> &HeaderMap {
         &self.headers
@@ -123,9 +406,24 @@ impl<'r> Field<'r> {
     /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
     /// ```
     pub async fn bytes(self) -> crate::Result<Bytes> {
-        let mut buf = BytesMut::new();
-
         let mut this = self;
+
+        // Data that arrives as a single chunk is returned as-is, without copying.
+        let first = match this.chunk().await? {
+            Some(bytes) => bytes,
+            None => return Ok(Bytes::new()),
+        };
+
+        let mut buf = match this.chunk().await? {
+            Some(bytes) => {
+                let mut buf = BytesMut::with_capacity(first.len() + bytes.len());
+                buf.extend_from_slice(&first);
+                buf.extend_from_slice(&bytes);
+                buf
+            }
+            None => return Ok(first),
+        };
+
         while let Some(bytes) = this.chunk().await? {
             buf.extend_from_slice(&bytes);
         }
@@ -214,7 +512,10 @@ impl<'r> Field<'r> {
     /// This method decodes the field data with `BOM sniffing` and with
     /// malformed sequences replaced with the `REPLACEMENT CHARACTER`.
     /// Encoding is determined from the `charset` parameter of `Content-Type`
-    /// header, and defaults to `utf-8` if not presented.
+    /// header, and defaults to `utf-8` if not presented, or to the charset of
+    /// a preceding `_charset_` field if
+    /// [`Constraints::honor_charset_field()`](crate::Constraints::honor_charset_field)
+    /// is set.
     ///
     /// # Examples
     ///
@@ -239,7 +540,8 @@ impl<'r> Field<'r> {
     /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
     /// ```
     pub async fn text(self) -> crate::Result<String> {
-        self.text_with_charset("utf-8").await
+        let form_charset = self.state.try_lock().and_then(|state| state.form_charset.clone());
+        self.text_with_charset(form_charset.as_deref().unwrap_or("utf-8")).await
     }
 
     /// Get the full field data as text given a specific encoding.
@@ -248,6 +550,8 @@ impl<'r> Field<'r> {
     /// malformed sequences replaced with the `REPLACEMENT CHARACTER`.
     /// You can provide a default encoding for decoding the raw message, while
     /// the `charset` parameter of `Content-Type` header is still prioritized.
+    /// The default encoding is also used if the `charset` parameter names an
+    /// unknown encoding, and `utf-8` if the default encoding is unknown too.
     /// For more information about the possible encoding name, please go to
     /// [encoding_rs] docs.
     ///
@@ -274,17 +578,360 @@ impl<'r> Field<'r> {
     /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
     /// ```
     pub async fn text_with_charset(self, default_encoding: &str) -> crate::Result<String> {
-        let encoding_name = self
-            .content_type()
-            .and_then(|mime| mime.get_param(mime::CHARSET))
-            .map(|charset| charset.as_str())
-            .unwrap_or(default_encoding);
-
-        let encoding = Encoding::for_label(encoding_name.as_bytes()).unwrap_or(UTF_8);
+        let encoding = self.text_encoding(default_encoding);
         let bytes = self.bytes().await?;
         Ok(encoding.decode(&bytes).0.into_owned())
     }
 
+    /// Get the full field data as text, like [`text()`](Self::text), failing
+    /// with [`Error::FieldSizeExceeded`] if the data is longer than `limit`
+    /// bytes.
+    ///
+    /// This bounds the memory used for a field whatever the size limits of
+    /// the parser, e.g. for fields expected to hold short values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nefghijkl\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert_eq!(field.text_up_to(4).await.unwrap(), "abcd");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert!(matches!(
+    ///     field.text_up_to(4).await,
+    ///     Err(multer::Error::FieldSizeExceeded { limit: 4, .. })
+    /// ));
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub async fn text_up_to(mut self, limit: usize) -> crate::Result<String> {
+        let form_charset = self.state.try_lock().and_then(|state| state.form_charset.clone());
+        let encoding = self.text_encoding(form_charset.as_deref().unwrap_or("utf-8"));
+
+        let mut buf = BytesMut::new();
+        while let Some(bytes) = self.chunk().await? {
+            if buf.len() + bytes.len() > limit {
+                return Err(Error::FieldSizeExceeded {
+                    limit: limit as u64,
+                    field_name: self.name().map(str::to_owned),
+                });
+            }
+
+            buf.extend_from_slice(&bytes);
+        }
+
+        Ok(encoding.decode(&buf).0.into_owned())
+    }
+
+    /// Parses the field data as a value of type `T` with [`FromStr`], e.g. a
+    /// number or a boolean, failing with [`Error::ParseFailed`] if it's not a
+    /// valid value.
+    ///
+    /// The data is read as text with [`text_up_to()`](Self::text_up_to),
+    /// bounded to 1 KiB, and is not trimmed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"age\"\r\n\r\n42\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"admin\"\r\n\r\nyes\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert_eq!(field.parse::<u32>().await.unwrap(), 42);
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// let err = field.parse::<bool>().await.unwrap_err();
+    /// assert_eq!(
+    ///     err.to_string(),
+    ///     "failed to parse field \"admin\": provided string was not `true` or `false`"
+    /// );
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub async fn parse<T>(self) -> crate::Result<T>
+    where
+        T: FromStr,
+        T::Err: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let field_name = self.name().map(str::to_owned);
+        let text = self.text_up_to(constants::MAX_PARSED_FIELD_SIZE).await?;
+        text.parse().map_err(|err: T::Err| Error::ParseFailed {
+            field_name,
+            cause: err.into(),
+        })
+    }
+
+    /// The encoding of the text of the field, given by the `charset`
+    /// parameter of its `Content-Type` header, or else by `default_encoding`.
+    fn text_encoding(&self, default_encoding: &str) -> &'static Encoding {
+        self.content_type()
+            .and_then(|mime| mime.get_param(mime::CHARSET))
+            .and_then(|charset| Encoding::for_label(charset.as_str().as_bytes()))
+            .or_else(|| Encoding::for_label(default_encoding.as_bytes()))
+            .unwrap_or(UTF_8)
+    }
+
+    /// Turns the field into a reader of its data, implementing `AsyncRead` and
+    /// `AsyncBufRead`, see [`FieldReader`].
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-io` or `futures-io` feature to be
+    /// enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "tokio-io")]
+    /// # async fn run() {
+    /// use multer::Multipart;
+    ///
+    /// let data =
+    ///     "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// let mut reader = field.into_async_read();
+    ///
+    /// let mut file = Vec::new();
+    /// tokio::io::copy(&mut reader, &mut file).await.unwrap();
+    /// assert_eq!(file, b"abcd");
+    /// # }
+    /// # #[cfg(feature = "tokio-io")]
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    #[cfg(any(feature = "tokio-io", feature = "futures-io"))]
+    #[cfg_attr(nightly, doc(cfg(any(feature = "tokio-io", feature = "futures-io"))))]
+    pub fn into_async_read(self) -> FieldReader<'r> {
+        FieldReader::new(self)
+    }
+
+    /// Stream the field data into the writer, e.g. a file, a socket or the
+    /// body of an upload, and return the number of bytes written.
+    ///
+    /// The size limit of the field is enforced as it is written. The writer is
+    /// flushed once the field has been written, but not shut down.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-io` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data =
+    ///     "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// let mut file = Vec::new();
+    /// assert_eq!(field.copy_to(&mut file).await.unwrap(), 4);
+    /// assert_eq!(file, b"abcd");
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    #[cfg(feature = "tokio-io")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-io")))]
+    pub async fn copy_to<W>(mut self, writer: &mut W) -> crate::Result<u64>
+    where
+        W: tokio::io::AsyncWrite + Unpin + ?Sized,
+    {
+        use tokio::io::AsyncWriteExt;
+
+        let mut written = 0;
+        while let Some(chunk) = self.chunk().await? {
+            writer.write_all(&chunk).await.map_err(Error::WriteFailed)?;
+            written += chunk.len() as u64;
+        }
+
+        writer.flush().await.map_err(Error::WriteFailed)?;
+
+        Ok(written)
+    }
+
+    /// Forward the field data into the sink, e.g. a channel, a websocket or
+    /// the producer of a message broker, and return the number of bytes
+    /// forwarded.
+    ///
+    /// Each chunk is fed to the sink once it's ready to accept it, so a slow
+    /// consumer applies backpressure on the parsing. The sink is flushed once
+    /// the field has been forwarded, but not closed, so several fields may be
+    /// forwarded into the same sink. An error of the sink fails with
+    /// [`Error::ForwardFailed`] carrying it as its source.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `sink` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data =
+    ///     "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// let mut chunks: Vec<Bytes> = Vec::new();
+    /// assert_eq!(field.forward_to(&mut chunks).await.unwrap(), 4);
+    /// assert_eq!(chunks.concat(), b"abcd");
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    #[cfg(feature = "sink")]
+    #[cfg_attr(nightly, doc(cfg(feature = "sink")))]
+    pub async fn forward_to<S>(mut self, sink: &mut S) -> crate::Result<u64>
+    where
+        S: futures_util::Sink<Bytes> + Unpin + ?Sized,
+        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        use futures_util::SinkExt;
+
+        let mut forwarded = 0;
+        while let Some(chunk) = self.chunk().await? {
+            forwarded += chunk.len() as u64;
+            sink.feed(chunk).await.map_err(|err| Error::ForwardFailed(err.into()))?;
+        }
+
+        sink.flush().await.map_err(|err| Error::ForwardFailed(err.into()))?;
+
+        Ok(forwarded)
+    }
+
+    /// Parse the field data as a nested multipart body, e.g. the
+    /// `multipart/mixed` body legacy clients send with several files under a
+    /// single field name.
+    ///
+    /// The boundary is taken from the field's `Content-Type` header. The
+    /// nested parts are yielded by the returned [`Multipart`], which consumes
+    /// this field.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"files\"\r\n\
+    ///     Content-Type: multipart/mixed; boundary=Y-BOUNDARY\r\n\r\n\
+    ///     --Y-BOUNDARY\r\nContent-Disposition: file; filename=\"a.txt\"\r\n\r\nabcd\r\n\
+    ///     --Y-BOUNDARY\r\nContent-Disposition: file; filename=\"b.txt\"\r\n\r\nefgh\r\n\
+    ///     --Y-BOUNDARY--\r\n\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// let mut nested = field.into_nested_multipart().unwrap();
+    ///
+    /// while let Some(file) = nested.next_field().await.unwrap() {
+    ///     println!("File: {:?}", file.file_name());
+    ///     println!("Content: {:?}", file.text().await);
+    /// }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::NoMultipart`] if the field's
+    /// `Content-Type` is not `multipart/*`, with [`Error::NoBoundary`] if it
+    /// has no `boundary` parameter, and with [`Error::NestingDepthExceeded`]
+    /// if the nested body would exceed the
+    /// [`max_depth()`](Constraints::max_depth) limit.
+    pub fn into_nested_multipart(self) -> crate::Result<Multipart<'r>> {
+        self.into_nested_multipart_with_constraints(Constraints::default())
+    }
+
+    /// Parse the field data as a nested multipart body, applying the given
+    /// constraints to the nested parts.
+    ///
+    /// See [`Field::into_nested_multipart()`] for details.
+    pub fn into_nested_multipart_with_constraints(self, mut constraints: Constraints) -> crate::Result<Multipart<'r>> {
+        let content_type = self.content_type().ok_or(Error::NoMultipart)?;
+        if content_type.type_() != mime::MULTIPART {
+            return Err(Error::NoMultipart);
+        }
+
+        let boundary = content_type
+            .get_param(mime::BOUNDARY)
+            .map(|boundary| boundary.as_str().to_owned())
+            .ok_or(Error::NoBoundary)?;
+
+        let (depth, max_depth) = match self.state.try_lock() {
+            Some(state) => (state.depth + 1, state.constraints.max_depth),
+            None => return Err(Error::LockFailure),
+        };
+
+        constraints.max_depth = match (constraints.max_depth, max_depth) {
+            (Some(limit), Some(inherited)) => Some(limit.min(inherited)),
+            (limit, inherited) => limit.or(inherited),
+        };
+
+        if let Some(limit) = constraints.max_depth.filter(|limit| depth > *limit) {
+            return Err(Error::NestingDepthExceeded { depth, limit });
+        }
+
+        Ok(Multipart::with_constraints(self, boundary, constraints).with_depth(depth))
+    }
+
+    /// Discard the rest of the field data without yielding it.
+    ///
+    /// The data is skipped by the next call to
+    /// [`Multipart::next_field()`], which searches the raw body for the next
+    /// boundary instead of decoding and counting the field data, as happens
+    /// when the field is merely dropped. The size limit of the field is thus
+    /// not enforced on the skipped data.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; \
+    ///     filename=\"a.exe\"\r\n\r\nMZ...\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; \
+    ///     name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// while let Some(field) = multipart.next_field().await.unwrap() {
+    ///     if field.file_name().is_some() {
+    ///         field.skip();
+    ///         continue;
+    ///     }
+    ///
+    ///     assert_eq!(field.text().await.unwrap(), "abcd");
+    /// }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn skip(self) {
+        if self.done {
+            return;
+        }
+
+        if let Some(mut state) = self.state.try_lock() {
+            if state.stage == StreamingStage::ReadingFieldData {
+                state.stage = StreamingStage::SkippingPart;
+            }
+        }
+    }
+
     /// Get the index of this field in order they appeared in the stream.
     ///
     /// # Examples
@@ -314,10 +961,85 @@ impl<'r> Field<'r> {
     }
 }
 
+/// Wakes the task polling the `Stream` of the `Multipart` when the field is
+/// dropped.
+#[derive(Debug)]
+struct FieldRelease(Arc<Mutex<Option<Waker>>>);
+
+impl Drop for FieldRelease {
+    fn drop(&mut self) {
+        let waker = self.0.lock().take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
 impl Stream for Field<'_> {
     type Item = Result<Bytes, Error>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let poll = self.poll_next_chunk(cx);
+        report_progress(&self.state);
+
+        let poll = poll.map(|item| self.inspect(item));
+        if let Poll::Ready(Some(Err(err))) = &poll {
+            let draining = self.state.try_lock().is_some_and(|mut state| {
+                state.record_violation(err);
+                state.stage == StreamingStage::Draining
+            });
+
+            if draining {
+                self.done = true;
+            }
+        }
+
+        poll
+    }
+}
+
+impl Field<'_> {
+    /// Runs the inspector set with [`Constraints::inspect()`] on the data
+    /// read, turning a veto into an error.
+    fn inspect(&mut self, item: Option<Result<Bytes, Error>>) -> Option<Result<Bytes, Error>> {
+        let inspector = match &self.inspector {
+            Some(inspector) => inspector.0.clone(),
+            None => return item,
+        };
+
+        let result = match &item {
+            Some(Ok(chunk)) => inspector.inspect_chunk(self, chunk),
+            Some(Err(_)) => return item,
+            None => {
+                // The end of the field is inspected only once.
+                self.inspector = None;
+                inspector.end_field(self)
+            }
+        };
+
+        let veto = match result {
+            Ok(()) => return item,
+            Err(veto) => veto,
+        };
+
+        self.done = true;
+        self.inspector = None;
+        let field_name = self.name().map(str::to_owned);
+        let err = match veto {
+            Veto::Field(cause) => Error::FieldRejected { field_name, cause },
+            Veto::Request(cause) => {
+                if let Some(mut state) = self.state.try_lock() {
+                    state.stage = StreamingStage::Eof;
+                    state.missing_required_fields.clear();
+                }
+                Error::RequestRejected { field_name, cause }
+            }
+        };
+
+        Some(Err(err))
+    }
+
+    fn poll_next_chunk(&mut self, cx: &mut Context<'_>) -> Poll<Option<Result<Bytes, Error>>> {
         if self.done {
             return Poll::Ready(None);
         }
@@ -330,33 +1052,150 @@ impl Stream for Field<'_> {
         };
 
         let state = &mut *lock;
-        if let Err(err) = state.buffer.poll_stream(cx) {
-            return Poll::Ready(Some(Err(err)));
-        }
+        loop {
+            if let Err(err) = state.buffer.poll_stream(cx) {
+                return Poll::Ready(Some(Err(err)));
+            }
 
-        match state
-            .buffer
-            .read_field_data(&state.boundary, state.curr_field_name.as_deref())
-        {
-            Ok(Some((done, bytes))) => {
-                state.curr_field_size_counter += bytes.len() as u64;
-
-                if state.curr_field_size_counter > state.curr_field_size_limit {
-                    return Poll::Ready(Some(Err(Error::FieldSizeExceeded {
-                        limit: state.curr_field_size_limit,
-                        field_name: state.curr_field_name.clone(),
-                    })));
-                }
+            match state
+                .buffer
+                .read_field_data(&state.boundary, state.curr_field_name.as_deref())
+            {
+                Ok(Some((done, mut bytes))) => {
+                    state.curr_field_size_counter += bytes.len() as u64;
 
-                if done {
-                    state.stage = StreamingStage::ReadingBoundary;
-                    self.done = true;
-                }
+                    if state.curr_field_size_counter > state.curr_field_size_limit {
+                        if !state.curr_field_truncate {
+                            return Poll::Ready(Some(Err(Error::FieldSizeExceeded {
+                                limit: state.curr_field_size_limit,
+                                field_name: state.curr_field_name.clone(),
+                            })));
+                        }
+
+                        // Keep the data up to the limit and discard the rest.
+                        let received = state.curr_field_size_counter - bytes.len() as u64;
+                        let keep = state.curr_field_size_limit.saturating_sub(received);
+                        bytes.truncate(keep as usize);
+                        self.truncated = true;
+
+                        if bytes.is_empty() && !done {
+                            continue;
+                        }
+                    }
+
+                    if let Err(err) = state.check_empty_file(done) {
+                        return Poll::Ready(Some(Err(err)));
+                    }
+
+                    if let Some(expected) = state.curr_field_expected_size {
+                        let actual = state.curr_field_size_counter;
+                        if actual > expected || (done && actual != expected) {
+                            return Poll::Ready(Some(Err(Error::ContentLengthMismatch {
+                                field_name: state.curr_field_name.clone(),
+                                expected,
+                                actual,
+                            })));
+                        }
+                    }
+
+                    state.record_progress();
+
+                    if done {
+                        state.stage = StreamingStage::ReadingBoundary;
+                        self.done = true;
+                    }
 
-                Poll::Ready(Some(Ok(bytes)))
+                    let bytes = match self.decoder.as_mut() {
+                        Some(decoder) => match decoder.decode(&bytes, done) {
+                            Ok(bytes) => bytes,
+                            Err(cause) => {
+                                return Poll::Ready(Some(Err(Error::DecodeTransferEncoding {
+                                    field_name: state.curr_field_name.clone(),
+                                    cause: cause.into(),
+                                })));
+                            }
+                        },
+                        None => bytes,
+                    };
+
+                    #[cfg(feature = "content-encoding")]
+                    let bytes = match self.content_decoder.as_mut() {
+                        Some(decoder) => match decoder.decode(&bytes, done) {
+                            Ok(bytes) => bytes,
+                            Err(ContentDecodeError::SizeExceeded(limit)) => {
+                                return Poll::Ready(Some(Err(Error::DecompressedSizeExceeded {
+                                    limit,
+                                    field_name: state.curr_field_name.clone(),
+                                })));
+                            }
+                            Err(ContentDecodeError::Invalid(cause)) => {
+                                return Poll::Ready(Some(Err(Error::DecodeContentEncoding {
+                                    field_name: state.curr_field_name.clone(),
+                                    cause: cause.into(),
+                                })));
+                            }
+                        },
+                        None => bytes,
+                    };
+
+                    let bytes = match self.sniffer.as_mut() {
+                        Some(sniffer) => match sniffer.sniff(bytes, done) {
+                            Ok(Some(bytes)) => {
+                                self.sniffer = None;
+                                bytes
+                            }
+                            Ok(None) => continue,
+                            Err(detected) => {
+                                return Poll::Ready(Some(Err(Error::ContentTypeMismatch {
+                                    field_name: state.curr_field_name.clone(),
+                                    declared: self
+                                        .content_type()
+                                        .map(|m| m.essence_str().to_owned())
+                                        .unwrap_or_default(),
+                                    detected: detected.map(str::to_owned),
+                                })));
+                            }
+                        },
+                        None => bytes,
+                    };
+
+                    let bytes = match self.bom_prefix.take() {
+                        Some(mut prefix) => {
+                            let data = match prefix.is_empty() {
+                                true => bytes,
+                                false => {
+                                    prefix.extend_from_slice(&bytes);
+                                    Bytes::from(prefix)
+                                }
+                            };
+
+                            // Wait for enough data to tell whether it starts with a BOM.
+                            if !done && data.len() < constants::UTF8_BOM.len() && constants::UTF8_BOM.starts_with(&data)
+                            {
+                                self.bom_prefix = Some(data.to_vec());
+                                continue;
+                            }
+
+                            match data.starts_with(constants::UTF8_BOM) {
+                                true => data.slice(constants::UTF8_BOM.len()..),
+                                false => data,
+                            }
+                        }
+                        None => bytes,
+                    };
+
+                    state.track_charset_field(&bytes, done);
+
+                    // Keep reading if the decoder is still waiting for more input.
+                    if bytes.is_empty() && !done {
+                        continue;
+                    }
+
+                    return Poll::Ready(Some(Ok(bytes)));
+                }
+                Ok(None) => return Poll::Pending,
+                Err(err) => return Poll::Ready(Some(Err(err))),
             }
-            Ok(None) => Poll::Pending,
-            Err(err) => Poll::Ready(Some(Err(err))),
         }
     }
 }
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use bytes::Bytes;
+
+use crate::multipart::Multipart;
+
+/// All the fields of a form, as returned by
+/// [`Multipart::collect_form()`](crate::Multipart::collect_form).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MultipartForm {
+    /// The text fields by name, with the value of the last one if a name is
+    /// repeated.
+    pub texts: HashMap<String, String>,
+    /// Every value of the text fields by name, in the order they appeared in
+    /// the stream, as names may be repeated, e.g. by checkbox groups.
+    pub all_texts: HashMap<String, Vec<String>>,
+    /// The file fields, in the order they appeared in the stream.
+    pub files: Vec<FilePart>,
+}
+
+/// A file field of a [`MultipartForm`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct FilePart {
+    /// The field name found in the `Content-Disposition` header.
+    pub name: Option<String>,
+    /// The file name found in the `Content-Disposition` header.
+    pub file_name: String,
+    /// The content type of the file.
+    pub content_type: Option<mime::Mime>,
+    /// The data of the file.
+    pub data: Bytes,
+}
+
+impl MultipartForm {
+    /// Get the first file field with the given name.
+    pub fn file(&self, name: &str) -> Option<&FilePart> {
+        self.files.iter().find(|file| file.name.as_deref() == Some(name))
+    }
+
+    /// Get every value of the text fields with the given name, in the order
+    /// they appeared in the stream, e.g. the checked boxes of a group or the
+    /// selected options of a `<select multiple>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tags\"\r\n\r\nrust\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tags\"\r\n\r\nasync\r\n\
+    ///     --X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let form = multipart.collect_form().await.unwrap();
+    /// assert_eq!(form.get_all("tags"), ["rust", "async"]);
+    /// assert_eq!(form.texts["tags"], "async");
+    /// assert!(form.get_all("missing").is_empty());
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn get_all(&self, name: &str) -> &[String] {
+        self.all_texts.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Get every file field with the given name, in the order they appeared
+    /// in the stream.
+    pub fn files_all(&self, name: &str) -> Vec<&FilePart> {
+        self.files
+            .iter()
+            .filter(|file| file.name.as_deref() == Some(name))
+            .collect()
+    }
+
+    /// Get the file fields sent as an array named after `prefix`, e.g. the
+    /// `files[]` or `files[0]`, `files[1]` fields HTML and JavaScript forms
+    /// send for several files.
+    ///
+    /// The indexed fields are sorted by index, after the `files[]` ones in
+    /// the order they appeared in the stream.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"files[1]\"; filename=\"b.txt\"\r\n\r\nb\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"files[0]\"; filename=\"a.txt\"\r\n\r\na\r\n\
+    ///     --X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let form = multipart.collect_form().await.unwrap();
+    /// let names: Vec<_> = form.file_group("files").iter().map(|file| file.file_name.as_str()).collect();
+    /// assert_eq!(names, ["a.txt", "b.txt"]);
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn file_group(&self, prefix: &str) -> Vec<&FilePart> {
+        let mut group: Vec<_> = self
+            .files
+            .iter()
+            .filter_map(|file| Some((array_index(file.name.as_deref()?, prefix)?, file)))
+            .collect();
+
+        group.sort_by_key(|(idx, _)| *idx);
+        group.into_iter().map(|(_, file)| file).collect()
+    }
+
+    /// Get the values of the text fields sent as an array named after
+    /// `prefix`, e.g. `tags[]` or `tags[0]`, `tags[1]`.
+    ///
+    /// The indexed fields are sorted by index, after the `tags[]` ones in the
+    /// order they appeared in the stream.
+    pub fn text_group(&self, prefix: &str) -> Vec<&str> {
+        let mut group: Vec<_> = self
+            .all_texts
+            .iter()
+            .filter_map(|(name, values)| Some((array_index(name, prefix)?, values)))
+            .flat_map(|(idx, values)| values.iter().map(move |value| (idx, value.as_str())))
+            .collect();
+
+        group.sort_by_key(|(idx, _)| *idx);
+        group.into_iter().map(|(_, value)| value).collect()
+    }
+}
+
+/// Returns the index of an array field named `name` if it belongs to the
+/// `prefix` array, `None` as the index standing for `prefix[]`.
+fn array_index(name: &str, prefix: &str) -> Option<Option<usize>> {
+    let index = name.strip_prefix(prefix)?.strip_prefix('[')?.strip_suffix(']')?;
+    match index {
+        "" => Some(None),
+        index if index.bytes().all(|b| b.is_ascii_digit()) => index.parse().ok().map(Some),
+        _ => None,
+    }
+}
+
+impl<'r> Multipart<'r> {
+    /// Reads all the fields in memory, as text for fields without a file
+    /// name, for the common case of a small form.
+    ///
+    /// The whole body is buffered, so the [`Constraints`](crate::Constraints)
+    /// of the parser should limit its size, e.g. with
+    /// [`SizeLimit::whole_stream()`](crate::SizeLimit::whole_stream). Text
+    /// fields without a name are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{Constraints, Multipart, SizeLimit};
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.txt\"\r\n\r\nHello world\r\n\
+    ///     --X-BOUNDARY--\r\n";
+    /// let constraints = Constraints::new().size_limit(SizeLimit::new().whole_stream(1024 * 1024));
+    /// let mut multipart = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+    ///
+    /// let form = multipart.collect_form().await.unwrap();
+    /// assert_eq!(form.texts["my_text_field"], "abcd");
+    ///
+    /// let file = form.file("my_file_field").unwrap();
+    /// assert_eq!(file.file_name, "a.txt");
+    /// assert_eq!(file.data, "Hello world");
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub async fn collect_form(&mut self) -> crate::Result<MultipartForm> {
+        let mut form = MultipartForm::default();
+
+        while let Some(field) = self.next_field().await? {
+            let name = field.name().map(str::to_owned);
+
+            match field.file_name() {
+                Some(file_name) => form.files.push(FilePart {
+                    name,
+                    file_name: file_name.to_owned(),
+                    content_type: field.content_type().cloned(),
+                    data: field.bytes().await?,
+                }),
+                None => {
+                    let value = field.text().await?;
+                    if let Some(name) = name {
+                        form.all_texts.entry(name.clone()).or_default().push(value.clone());
+                        form.texts.insert(name, value);
+                    }
+                }
+            }
+        }
+
+        Ok(form)
+    }
+}
@@ -0,0 +1,364 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+
+use bytes::{Bytes, BytesMut};
+use futures_util::stream::{Stream, StreamExt, TryStreamExt};
+use http::header::HeaderMap;
+
+use crate::constraints::{Constraints, UnknownFieldPolicy};
+use crate::error::Error;
+use crate::field::Field;
+use crate::multipart::Multipart;
+
+type BoxStream<'r> = Pin<Box<dyn Stream<Item = crate::Result<Bytes>> + Send + 'r>>;
+
+/// A form body, either `multipart/form-data` or
+/// `application/x-www-form-urlencoded` depending on its `Content-Type`, whose
+/// fields are iterated the same way, so that a handler accepting both
+/// encodings doesn't need two code paths.
+///
+/// A `multipart/form-data` body is parsed by a [`Multipart`]. An urlencoded
+/// body is read in full, within the
+/// [`SizeLimit::whole_stream()`](crate::SizeLimit::whole_stream) limit, and its
+/// fields are checked against the field count and size limits, and the
+/// allowed, required and duplicate fields of the [`Constraints`]. The other
+/// constraints only apply to multipart bodies.
+///
+/// # Optional
+///
+/// This requires the optional `urlencoded` feature to be enabled.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::Infallible;
+///
+/// use bytes::Bytes;
+/// use futures_util::stream::once;
+/// use multer::FormData;
+///
+/// # async fn run() {
+/// async fn names(data: &'static str, content_type: &str) -> Vec<String> {
+///     let stream = once(async move { Result::<Bytes, Infallible>::Ok(Bytes::from(data)) });
+///     let mut form = FormData::new(stream, content_type).unwrap();
+///
+///     let mut names = Vec::new();
+///     while let Some(field) = form.next_field().await.unwrap() {
+///         let name = field.name().unwrap().to_owned();
+///         names.push(format!("{}={}", name, field.text().await.unwrap()));
+///     }
+///     names
+/// }
+///
+/// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"name\"\r\n\r\nJohn Doe\r\n--X-BOUNDARY--\r\n";
+/// let content_type = "multipart/form-data; boundary=X-BOUNDARY";
+/// assert_eq!(names(data, content_type).await, ["name=John Doe"]);
+///
+/// let content_type = "application/x-www-form-urlencoded";
+/// assert_eq!(names("name=John+Doe", content_type).await, ["name=John Doe"]);
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+/// ```
+#[derive(Debug)]
+pub struct FormData<'r> {
+    inner: Inner<'r>,
+}
+
+#[derive(Debug)]
+enum Inner<'r> {
+    Multipart(Multipart<'r>),
+    UrlEncoded(UrlEncoded<'r>),
+}
+
+struct UrlEncoded<'r> {
+    // Taken once the body has been read.
+    stream: Option<BoxStream<'r>>,
+    constraints: Constraints,
+    fields: VecDeque<(String, String)>,
+    next_field_idx: usize,
+    missing_required_fields: Vec<String>,
+    seen_field_names: HashSet<String>,
+}
+
+impl<'r> FormData<'r> {
+    /// Construct a new `FormData` instance with the given [`Bytes`] stream
+    /// and the value of the `Content-Type` header.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::NoMultipart`] if the `Content-Type` is
+    /// neither `multipart/form-data` nor `application/x-www-form-urlencoded`,
+    /// or with [`Error::NoBoundary`] for a multipart body without a boundary.
+    pub fn new<S, O, E, C>(stream: S, content_type: C) -> crate::Result<Self>
+    where
+        S: Stream<Item = Result<O, E>> + Send + 'r,
+        O: Into<Bytes> + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + 'r,
+        C: AsRef<str>,
+    {
+        FormData::with_constraints(stream, content_type, Constraints::default())
+    }
+
+    /// Construct a new `FormData` instance with the given [`Bytes`] stream,
+    /// the value of the `Content-Type` header and the constraints.
+    pub fn with_constraints<S, O, E, C>(stream: S, content_type: C, constraints: Constraints) -> crate::Result<Self>
+    where
+        S: Stream<Item = Result<O, E>> + Send + 'r,
+        O: Into<Bytes> + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + 'r,
+        C: AsRef<str>,
+    {
+        let m = content_type
+            .as_ref()
+            .parse::<mime::Mime>()
+            .map_err(Error::DecodeContentType)?;
+
+        let inner = match (m.type_(), m.subtype()) {
+            (mime::MULTIPART, mime::FORM_DATA) => {
+                let boundary = m.get_param(mime::BOUNDARY).ok_or(Error::NoBoundary)?;
+                Inner::Multipart(Multipart::with_constraints(stream, boundary.as_str(), constraints))
+            }
+            (mime::APPLICATION, mime::WWW_FORM_URLENCODED) => {
+                let stream = stream
+                    .map_ok(|b| b.into())
+                    .map_err(|err| Error::StreamReadFailed(err.into()));
+
+                Inner::UrlEncoded(UrlEncoded {
+                    stream: Some(Box::pin(stream)),
+                    fields: VecDeque::new(),
+                    next_field_idx: 0,
+                    missing_required_fields: constraints.required_fields.clone(),
+                    seen_field_names: HashSet::new(),
+                    constraints,
+                })
+            }
+            _ => return Err(Error::NoMultipart),
+        };
+
+        Ok(FormData { inner })
+    }
+
+    /// Whether the body is `multipart/form-data`, as opposed to
+    /// `application/x-www-form-urlencoded`.
+    pub fn is_multipart(&self) -> bool {
+        matches!(self.inner, Inner::Multipart(_))
+    }
+
+    /// Yields the next [`FormField`] if available.
+    ///
+    /// For a multipart body, any previous `FormField` must be dropped before
+    /// calling this method again, as for [`Multipart::next_field()`].
+    pub async fn next_field(&mut self) -> crate::Result<Option<FormField<'r>>> {
+        let inner = match &mut self.inner {
+            Inner::Multipart(multipart) => {
+                let field = multipart.next_field().await?;
+                return Ok(field.map(|field| FormField {
+                    inner: FieldInner::Multipart(Box::new(field)),
+                }));
+            }
+            Inner::UrlEncoded(urlencoded) => urlencoded.next_field().await?,
+        };
+
+        Ok(inner.map(|inner| FormField { inner }))
+    }
+}
+
+impl<'r> UrlEncoded<'r> {
+    async fn next_field(&mut self) -> crate::Result<Option<FieldInner<'r>>> {
+        if let Some(mut stream) = self.stream.take() {
+            let limit = self.constraints.size_limit.whole_stream;
+            let mut body = BytesMut::new();
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk?;
+                if (body.len() + chunk.len()) as u64 > limit {
+                    return Err(Error::StreamSizeExceeded { limit });
+                }
+
+                body.extend_from_slice(&chunk);
+            }
+
+            self.fields = form_urlencoded::parse(&body)
+                .map(|(name, value)| (name.into_owned(), value.into_owned()))
+                .collect();
+        }
+
+        loop {
+            let (name, value) = match self.fields.pop_front() {
+                Some(field) => field,
+                None => {
+                    return match self.missing_required_fields.is_empty() {
+                        true => Ok(None),
+                        false => Err(Error::MissingRequiredFields {
+                            field_names: std::mem::take(&mut self.missing_required_fields),
+                        }),
+                    };
+                }
+            };
+
+            let max_fields = self.constraints.size_limit.max_fields;
+            if self.next_field_idx >= max_fields {
+                return Err(Error::FieldCountExceeded { limit: max_fields });
+            }
+
+            let idx = self.next_field_idx;
+            self.next_field_idx += 1;
+
+            if !self.constraints.is_it_allowed(Some(&name)) {
+                let err = Error::UnknownField { field_name: Some(name) };
+                match self.constraints.unknown_fields {
+                    UnknownFieldPolicy::Reject => return Err(err),
+                    UnknownFieldPolicy::Ignore => continue,
+                    UnknownFieldPolicy::SkipAndWarn => {
+                        warn!("skipping field: {}", err);
+                        continue;
+                    }
+                }
+            }
+
+            self.missing_required_fields.retain(|field_name| *field_name != name);
+
+            if self.constraints.reject_duplicate_fields
+                && !self.seen_field_names.insert(name.clone())
+                && !self.constraints.is_duplicate_allowed(&name)
+            {
+                return Err(Error::DuplicateField { field_name: name });
+            }
+
+            let mut value = Bytes::from(value);
+            let limit = self.constraints.size_limit.extract_size_limit_for(Some(&name));
+            if value.len() as u64 > limit {
+                if !self.constraints.is_truncated_field(&name) {
+                    return Err(Error::FieldSizeExceeded {
+                        limit,
+                        field_name: Some(name),
+                    });
+                }
+
+                value.truncate(limit as usize);
+            }
+
+            return Ok(Some(FieldInner::UrlEncoded {
+                name,
+                value: Some(value),
+                idx,
+                headers: HeaderMap::new(),
+            }));
+        }
+    }
+}
+
+impl Debug for UrlEncoded<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UrlEncoded")
+            .field("fields", &self.fields)
+            .field("next_field_idx", &self.next_field_idx)
+            .finish()
+    }
+}
+
+/// A field of a [`FormData`], either a multipart [`Field`] or a field of an
+/// urlencoded body.
+///
+/// # Optional
+///
+/// This requires the optional `urlencoded` feature to be enabled.
+#[derive(Debug)]
+pub struct FormField<'r> {
+    inner: FieldInner<'r>,
+}
+
+#[derive(Debug)]
+enum FieldInner<'r> {
+    Multipart(Box<Field<'r>>),
+    UrlEncoded {
+        name: String,
+        // Taken once yielded by `chunk()`.
+        value: Option<Bytes>,
+        idx: usize,
+        headers: HeaderMap,
+    },
+}
+
+impl<'r> FormField<'r> {
+    /// The field name.
+    pub fn name(&self) -> Option<&str> {
+        match &self.inner {
+            FieldInner::Multipart(field) => field.name(),
+            FieldInner::UrlEncoded { name, .. } => Some(name),
+        }
+    }
+
+    /// The file name of a multipart file field, always `None` for an
+    /// urlencoded body.
+    pub fn file_name(&self) -> Option<&str> {
+        match &self.inner {
+            FieldInner::Multipart(field) => field.file_name(),
+            FieldInner::UrlEncoded { .. } => None,
+        }
+    }
+
+    /// The content type of a multipart field, always `None` for an urlencoded
+    /// body.
+    pub fn content_type(&self) -> Option<&mime::Mime> {
+        match &self.inner {
+            FieldInner::Multipart(field) => field.content_type(),
+            FieldInner::UrlEncoded { .. } => None,
+        }
+    }
+
+    /// The headers of a multipart field, always empty for an urlencoded body.
+    pub fn headers(&self) -> &HeaderMap {
+        match &self.inner {
+            FieldInner::Multipart(field) => field.headers(),
+            FieldInner::UrlEncoded { headers, .. } => headers,
+        }
+    }
+
+    /// Get the index of this field in order they appeared in the body.
+    pub fn index(&self) -> usize {
+        match &self.inner {
+            FieldInner::Multipart(field) => field.index(),
+            FieldInner::UrlEncoded { idx, .. } => *idx,
+        }
+    }
+
+    /// Stream a chunk of the field data.
+    ///
+    /// The value of an urlencoded field is returned as a single chunk. When
+    /// the field data has been exhausted, this will return [`None`].
+    pub async fn chunk(&mut self) -> crate::Result<Option<Bytes>> {
+        match &mut self.inner {
+            FieldInner::Multipart(field) => field.chunk().await,
+            FieldInner::UrlEncoded { value, .. } => Ok(value.take()),
+        }
+    }
+
+    /// Get the full data of the field as [`Bytes`].
+    pub async fn bytes(self) -> crate::Result<Bytes> {
+        match self.inner {
+            FieldInner::Multipart(field) => field.bytes().await,
+            FieldInner::UrlEncoded { value, .. } => Ok(value.unwrap_or_default()),
+        }
+    }
+
+    /// Get the full field data as text, see [`Field::text()`]. The values of
+    /// an urlencoded body are decoded as UTF-8.
+    pub async fn text(self) -> crate::Result<String> {
+        match self.inner {
+            FieldInner::Multipart(field) => field.text().await,
+            FieldInner::UrlEncoded { value, .. } => {
+                Ok(String::from_utf8_lossy(&value.unwrap_or_default()).into_owned())
+            }
+        }
+    }
+
+    /// Returns the underlying multipart [`Field`], or `None` for a field of an
+    /// urlencoded body.
+    pub fn into_multipart_field(self) -> Option<Field<'r>> {
+        match self.inner {
+            FieldInner::Multipart(field) => Some(*field),
+            FieldInner::UrlEncoded { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use bytes::Bytes;
+
+#[cfg(feature = "tokio-fs")]
+use crate::field::Field;
+#[cfg(feature = "tokio-fs")]
+use crate::spool::SpooledField;
+
+/// A file field read with `Multipart::deserialize()` or into a struct
+/// deriving `FromMultipart`, holding its data in memory.
+///
+/// # Optional
+///
+/// This requires the optional `serde` or `derive` feature to be enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FormFile {
+    pub(crate) file_name: Option<String>,
+    pub(crate) content_type: Option<mime::Mime>,
+    pub(crate) data: Bytes,
+}
+
+impl FormFile {
+    /// The file name found in the `Content-Disposition` header.
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// The content type of the file.
+    pub fn content_type(&self) -> Option<&mime::Mime> {
+        self.content_type.as_ref()
+    }
+
+    /// The data of the file.
+    pub fn data(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// Consume the file and return its data.
+    pub fn into_data(self) -> Bytes {
+        self.data
+    }
+}
+
+/// A file field read with `Multipart::deserialize_spooled()` or into a
+/// struct deriving `FromMultipart`, holding its data in memory or in a
+/// temporary file.
+///
+/// The temporary file is not removed automatically, it's up to the caller to
+/// move or delete it once it has been processed.
+///
+/// # Optional
+///
+/// This requires the optional `tokio-fs` feature, and the `serde` or `derive`
+/// feature to be enabled.
+#[cfg(feature = "tokio-fs")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpooledFormFile {
+    pub(crate) file_name: Option<String>,
+    pub(crate) content_type: Option<mime::Mime>,
+    pub(crate) data: SpooledField,
+}
+
+#[cfg(feature = "tokio-fs")]
+impl SpooledFormFile {
+    /// Reads the field with [`Field::spool()`], spilling its data to a
+    /// temporary file if it exceeds `threshold` bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{Multipart, SpooledFormFile};
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; \
+    ///     filename=\"a.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// let file = SpooledFormFile::spool(field, 1024).await.unwrap();
+    /// assert_eq!(file.file_name(), Some("a.txt"));
+    /// assert!(file.data().is_in_memory());
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub async fn spool(field: Field<'_>, threshold: usize) -> crate::Result<SpooledFormFile> {
+        let file_name = field.file_name().map(str::to_owned);
+        let content_type = field.content_type().cloned();
+
+        Ok(SpooledFormFile {
+            file_name,
+            content_type,
+            data: field.spool(threshold).await?,
+        })
+    }
+
+    /// The file name found in the `Content-Disposition` header.
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// The content type of the file.
+    pub fn content_type(&self) -> Option<&mime::Mime> {
+        self.content_type.as_ref()
+    }
+
+    /// The data of the file.
+    pub fn data(&self) -> &SpooledField {
+        &self.data
+    }
+
+    /// Consume the file and return its data.
+    pub fn into_data(self) -> SpooledField {
+        self.data
+    }
+}
@@ -0,0 +1,143 @@
+use bytes::Bytes;
+use futures_util::future::{BoxFuture, FutureExt};
+
+use crate::field::Field;
+use crate::form_file::FormFile;
+use crate::multipart::Multipart;
+
+/// A type read from all the fields of a [`Multipart`], usually implemented
+/// with `#[derive(FromMultipart)]`.
+///
+/// The derive reads each field of a struct with named fields from the
+/// multipart field of the same name, with [`FromField`]:
+///
+/// - `T` keeps the last field of that name, and fails with
+///   `Error::DecodeForm` if there is none.
+/// - `Option<T>` keeps the last field of that name, if any.
+/// - `Vec<T>` keeps all the fields of that name.
+///
+/// Fields with no matching struct field are ignored. A struct field can be
+/// read from a differently named multipart field with
+/// `#[multer(rename = "name")]`, and a `SpooledFormFile` field can be
+/// spilled to a temporary file past a threshold with `#[multer(spool = 1024)]`,
+/// which requires the `tokio-fs` feature.
+///
+/// # Optional
+///
+/// This requires the optional `derive` feature to be enabled.
+///
+/// # Examples
+///
+/// ```
+/// use multer::{FormFile, FromMultipart, Multipart};
+///
+/// #[derive(FromMultipart)]
+/// struct Upload {
+///     title: String,
+///     #[multer(rename = "tag")]
+///     tags: Vec<String>,
+///     rating: Option<u8>,
+///     file: FormFile,
+/// }
+///
+/// # async fn run() {
+/// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nHello\r\n\
+///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tag\"\r\n\r\na\r\n\
+///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tag\"\r\n\r\nb\r\n\
+///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\r\nabcd\r\n\
+///     --X-BOUNDARY--\r\n";
+/// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+///
+/// let upload = Upload::from_multipart(&mut multipart).await.unwrap();
+/// assert_eq!(upload.title, "Hello");
+/// assert_eq!(upload.tags, ["a", "b"]);
+/// assert_eq!(upload.rating, None);
+/// assert_eq!(upload.file.file_name(), Some("a.txt"));
+/// assert_eq!(upload.file.data(), "abcd");
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+/// ```
+#[cfg_attr(nightly, doc(cfg(feature = "derive")))]
+pub trait FromMultipart: Sized {
+    /// Reads all the remaining fields of `multipart` into `Self`.
+    fn from_multipart<'a, 'r: 'a>(multipart: &'a mut Multipart<'r>) -> BoxFuture<'a, crate::Result<Self>>;
+}
+
+/// A type read from the data of a single field, for the fields of a struct
+/// deriving [`FromMultipart`].
+///
+/// It's implemented for [`String`] with [`Field::text()`], [`Bytes`] with
+/// [`Field::bytes()`], [`FormFile`], and for the primitive types parsed
+/// from the text of the field.
+///
+/// # Optional
+///
+/// This requires the optional `derive` feature to be enabled.
+#[cfg_attr(nightly, doc(cfg(feature = "derive")))]
+pub trait FromField: Sized {
+    /// Reads the data of `field` into `Self`.
+    fn from_field(field: Field<'_>) -> BoxFuture<'_, crate::Result<Self>>;
+}
+
+impl FromField for String {
+    fn from_field(field: Field<'_>) -> BoxFuture<'_, crate::Result<Self>> {
+        field.text().boxed()
+    }
+}
+
+impl FromField for Bytes {
+    fn from_field(field: Field<'_>) -> BoxFuture<'_, crate::Result<Self>> {
+        field.bytes().boxed()
+    }
+}
+
+impl FromField for FormFile {
+    fn from_field(field: Field<'_>) -> BoxFuture<'_, crate::Result<Self>> {
+        async move {
+            let file_name = field.file_name().map(str::to_owned);
+            let content_type = field.content_type().cloned();
+
+            Ok(FormFile {
+                file_name,
+                content_type,
+                data: field.bytes().await?,
+            })
+        }
+        .boxed()
+    }
+}
+
+macro_rules! from_field_parse {
+    ($($ty:ty),*) => {
+        $(
+            impl FromField for $ty {
+                fn from_field(field: Field<'_>) -> BoxFuture<'_, crate::Result<Self>> {
+                    async move {
+                        let field_name = field.name().map(str::to_owned);
+                        let text = field.text().await?;
+
+                        text.trim().parse().map_err(|err: <$ty as std::str::FromStr>::Err| crate::Error::DecodeForm {
+                            field_name,
+                            reason: err.to_string(),
+                        })
+                    }
+                    .boxed()
+                }
+            }
+        )*
+    };
+}
+
+from_field_parse!(bool, char, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+#[doc(hidden)]
+pub mod __private {
+    pub use futures_util::future::BoxFuture;
+
+    pub fn missing_field(name: &str) -> crate::Error {
+        crate::Error::DecodeForm {
+            field_name: Some(name.to_owned()),
+            reason: "missing field".to_owned(),
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use std::path::Path;
+
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+
+use crate::error::Error;
+use crate::field::Field;
+
+impl Field<'_> {
+    /// Stream the field data into the file at `path`, and return the number
+    /// of bytes written.
+    ///
+    /// The file is created if it does not exist, and truncated if it does.
+    /// The chunks are written as they arrive, so the field is never buffered
+    /// in memory, and the size limit of the field is enforced as it is written:
+    /// a field exceeding its limit fails with
+    /// [`Error::FieldSizeExceeded`], leaving the data written so far in the
+    /// file.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-fs` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; \
+    ///     filename=\"a.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// while let Some(field) = multipart.next_field().await.unwrap() {
+    ///     let written = field.save_to_file("/tmp/a.txt").await.unwrap();
+    ///     assert_eq!(written, 4);
+    /// }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-fs")))]
+    pub async fn save_to_file<P: AsRef<Path>>(mut self, path: P) -> crate::Result<u64> {
+        let mut file = File::create(path).await.map_err(Error::WriteFailed)?;
+
+        let mut written = 0;
+        while let Some(chunk) = self.chunk().await? {
+            file.write_all(&chunk).await.map_err(Error::WriteFailed)?;
+            written += chunk.len() as u64;
+        }
+
+        file.flush().await.map_err(Error::WriteFailed)?;
+
+        Ok(written)
+    }
+}
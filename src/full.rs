@@ -0,0 +1,153 @@
+use bytes::Bytes;
+use http::header::HeaderMap;
+
+use crate::constraints::DuplicateHeaderPolicy;
+use crate::content_disposition::ContentDisposition;
+use crate::error::Error;
+use crate::multipart::Multipart;
+use crate::{constants, helpers};
+
+/// A part of a fully buffered body, as returned by
+/// [`Multipart::parse_full()`].
+///
+/// The data is a slice of the body, without any copy.
+#[derive(Debug, Clone)]
+pub struct OwnedPart {
+    headers: HeaderMap,
+    content_disposition: ContentDisposition,
+    content_type: Option<mime::Mime>,
+    data: Bytes,
+}
+
+impl OwnedPart {
+    /// The field name found in the `Content-Disposition` header.
+    pub fn name(&self) -> Option<&str> {
+        self.content_disposition.field_name.as_deref()
+    }
+
+    /// The file name found in the `Content-Disposition` header.
+    pub fn file_name(&self) -> Option<&str> {
+        self.content_disposition.file_name.as_deref()
+    }
+
+    /// Get the content type of the part.
+    pub fn content_type(&self) -> Option<&mime::Mime> {
+        self.content_type.as_ref()
+    }
+
+    /// Get a map of headers as [`HeaderMap`].
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The raw data of the part.
+    ///
+    /// Unlike the data of a [`Field`](crate::Field), it is not decoded from
+    /// any `Content-Transfer-Encoding`.
+    pub fn data(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// Returns the data of the part.
+    pub fn into_data(self) -> Bytes {
+        self.data
+    }
+}
+
+impl Multipart<'_> {
+    /// Parses an already buffered body in one go, e.g. the body of a Lambda
+    /// event, a message from a queue or a test fixture.
+    ///
+    /// Unlike [`Multipart::with_bytes()`], this doesn't go through the async
+    /// parser: the parts are sliced out of `body` without copying their data,
+    /// and no [`Constraints`](crate::Constraints) are applied, as the body is
+    /// already in memory. The preamble and the epilogue are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.txt\"\r\n\r\nHello world\r\n\
+    ///     --X-BOUNDARY--\r\n";
+    ///
+    /// let parts = Multipart::parse_full(data, "X-BOUNDARY").unwrap();
+    /// assert_eq!(parts.len(), 2);
+    /// assert_eq!(parts[0].name(), Some("my_text_field"));
+    /// assert_eq!(parts[0].data(), "abcd");
+    /// assert_eq!(parts[1].file_name(), Some("a.txt"));
+    /// assert_eq!(parts[1].data(), "Hello world");
+    /// ```
+    pub fn parse_full<D, B>(body: D, boundary: B) -> crate::Result<Vec<OwnedPart>>
+    where
+        D: Into<Bytes>,
+        B: AsRef<str>,
+    {
+        let body = body.into();
+        let boundary = format!("{}{}", constants::BOUNDARY_EXT, boundary.as_ref());
+        let delimiter = format!("{}{}", constants::CRLF, boundary);
+
+        // The first boundary may start the body, or follow the preamble.
+        let mut pos = match body.starts_with(boundary.as_bytes()) {
+            true => boundary.len(),
+            false => find(&body, delimiter.as_bytes(), 0).ok_or(Error::IncompleteStream)? + delimiter.len(),
+        };
+
+        let mut parts = Vec::new();
+        loop {
+            if body[pos..].starts_with(constants::BOUNDARY_EXT.as_bytes()) {
+                return Ok(parts);
+            }
+
+            // Skip the transport padding and the line ending of the boundary.
+            pos += body[pos..].iter().take_while(|b| **b == b' ' || **b == b'\t').count();
+            if !body[pos..].starts_with(constants::CRLF.as_bytes()) {
+                return Err(Error::IncompleteStream);
+            }
+            pos += constants::CRLF.len();
+
+            let headers = match body[pos..].starts_with(constants::CRLF.as_bytes()) {
+                true => {
+                    pos += constants::CRLF.len();
+                    HeaderMap::new()
+                }
+                false => {
+                    let end = find(&body, constants::CRLF_CRLF.as_bytes(), pos).ok_or(Error::IncompleteHeaders)?
+                        + constants::CRLF_CRLF.len();
+                    let headers = parse_headers(&body[pos..end])?;
+                    pos = end;
+                    headers
+                }
+            };
+
+            let content_disposition = ContentDisposition::parse(&headers, false);
+            let end = find(&body, delimiter.as_bytes(), pos).ok_or_else(|| Error::IncompleteFieldData {
+                field_name: content_disposition.field_name.clone(),
+            })?;
+
+            parts.push(OwnedPart {
+                content_disposition,
+                content_type: helpers::parse_content_type(&headers),
+                data: body.slice(pos..end),
+                headers,
+            });
+
+            pos = end + delimiter.len();
+        }
+    }
+}
+
+fn find(body: &[u8], pattern: &[u8], from: usize) -> Option<usize> {
+    memchr::memmem::find(&body[from..], pattern).map(|idx| from + idx)
+}
+
+fn parse_headers(header_bytes: &[u8]) -> crate::Result<HeaderMap> {
+    let mut headers = [httparse::EMPTY_HEADER; constants::MAX_HEADERS];
+    match httparse::parse_headers(header_bytes, &mut headers).map_err(Error::ReadHeaderFailed)? {
+        httparse::Status::Complete((_, raw_headers)) => {
+            helpers::convert_raw_headers_to_header_map(raw_headers, DuplicateHeaderPolicy::default())
+        }
+        httparse::Status::Partial => Err(Error::IncompleteHeaders),
+    }
+}
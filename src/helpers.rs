@@ -1,9 +1,23 @@
 use std::convert::TryFrom;
 
+use bytes::Bytes;
 use http::header::{self, HeaderMap, HeaderName, HeaderValue};
 use httparse::Header;
 
-pub(crate) fn convert_raw_headers_to_header_map(raw_headers: &[Header<'_>]) -> crate::Result<HeaderMap> {
+use crate::constraints::DuplicateHeaderPolicy;
+
+/// The headers affecting how a field is parsed, which are subject to the
+/// [`DuplicateHeaderPolicy`].
+const POLICED_HEADERS: [HeaderName; 3] = [
+    header::CONTENT_DISPOSITION,
+    header::CONTENT_TYPE,
+    HeaderName::from_static("content-transfer-encoding"),
+];
+
+pub(crate) fn convert_raw_headers_to_header_map(
+    raw_headers: &[Header<'_>],
+    duplicate_policy: DuplicateHeaderPolicy,
+) -> crate::Result<HeaderMap> {
     let mut headers = HeaderMap::with_capacity(raw_headers.len());
 
     for raw_header in raw_headers {
@@ -17,15 +31,222 @@ pub(crate) fn convert_raw_headers_to_header_map(raw_headers: &[Header<'_>]) -> c
             cause: err.into(),
         })?;
 
+        if headers.contains_key(&name) && POLICED_HEADERS.contains(&name) {
+            match duplicate_policy {
+                DuplicateHeaderPolicy::FirstWins => continue,
+                DuplicateHeaderPolicy::LastWins => {}
+                DuplicateHeaderPolicy::Reject => {
+                    return Err(crate::Error::DuplicateHeader {
+                        name: name.as_str().to_owned(),
+                    })
+                }
+            }
+        }
+
         headers.insert(name, value);
     }
 
     Ok(headers)
 }
 
+/// Unfolds the header lines continued on the next line with leading
+/// whitespace (`obs-fold`), replacing each line break and the following
+/// whitespace with a single space.
+pub(crate) fn unfold_headers(header_bytes: Bytes) -> Bytes {
+    let is_fold = |lf: usize| matches!(header_bytes.get(lf + 1), Some(b' ' | b'\t'));
+
+    if !memchr::memchr_iter(b'\n', &header_bytes).any(is_fold) {
+        return header_bytes;
+    }
+
+    let mut unfolded = Vec::with_capacity(header_bytes.len());
+    let mut idx = 0;
+    while idx < header_bytes.len() {
+        match header_bytes[idx] {
+            b'\r' if header_bytes.get(idx + 1) == Some(&b'\n') && is_fold(idx + 1) => idx += 1,
+            b'\n' if is_fold(idx) => {
+                unfolded.push(b' ');
+                idx += 1;
+                while matches!(header_bytes.get(idx), Some(b' ' | b'\t')) {
+                    idx += 1;
+                }
+            }
+            byte => {
+                unfolded.push(byte);
+                idx += 1;
+            }
+        }
+    }
+
+    unfolded.into()
+}
+
+/// Strips the directory components and drive letter from a client-side path,
+/// returning `None` if no file name remains.
+pub(crate) fn strip_path(file_name: &str) -> Option<&str> {
+    let file_name = file_name.rsplit(['\\', '/']).next().unwrap_or(file_name);
+    let file_name = match file_name.as_bytes() {
+        [drive, b':', ..] if drive.is_ascii_alphabetic() => &file_name[2..],
+        _ => file_name,
+    };
+
+    match file_name {
+        "" | "." | ".." => None,
+        file_name => Some(file_name),
+    }
+}
+
+/// Matches a field name against a pattern where `*` stands for any sequence of
+/// characters, including none, and every other character matches itself.
+pub(crate) fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    let (pattern, name) = (pattern.as_bytes(), name.as_bytes());
+    let (mut p, mut n) = (0, 0);
+    let mut backtrack = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(ch) if *ch == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    backtrack = Some((star, matched + 1));
+                    p = star + 1;
+                    n = matched + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|ch| *ch == b'*')
+}
+
 pub(crate) fn parse_content_type(headers: &HeaderMap) -> Option<mime::Mime> {
     headers
         .get(header::CONTENT_TYPE)
         .and_then(|val| val.to_str().ok())
         .and_then(|val| val.parse::<mime::Mime>().ok())
 }
+
+pub(crate) fn parse_content_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|val| val.to_str().ok())
+        .and_then(|val| val.trim().parse::<u64>().ok())
+}
+
+pub(crate) fn parse_content_id(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get("content-id")
+        .and_then(|val| val.to_str().ok())
+        .map(crate::related::normalize_content_id)
+}
+
+/// Checks the boundary against the RFC 2046 grammar: 1 to 70 characters out
+/// of a restricted set, where spaces are allowed except at the end.
+pub(crate) fn validate_boundary(boundary: &str) -> crate::Result<()> {
+    fn is_bchar_nospace(ch: char) -> bool {
+        ch.is_ascii_alphanumeric() || "'()+_,-./:=?".contains(ch)
+    }
+
+    let invalid = |position: Option<usize>| crate::Error::InvalidBoundary {
+        boundary: boundary.to_owned(),
+        character: position.and_then(|pos| boundary[pos..].chars().next()),
+        position,
+    };
+
+    if boundary.is_empty() || boundary.len() > 70 {
+        return Err(invalid(None));
+    }
+
+    if let Some((pos, _)) = boundary
+        .char_indices()
+        .find(|(_, ch)| !is_bchar_nospace(*ch) && *ch != ' ')
+    {
+        return Err(invalid(Some(pos)));
+    }
+
+    if boundary.ends_with(' ') {
+        return Err(invalid(Some(boundary.len() - 1)));
+    }
+
+    Ok(())
+}
+
+/// Formats a header parameter value as a token, or as a quoted string if it
+/// contains characters which aren't allowed in a token, see RFC 2045.
+pub(crate) fn quote_param_value(value: &str) -> String {
+    fn is_token_char(ch: char) -> bool {
+        ch.is_ascii_graphic() && !"()<>@,;:\\\"/[]?=".contains(ch)
+    }
+
+    if !value.is_empty() && value.chars().all(is_token_char) {
+        return value.to_owned();
+    }
+
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for ch in value.chars() {
+        if ch == '"' || ch == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(ch);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// The size of the reads of [`futures_reader_stream()`].
+#[cfg(feature = "futures-io")]
+const READ_BUF_SIZE: usize = 4096;
+
+/// Reads a `futures::io::AsyncRead` reader as a stream of [`Bytes`] chunks,
+/// like `tokio_util::io::ReaderStream` does for tokio readers.
+#[cfg(feature = "futures-io")]
+pub(crate) fn futures_reader_stream<R>(reader: R) -> impl futures_util::stream::Stream<Item = std::io::Result<Bytes>>
+where
+    R: futures_util::io::AsyncRead + Unpin,
+{
+    use bytes::BytesMut;
+    use futures_util::io::AsyncReadExt;
+
+    futures_util::stream::unfold((reader, BytesMut::new()), |(mut reader, mut buf)| async move {
+        buf.resize(READ_BUF_SIZE, 0);
+        match reader.read(&mut buf).await {
+            Ok(0) => None,
+            Ok(n) => Some((Ok(buf.split_to(n).freeze()), (reader, buf))),
+            Err(err) => Some((Err(err), (reader, buf))),
+        }
+    })
+}
+
+/// Reads the data frames of an `http_body::Body` as a stream of [`Bytes`]
+/// chunks, skipping its trailers.
+#[cfg(feature = "http-body")]
+pub(crate) fn body_stream<B>(body: B) -> impl futures_util::stream::Stream<Item = Result<Bytes, B::Error>>
+where
+    B: http_body::Body,
+{
+    use std::task::Poll;
+
+    use bytes::Buf;
+
+    let mut body = Box::pin(body);
+    futures_util::stream::poll_fn(move |cx| loop {
+        return match body.as_mut().poll_frame(cx) {
+            Poll::Ready(Some(Ok(frame))) => match frame.into_data() {
+                Ok(mut data) => Poll::Ready(Some(Ok(data.copy_to_bytes(data.remaining())))),
+                Err(_trailers) => continue,
+            },
+            Poll::Ready(Some(Err(err))) => Poll::Ready(Some(Err(err))),
+            Poll::Ready(None) => Poll::Ready(None),
+            Poll::Pending => Poll::Pending,
+        };
+    })
+}
@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use crate::field::Field;
+
+/// A hook inspecting the data of each field as it streams through the
+/// parser, e.g. to feed a virus scanner or a checksum service, set with
+/// [`Constraints::inspect()`](crate::Constraints::inspect).
+///
+/// The hook sees the data as returned to the application, i.e. after any
+/// decoding, and is called on the task reading the field. It may veto the
+/// field or the whole request with a [`Veto`].
+///
+/// # Examples
+///
+/// ```
+/// use std::collections::HashMap;
+/// use std::sync::Mutex;
+///
+/// use multer::{Constraints, Field, FieldInspector, Multipart, Veto};
+///
+/// #[derive(Default)]
+/// struct Scanner {
+///     sizes: Mutex<HashMap<usize, usize>>,
+/// }
+///
+/// impl FieldInspector for Scanner {
+///     fn inspect_chunk(&self, field: &Field<'_>, chunk: &[u8]) -> Result<(), Veto> {
+///         if chunk.windows(6).any(|window| window == b"EICAR!") {
+///             return Err(Veto::request("malware detected"));
+///         }
+///
+///         *self.sizes.lock().unwrap().entry(field.index()).or_default() += chunk.len();
+///         Ok(())
+///     }
+/// }
+///
+/// # async fn run() {
+/// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"; filename=\"a.txt\"\r\n\r\nEICAR!\r\n\
+///     --X-BOUNDARY--\r\n";
+/// let constraints = Constraints::new().inspect(Scanner::default());
+/// let mut multipart = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+///
+/// let field = multipart.next_field().await.unwrap().unwrap();
+/// assert!(matches!(
+///     field.bytes().await,
+///     Err(multer::Error::RequestRejected { .. })
+/// ));
+/// assert!(multipart.next_field().await.unwrap().is_none());
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+/// ```
+pub trait FieldInspector: Send + Sync {
+    /// Called with each chunk of the data of a field, as it is read.
+    fn inspect_chunk(&self, field: &Field<'_>, chunk: &[u8]) -> Result<(), Veto>;
+
+    /// Called once the data of a field has been read to its end, e.g. to
+    /// check a checksum.
+    ///
+    /// It isn't called for a field which isn't read to its end, or which has
+    /// been vetoed.
+    fn end_field(&self, field: &Field<'_>) -> Result<(), Veto> {
+        let _ = field;
+        Ok(())
+    }
+}
+
+impl<I: FieldInspector + ?Sized> FieldInspector for Arc<I> {
+    fn inspect_chunk(&self, field: &Field<'_>, chunk: &[u8]) -> Result<(), Veto> {
+        (**self).inspect_chunk(field, chunk)
+    }
+
+    fn end_field(&self, field: &Field<'_>) -> Result<(), Veto> {
+        (**self).end_field(field)
+    }
+}
+
+/// The veto of a [`FieldInspector`], rejecting a field or the whole
+/// request.
+#[derive(Debug)]
+pub enum Veto {
+    /// Reject the field, failing its read with
+    /// [`Error::FieldRejected`](crate::Error::FieldRejected). The next fields
+    /// can still be read.
+    Field(Box<dyn std::error::Error + Send + Sync>),
+    /// Reject the whole request, failing the read of the field with
+    /// [`Error::RequestRejected`](crate::Error::RequestRejected).
+    /// [`next_field()`](crate::Multipart::next_field) then returns `None`.
+    Request(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl Veto {
+    /// Rejects the field with the given cause.
+    pub fn field<E: Into<Box<dyn std::error::Error + Send + Sync>>>(cause: E) -> Veto {
+        Veto::Field(cause.into())
+    }
+
+    /// Rejects the whole request with the given cause.
+    pub fn request<E: Into<Box<dyn std::error::Error + Send + Sync>>>(cause: E) -> Veto {
+        Veto::Request(cause.into())
+    }
+}
@@ -120,12 +120,48 @@
 #![doc(test(attr(deny(rust_2018_idioms, warnings))))]
 #![doc(test(attr(allow(unused_extern_crates, unused_variables))))]
 
+pub use budget::MemoryBudget;
+pub use byteranges::ContentRange;
 pub use bytes;
-pub use constraints::Constraints;
+#[cfg(all(feature = "serde", feature = "tokio-time"))]
+pub use config::RateLimitConfig;
+#[cfg(feature = "serde")]
+pub use config::{ConstraintsConfig, MinThroughputConfig, SizeLimitConfig};
+pub use constraints::{Constraints, DuplicateHeaderPolicy, ExtensionPolicy, FileContentTypePolicy, UnknownFieldPolicy};
 pub use error::Error;
 pub use field::Field;
+pub use form::{FilePart, MultipartForm};
+#[cfg(feature = "urlencoded")]
+pub use form_data::{FormData, FormField};
+#[cfg(any(feature = "serde", feature = "derive"))]
+pub use form_file::FormFile;
+#[cfg(all(any(feature = "serde", feature = "derive"), feature = "tokio-fs"))]
+pub use form_file::SpooledFormFile;
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub use from_multipart::__private;
+#[cfg(feature = "derive")]
+pub use from_multipart::{FromField, FromMultipart};
+pub use full::OwnedPart;
+pub use inspect::{FieldInspector, Veto};
+#[cfg(feature = "derive")]
+#[cfg_attr(nightly, doc(cfg(feature = "derive")))]
+pub use multer_derive::FromMultipart;
 pub use multipart::Multipart;
+pub use owned::{OwnedField, OwnedFields};
+#[cfg(any(feature = "tokio-io", feature = "futures-io"))]
+pub use reader::FieldReader;
+pub use related::{RelatedMultipart, RelatedPart, RelatedParts};
+#[cfg(feature = "http-body")]
+pub use request::RequestExt;
+pub use s3::S3PostForm;
 pub use size_limit::SizeLimit;
+#[cfg(feature = "tokio-fs")]
+pub use spool::SpooledField;
+#[cfg(feature = "tokio-uring")]
+pub use uring::UringOptions;
+pub use violation::{Constraint, ConstraintViolation};
+pub use writer::{MultipartStream, MultipartWriter, Part, WriteProgress};
 
 #[cfg(feature = "log")]
 macro_rules! trace {
@@ -137,15 +173,73 @@ macro_rules! trace {
     ($($t:tt)*) => {};
 }
 
+#[cfg(feature = "log")]
+macro_rules! warn {
+    ($($t:tt)*) => (::log::warn!($($t)*););
+}
+
+#[cfg(not(feature = "log"))]
+macro_rules! warn {
+    ($($t:tt)*) => {};
+}
+
+#[cfg(feature = "axum")]
+#[cfg_attr(nightly, doc(cfg(feature = "axum")))]
+pub mod axum;
+pub mod blocking;
+mod budget;
 mod buffer;
+mod byteranges;
+#[cfg(feature = "cancellation")]
+mod cancel;
+#[cfg(feature = "serde")]
+mod config;
 mod constants;
 mod constraints;
 mod content_disposition;
+#[cfg(feature = "content-encoding")]
+mod content_encoding;
+#[cfg(feature = "serde")]
+mod deserialize;
 mod error;
 mod field;
+mod form;
+#[cfg(feature = "urlencoded")]
+mod form_data;
+#[cfg(any(feature = "serde", feature = "derive"))]
+mod form_file;
+#[cfg(feature = "derive")]
+mod from_multipart;
+#[cfg(feature = "tokio-fs")]
+mod fs;
+mod full;
 mod helpers;
+mod inspect;
 mod multipart;
+mod owned;
+#[cfg(feature = "tokio-time")]
+mod rate_limit;
+#[cfg(any(feature = "tokio-io", feature = "futures-io"))]
+mod reader;
+mod related;
+#[cfg(feature = "http-body")]
+mod request;
+mod s3;
 mod size_limit;
+mod sniff;
+#[cfg(feature = "tokio-fs")]
+mod spool;
+mod throughput;
+#[cfg(feature = "tokio-time")]
+mod timeout;
+mod transfer_encoding;
+#[cfg(feature = "tokio-uring")]
+mod uring;
+mod violation;
+#[cfg(feature = "warp")]
+#[cfg_attr(nightly, doc(cfg(feature = "warp")))]
+pub mod warp;
+mod writer;
 
 /// A Result type often returned from methods that can have `multer` errors.
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -180,6 +274,122 @@ pub fn parse_boundary<T: AsRef<str>>(content_type: T) -> Result<String> {
         .ok_or(Error::NoBoundary)
 }
 
+/// Parses the `Content-Type` header to extract the boundary value, and
+/// validates it against the RFC 2046 grammar.
+///
+/// Unlike [`parse_boundary()`], which accepts any boundary, this fails with
+/// [`Error::InvalidBoundary`] if the boundary is empty, longer than 70
+/// characters, contains characters other than letters, digits, spaces and
+/// `'()+_,-./:=?`, or ends with a space.
+///
+/// # Examples
+///
+/// ```
+/// # fn run(){
+/// let content_type = "multipart/form-data; boundary=ABCDEFG";
+/// assert_eq!(
+///     multer::parse_boundary_strict(content_type),
+///     Ok("ABCDEFG".to_owned())
+/// );
+///
+/// let content_type = "multipart/form-data; boundary=\"ABC{DEFG\"";
+/// assert!(matches!(
+///     multer::parse_boundary_strict(content_type),
+///     Err(multer::Error::InvalidBoundary {
+///         character: Some('{'),
+///         position: Some(3),
+///         ..
+///     })
+/// ));
+/// # }
+/// # run();
+/// ```
+pub fn parse_boundary_strict<T: AsRef<str>>(content_type: T) -> Result<String> {
+    let boundary = parse_boundary(content_type)?;
+    helpers::validate_boundary(&boundary)?;
+    Ok(boundary)
+}
+
+/// Parses the `Content-Type` header of any `multipart/*` body, e.g.
+/// `multipart/mixed`, `multipart/alternative` or `multipart/digest`, to extract
+/// the boundary value.
+///
+/// Unlike [`parse_boundary()`], this doesn't assume `multipart/form-data`, so
+/// that arbitrary MIME multiparts can be iterated as raw parts with
+/// [`Multipart`]. The parts of such bodies typically have no
+/// `Content-Disposition` name, so [`Field::name()`] returns `None` and the
+/// part is identified by its [`headers()`](Field::headers) instead.
+///
+/// # Examples
+///
+/// ```
+/// use multer::Multipart;
+///
+/// # async fn run() {
+/// let content_type = "multipart/alternative; boundary=X-BOUNDARY";
+/// let data = "--X-BOUNDARY\r\nContent-Type: text/plain\r\n\r\nHello\r\n\
+///     --X-BOUNDARY\r\nContent-Type: text/html\r\n\r\n<p>Hello</p>\r\n--X-BOUNDARY--\r\n";
+///
+/// let boundary = multer::parse_mime_boundary(content_type).unwrap();
+/// let mut multipart = Multipart::with_bytes(data, boundary);
+///
+/// while let Some(field) = multipart.next_field().await.unwrap() {
+///     assert!(field.name().is_none());
+///     println!("Content-Type: {:?}", field.content_type());
+///     println!("Content: {:?}", field.text().await);
+/// }
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+/// ```
+pub fn parse_mime_boundary<T: AsRef<str>>(content_type: T) -> Result<String> {
+    let m = content_type
+        .as_ref()
+        .parse::<mime::Mime>()
+        .map_err(Error::DecodeContentType)?;
+
+    if m.type_() != mime::MULTIPART {
+        return Err(Error::NoMultipart);
+    }
+
+    m.get_param(mime::BOUNDARY)
+        .map(|name| name.as_str().to_owned())
+        .ok_or(Error::NoBoundary)
+}
+
+/// Builds the `Content-Type` header value of a `multipart/form-data` body
+/// with the given boundary, the reverse of [`parse_boundary()`].
+///
+/// The boundary is quoted if it contains characters which aren't allowed in
+/// a bare parameter value, e.g. spaces or `:`.
+///
+/// # Examples
+///
+/// ```
+/// # fn run(){
+/// assert_eq!(
+///     multer::content_type("ABCDEFG"),
+///     "multipart/form-data; boundary=ABCDEFG"
+/// );
+/// assert_eq!(
+///     multer::content_type("ABC:DEF G"),
+///     "multipart/form-data; boundary=\"ABC:DEF G\""
+/// );
+///
+/// let content_type = multer::content_type("ABC:DEF G");
+/// assert_eq!(multer::parse_boundary(content_type), Ok("ABC:DEF G".to_owned()));
+/// # }
+/// # run();
+/// ```
+pub fn content_type<T: AsRef<str>>(boundary: T) -> String {
+    format!(
+        "{}/{}; {}={}",
+        mime::MULTIPART,
+        mime::FORM_DATA,
+        mime::BOUNDARY,
+        helpers::quote_param_value(boundary.as_ref())
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,4 +411,74 @@ mod tests {
         let content_type = "text/plain; boundary=------ABCDEFG";
         assert!(parse_boundary(content_type).is_err());
     }
+
+    #[test]
+    fn test_parse_mime_boundary() {
+        let content_type = "multipart/mixed; boundary=ABCDEFG";
+        assert_eq!(parse_mime_boundary(content_type), Ok("ABCDEFG".to_owned()));
+
+        let content_type = "multipart/digest; boundary=\"--ABC DEF\"";
+        assert_eq!(parse_mime_boundary(content_type), Ok("--ABC DEF".to_owned()));
+
+        let content_type = "multipart/form-data; boundary=ABCDEFG";
+        assert_eq!(parse_mime_boundary(content_type), Ok("ABCDEFG".to_owned()));
+
+        let content_type = "multipart/alternative";
+        assert!(parse_mime_boundary(content_type).is_err());
+
+        let content_type = "text/plain; boundary=ABCDEFG";
+        assert!(parse_mime_boundary(content_type).is_err());
+    }
+
+    #[test]
+    fn test_content_type() {
+        assert_eq!(content_type("ABCDEFG"), "multipart/form-data; boundary=ABCDEFG");
+        assert_eq!(
+            content_type("----Web Kit'()+_,-./:=?"),
+            "multipart/form-data; boundary=\"----Web Kit'()+_,-./:=?\""
+        );
+        assert_eq!(content_type("a\"b\\c"), "multipart/form-data; boundary=\"a\\\"b\\\\c\"");
+
+        for boundary in ["ABCDEFG", "----Web Kit'()+_,-./:=?", "a=b", "a:b"] {
+            assert_eq!(parse_boundary(content_type(boundary)), Ok(boundary.to_owned()));
+        }
+    }
+
+    #[test]
+    fn test_parse_boundary_strict() {
+        let content_type = "multipart/form-data; boundary=\"----Web Kit'()+_,-./:=?\"";
+        assert_eq!(
+            parse_boundary_strict(content_type),
+            Ok("----Web Kit'()+_,-./:=?".to_owned())
+        );
+
+        let content_type = format!("multipart/form-data; boundary={}", "a".repeat(70));
+        assert!(parse_boundary_strict(content_type).is_ok());
+
+        let content_type = format!("multipart/form-data; boundary={}", "a".repeat(71));
+        assert!(matches!(
+            parse_boundary_strict(content_type),
+            Err(Error::InvalidBoundary { position: None, .. })
+        ));
+
+        let content_type = "multipart/form-data; boundary=\"ABC \"";
+        assert!(matches!(
+            parse_boundary_strict(content_type),
+            Err(Error::InvalidBoundary {
+                character: Some(' '),
+                position: Some(3),
+                ..
+            })
+        ));
+
+        let content_type = "multipart/form-data; boundary=\"AB@C\"";
+        assert!(matches!(
+            parse_boundary_strict(content_type),
+            Err(Error::InvalidBoundary {
+                character: Some('@'),
+                position: Some(2),
+                ..
+            })
+        ));
+    }
 }
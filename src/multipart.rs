@@ -1,18 +1,34 @@
+use std::collections::HashSet;
+use std::convert::Infallible;
+use std::fmt::{self, Debug};
+use std::pin::Pin;
 use std::sync::Arc;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, Waker};
 
 use bytes::Bytes;
-use futures_util::future;
-use futures_util::stream::{Stream, TryStreamExt};
+use futures_util::future::{self, BoxFuture};
+use futures_util::ready;
+use futures_util::stream::{self, Stream, TryStreamExt};
 use spin::mutex::spin::SpinMutex as Mutex;
 #[cfg(feature = "tokio-io")]
 use {tokio::io::AsyncRead, tokio_util::io::ReaderStream};
 
-use crate::buffer::StreamBuffer;
-use crate::constraints::Constraints;
+use crate::buffer::{RawCapture, StreamBuffer};
+#[cfg(feature = "cancellation")]
+use crate::cancel::Cancellation;
+use crate::constraints::{Constraints, Progress, UnknownFieldPolicy, Validator};
 use crate::content_disposition::ContentDisposition;
+#[cfg(feature = "content-encoding")]
+use crate::content_encoding::ContentEncoding;
 use crate::error::Error;
 use crate::field::Field;
+#[cfg(feature = "tokio-time")]
+use crate::rate_limit::TokenBucket;
+use crate::sniff::ContentSniffer;
+use crate::throughput::MinThroughput;
+#[cfg(feature = "tokio-time")]
+use crate::timeout::{Deadline, IdleTimeout};
+use crate::transfer_encoding::TransferEncoding;
 use crate::{constants, helpers, Result};
 
 /// Represents the implementation of `multipart/form-data` formatted data.
@@ -26,6 +42,8 @@ use crate::{constants, helpers, Result};
 /// such, only _one_ `Field` from a given `Multipart` instance may be live at
 /// once. That is, a `Field` emitted by `next_field()` must be dropped before
 /// calling `next_field()` again. Failure to do so will result in an error.
+/// Polled as a [`Stream`] instead, the `Multipart` waits for the previous
+/// field to be dropped.
 ///
 /// ```rust
 /// use std::convert::Infallible;
@@ -74,6 +92,12 @@ use crate::{constants, helpers, Result};
 #[derive(Debug)]
 pub struct Multipart<'r> {
     state: Arc<Mutex<MultipartState<'r>>>,
+    boundary: String,
+    /// The field parsed by [`Multipart::peek_field()`], returned next.
+    peeked: Option<Field<'r>>,
+    /// The field being checked by the validators, returned once they all
+    /// pass.
+    validating: Option<Box<Validation<'r>>>,
 }
 
 #[derive(Debug)]
@@ -82,10 +106,28 @@ pub(crate) struct MultipartState<'r> {
     pub(crate) boundary: String,
     pub(crate) stage: StreamingStage,
     pub(crate) next_field_idx: usize,
+    pub(crate) file_count: usize,
+    pub(crate) missing_required_fields: Vec<String>,
+    pub(crate) seen_field_names: HashSet<String>,
+    pub(crate) violation: Option<Error>,
     pub(crate) curr_field_name: Option<String>,
     pub(crate) curr_field_size_limit: u64,
+    pub(crate) curr_field_truncate: bool,
+    pub(crate) curr_field_reject_empty: bool,
     pub(crate) curr_field_size_counter: u64,
+    pub(crate) curr_field_expected_size: Option<u64>,
     pub(crate) constraints: Constraints,
+    pub(crate) preamble: Option<Bytes>,
+    pub(crate) epilogue: Option<Bytes>,
+    pub(crate) charset_field: Option<Vec<u8>>,
+    pub(crate) form_charset: Option<String>,
+    pub(crate) depth: usize,
+    /// The task polling the `Stream` of the `Multipart`, woken once the live
+    /// field is dropped and has released the state.
+    pub(crate) field_waker: Arc<Mutex<Option<Waker>>>,
+    /// The progress to report once the state is unlocked, see
+    /// [`report_progress()`].
+    pub(crate) progress: Vec<Progress>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -96,9 +138,130 @@ pub(crate) enum StreamingStage {
     ReadingTransportPadding,
     ReadingFieldHeaders,
     ReadingFieldData,
+    SkippingPart,
+    Draining,
     Eof,
 }
 
+impl MultipartState<'_> {
+    /// The longest `_charset_` field value that is tracked.
+    const MAX_CHARSET_LEN: usize = 64;
+
+    /// Records the progress of the data consumed so far, if a callback is set
+    /// with [`Constraints::on_progress()`]. The data drained from the stream
+    /// doesn't belong to any field.
+    pub(crate) fn record_progress(&mut self) {
+        let handler = match &self.constraints.on_progress {
+            Some(handler) => handler.clone(),
+            None => return,
+        };
+
+        let (field_name, field_bytes) = match self.stage {
+            StreamingStage::Draining | StreamingStage::Eof => (None, 0),
+            _ => (self.curr_field_name.clone(), self.curr_field_size_counter),
+        };
+        self.progress.push(Progress {
+            handler,
+            field_name,
+            field_bytes,
+            total_bytes: self.buffer.stream_size_counter - self.buffer.len() as u64,
+        });
+    }
+
+    /// Reads and discards the rest of the stream, recording the progress of
+    /// the data discarded.
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let consumed = |buffer: &StreamBuffer<'_>| buffer.stream_size_counter - buffer.len() as u64;
+        let before = consumed(&self.buffer);
+        let poll = self.buffer.poll_drain(cx);
+        if consumed(&self.buffer) != before {
+            self.record_progress();
+        }
+
+        poll
+    }
+
+    /// Fails if any of the required fields has not been found by the end of
+    /// the stream.
+    fn check_required_fields(&self) -> Result<()> {
+        match self.missing_required_fields.is_empty() {
+            true => Ok(()),
+            false => Err(Error::MissingRequiredFields {
+                field_names: self.missing_required_fields.clone(),
+            }),
+        }
+    }
+
+    /// Fails at the end of a file field without data, see
+    /// [`Constraints::reject_empty_files()`].
+    pub(crate) fn check_empty_file(&self, done: bool) -> Result<()> {
+        match done && self.curr_field_reject_empty && self.curr_field_size_counter == 0 {
+            true => Err(Error::EmptyFile {
+                field_name: self.curr_field_name.clone(),
+            }),
+            false => Ok(()),
+        }
+    }
+
+    /// Records the error if it's a constraint violation and the stream is to
+    /// be drained after it, see [`Constraints::drain_on_violation()`].
+    pub(crate) fn record_violation(&mut self, err: &Error) {
+        if !self.constraints.drain_on_violation || self.violation.is_some() {
+            return;
+        }
+
+        if let Some(violation) = err.clone_violation() {
+            self.violation = Some(violation);
+            self.missing_required_fields.clear();
+            self.stage = StreamingStage::Draining;
+        }
+    }
+
+    /// Whether the error was caused by an unparseable header block or boundary
+    /// delimiter, which parsing can recover from by skipping to the next
+    /// boundary.
+    fn is_malformed_part(&self, err: &Error) -> bool {
+        match self.stage {
+            StreamingStage::ReadingFieldHeaders => matches!(
+                err,
+                Error::ReadHeaderFailed(_)
+                    | Error::DecodeHeaderName { .. }
+                    | Error::DecodeHeaderValue { .. }
+                    | Error::IncompleteHeaders
+                    | Error::DuplicateHeader { .. }
+            ),
+            StreamingStage::ReadingBoundary
+            | StreamingStage::DeterminingBoundaryType
+            | StreamingStage::ReadingTransportPadding => {
+                matches!(err, Error::IncompleteStream) && !(self.buffer.eof && self.buffer.len() == 0)
+            }
+            _ => false,
+        }
+    }
+
+    /// Collects the data of a `_charset_` field, if it is being tracked, and
+    /// records its value once the field is complete.
+    pub(crate) fn track_charset_field(&mut self, bytes: &[u8], done: bool) {
+        let value = match self.charset_field.as_mut() {
+            Some(value) => value,
+            None => return,
+        };
+
+        if value.len() + bytes.len() > Self::MAX_CHARSET_LEN {
+            self.charset_field = None;
+            return;
+        }
+
+        value.extend_from_slice(bytes);
+        if done {
+            if let Some(value) = self.charset_field.take() {
+                let value = String::from_utf8_lossy(&value);
+                self.form_charset = Some(value.trim().to_owned());
+            }
+        }
+    }
+}
+
 impl<'r> Multipart<'r> {
     /// Construct a new `Multipart` instance with the given [`Bytes`] stream and
     /// the boundary.
@@ -125,20 +288,112 @@ impl<'r> Multipart<'r> {
             .map_ok(|b| b.into())
             .map_err(|err| Error::StreamReadFailed(err.into()));
 
+        let mut buffer = StreamBuffer::new(
+            stream,
+            constraints.size_limit.whole_stream,
+            constraints.allow_lf_line_endings,
+        );
+        buffer.memory_budget = constraints.memory_budget.clone();
+        buffer.min_throughput = constraints
+            .min_throughput
+            .map(|(min_bytes, window)| MinThroughput::new(min_bytes, window));
+        #[cfg(feature = "tokio-time")]
+        {
+            buffer.deadline = constraints.deadline.and_then(Deadline::new);
+            buffer.read_timeout = constraints.read_timeout.map(IdleTimeout::new);
+            buffer.rate_limit = constraints
+                .rate_limit
+                .map(|(bytes_per_sec, burst)| TokenBucket::new(bytes_per_sec, burst));
+        }
+        buffer.raw = constraints.capture_raw.then(RawCapture::default);
+        #[cfg(feature = "cancellation")]
+        {
+            buffer.cancellation = constraints.cancellation.clone().map(Cancellation::new);
+        }
+
+        let boundary = boundary.into();
         Multipart {
             state: Arc::new(Mutex::new(MultipartState {
-                buffer: StreamBuffer::new(stream, constraints.size_limit.whole_stream),
-                boundary: boundary.into(),
+                buffer,
+                boundary: boundary.clone(),
                 stage: StreamingStage::FindingFirstBoundary,
                 next_field_idx: 0,
+                file_count: 0,
+                missing_required_fields: constraints.required_fields.clone(),
+                seen_field_names: HashSet::new(),
+                violation: None,
                 curr_field_name: None,
                 curr_field_size_limit: constraints.size_limit.per_field,
+                curr_field_truncate: false,
+                curr_field_reject_empty: false,
                 curr_field_size_counter: 0,
+                curr_field_expected_size: None,
                 constraints,
+                preamble: None,
+                epilogue: None,
+                charset_field: None,
+                form_charset: None,
+                depth: 0,
+                field_waker: Arc::new(Mutex::new(None)),
+                progress: Vec::new(),
             })),
+            boundary,
+            peeked: None,
+            validating: None,
         }
     }
 
+    /// Sets the nesting depth of a body parsed from a field of another one.
+    pub(crate) fn with_depth(self, depth: usize) -> Self {
+        self.state.lock().depth = depth;
+        self
+    }
+
+    /// Construct a new `Multipart` instance over an already buffered body and
+    /// the boundary.
+    ///
+    /// The body may be any contiguous buffer convertible to [`Bytes`], e.g. a
+    /// memory-mapped file wrapped with [`Bytes::from_owner()`]. The chunks of
+    /// the yielded fields are zero-copy slices of `data`, which makes this the
+    /// preferred way to reprocess stored raw requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data =
+    ///     "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// while let Some(field) = multipart.next_field().await.unwrap() {
+    ///     assert_eq!(field.bytes().await.unwrap(), "abcd");
+    /// }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn with_bytes<D, B>(data: D, boundary: B) -> Self
+    where
+        D: Into<Bytes>,
+        B: Into<String>,
+    {
+        Multipart::with_bytes_with_constraints(data, boundary, Constraints::default())
+    }
+
+    /// Construct a new `Multipart` instance over an already buffered body, the
+    /// boundary and the constraints.
+    ///
+    /// See [`Multipart::with_bytes()`] for details.
+    pub fn with_bytes_with_constraints<D, B>(data: D, boundary: B, constraints: Constraints) -> Self
+    where
+        D: Into<Bytes>,
+        B: Into<String>,
+    {
+        let stream = stream::once(future::ready(Ok::<_, Infallible>(data.into())));
+        Multipart::with_constraints(stream, boundary, constraints)
+    }
+
     /// Construct a new `Multipart` instance with the given [`AsyncRead`] reader
     /// and the boundary.
     ///
@@ -213,6 +468,121 @@ impl<'r> Multipart<'r> {
         Multipart::with_constraints(stream, boundary, constraints)
     }
 
+    /// Construct a new `Multipart` instance with the given
+    /// `futures::io::AsyncRead` reader and the boundary, e.g. an `async-std`
+    /// or `smol` socket or file.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `futures-io` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data =
+    ///     "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let reader = futures_util::io::Cursor::new(data);
+    /// let mut multipart = Multipart::with_futures_reader(reader, "X-BOUNDARY");
+    ///
+    /// while let Some(mut field) = multipart.next_field().await.unwrap() {
+    ///     while let Some(chunk) = field.chunk().await.unwrap() {
+    ///         println!("Chunk: {:?}", chunk);
+    ///     }
+    /// }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    #[cfg(feature = "futures-io")]
+    #[cfg_attr(nightly, doc(cfg(feature = "futures-io")))]
+    pub fn with_futures_reader<R, B>(reader: R, boundary: B) -> Self
+    where
+        R: futures_util::io::AsyncRead + Unpin + Send + 'r,
+        B: Into<String>,
+    {
+        Multipart::with_futures_reader_with_constraints(reader, boundary, Constraints::default())
+    }
+
+    /// Construct a new `Multipart` instance with the given
+    /// `futures::io::AsyncRead` reader, the boundary and the constraints.
+    ///
+    /// See [`Multipart::with_futures_reader()`] for details.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `futures-io` feature to be enabled.
+    #[cfg(feature = "futures-io")]
+    #[cfg_attr(nightly, doc(cfg(feature = "futures-io")))]
+    pub fn with_futures_reader_with_constraints<R, B>(reader: R, boundary: B, constraints: Constraints) -> Self
+    where
+        R: futures_util::io::AsyncRead + Unpin + Send + 'r,
+        B: Into<String>,
+    {
+        let stream = helpers::futures_reader_stream(reader);
+        Multipart::with_constraints(stream, boundary, constraints)
+    }
+
+    /// Construct a new `Multipart` instance with the given `http_body::Body`
+    /// and the boundary, e.g. a hyper 1.x `Incoming` body or an axum `Body`.
+    ///
+    /// The data frames of the body are parsed, and its trailers are ignored.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `http-body` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use bytes::Bytes;
+    /// use http_body_util::Full;
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data =
+    ///     "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let body = Full::new(Bytes::from(data));
+    /// let mut multipart = Multipart::from_body(body, "X-BOUNDARY");
+    ///
+    /// while let Some(field) = multipart.next_field().await.unwrap() {
+    ///     assert_eq!(field.text().await.unwrap(), "abcd");
+    /// }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    #[cfg(feature = "http-body")]
+    #[cfg_attr(nightly, doc(cfg(feature = "http-body")))]
+    pub fn from_body<T, B>(body: T, boundary: B) -> Self
+    where
+        T: http_body::Body + Send + 'r,
+        T::Error: Into<Box<dyn std::error::Error + Send + Sync>> + 'r,
+        B: Into<String>,
+    {
+        Multipart::from_body_with_constraints(body, boundary, Constraints::default())
+    }
+
+    /// Construct a new `Multipart` instance with the given `http_body::Body`,
+    /// the boundary and the constraints.
+    ///
+    /// See [`Multipart::from_body()`] for details.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `http-body` feature to be enabled.
+    #[cfg(feature = "http-body")]
+    #[cfg_attr(nightly, doc(cfg(feature = "http-body")))]
+    pub fn from_body_with_constraints<T, B>(body: T, boundary: B, constraints: Constraints) -> Self
+    where
+        T: http_body::Body + Send + 'r,
+        T::Error: Into<Box<dyn std::error::Error + Send + Sync>> + 'r,
+        B: Into<String>,
+    {
+        let stream = helpers::body_stream(body);
+        Multipart::with_constraints(stream, boundary, constraints)
+    }
+
     /// Yields the next [`Field`] if available.
     ///
     /// Any previous `Field` returned by this method must be dropped before
@@ -222,6 +592,133 @@ impl<'r> Multipart<'r> {
         future::poll_fn(|cx| self.poll_next_field(cx)).await
     }
 
+    /// Skips the next field, discarding its data with [`Field::skip()`].
+    ///
+    /// Returns `false` if there are no more fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nefgh\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// assert!(multipart.skip_field().await.unwrap());
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert_eq!(field.name(), Some("b"));
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub async fn skip_field(&mut self) -> Result<bool> {
+        match self.next_field().await? {
+            Some(field) => {
+                field.skip();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Reads and discards the rest of the stream, without looking for
+    /// boundaries or buffering it, e.g. to keep the connection usable after
+    /// rejecting an upload mid-way.
+    ///
+    /// The size limit of the whole stream isn't enforced while draining, and
+    /// [`next_field()`](Self::next_field) returns `None` afterwards. Any
+    /// [`Field`] still held must be dropped first, otherwise this fails with
+    /// [`Error::LockFailure`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nefgh\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert_eq!(field.name(), Some("a"));
+    /// drop(field);
+    ///
+    /// // Reject the upload, but read the request to its end.
+    /// multipart.drain().await.unwrap();
+    /// assert!(multipart.next_field().await.unwrap().is_none());
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub async fn drain(&mut self) -> Result<()> {
+        self.peeked = None;
+        self.validating = None;
+        future::poll_fn(|cx| {
+            let poll = self.poll_drain(cx);
+            report_progress(&self.state);
+            poll
+        })
+        .await
+    }
+
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        let mut lock = match self.state.try_lock() {
+            Some(lock) if Arc::strong_count(&self.state) == 1 => lock,
+            _ => return Poll::Ready(Err(Error::LockFailure)),
+        };
+
+        // Any later call to `next_field()` carries on draining.
+        let state = &mut *lock;
+        state.stage = StreamingStage::Draining;
+        state.missing_required_fields.clear();
+
+        match state.poll_drain(cx) {
+            Poll::Ready(Ok(())) => {
+                state.stage = StreamingStage::Eof;
+                Poll::Ready(Ok(()))
+            }
+            Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    /// Parses the headers of the next field without reading its data, so that
+    /// its name, file name or content type can be inspected before deciding
+    /// how to process it.
+    ///
+    /// The peeked field is returned by the next call to
+    /// [`next_field()`](Self::next_field), and calling `peek_field()` again
+    /// returns the same field. To skip it, drop the field returned by
+    /// `next_field()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; \
+    ///     filename=\"a.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.peek_field().await.unwrap().unwrap();
+    /// assert_eq!(field.file_name(), Some("a.txt"));
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert_eq!(field.text().await.unwrap(), "abcd");
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub async fn peek_field(&mut self) -> Result<Option<&Field<'r>>> {
+        if self.peeked.is_none() {
+            self.peeked = self.next_field().await?;
+        }
+
+        Ok(self.peeked.as_ref())
+    }
+
     /// Yields the next [`Field`] if available.
     ///
     /// Any previous `Field` returned by this method must be dropped before
@@ -230,6 +727,63 @@ impl<'r> Multipart<'r> {
     ///
     /// This method is available since version 2.1.0.
     pub fn poll_next_field(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Field<'r>>>> {
+        if let Some(field) = self.peeked.take() {
+            return Poll::Ready(Ok(Some(field)));
+        }
+
+        let poll = self.poll_next_field_skipping(cx);
+        report_progress(&self.state);
+        poll
+    }
+
+    /// Polls for the next field, skipping the parts which are ignored.
+    fn poll_next_field_skipping(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Field<'r>>>> {
+        loop {
+            let err = match self.poll_next_field_once(cx) {
+                Poll::Ready(Err(err)) => err,
+                poll => return poll,
+            };
+
+            let mut state = match self.state.try_lock() {
+                Some(state) => state,
+                None => return Poll::Ready(Err(Error::LockFailure)),
+            };
+
+            if matches!(err, Error::UnknownField { .. }) {
+                match state.constraints.unknown_fields {
+                    UnknownFieldPolicy::Reject => {}
+                    UnknownFieldPolicy::Ignore => {
+                        state.stage = StreamingStage::SkippingPart;
+                        continue;
+                    }
+                    UnknownFieldPolicy::SkipAndWarn => {
+                        warn!("skipping field: {}", err);
+                        state.stage = StreamingStage::SkippingPart;
+                        continue;
+                    }
+                }
+            }
+
+            let on_skip = match &state.constraints.skip_malformed_parts {
+                Some(handler) if state.is_malformed_part(&err) => handler.0.clone(),
+                _ => {
+                    state.record_violation(&err);
+                    return Poll::Ready(Err(err));
+                }
+            };
+
+            state.stage = StreamingStage::SkippingPart;
+            drop(state);
+
+            on_skip(err);
+        }
+    }
+
+    fn poll_next_field_once(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Field<'r>>>> {
+        if self.validating.is_some() {
+            return self.poll_validation(cx);
+        }
+
         // This is consistent as we have an `&mut` and `Field` is not `Clone`.
         // Here, we are guaranteeing that the returned `Field` will be the
         // _only_ field with access to the multipart parsing state. This ensure
@@ -248,17 +802,84 @@ impl<'r> Multipart<'r> {
 
         let state = &mut *lock;
         if state.stage == StreamingStage::Eof {
-            return Poll::Ready(Ok(None));
+            return Poll::Ready(state.check_required_fields().map(|_| None));
+        }
+
+        if state.stage == StreamingStage::Draining {
+            return match state.poll_drain(cx) {
+                Poll::Ready(Ok(())) => {
+                    state.stage = StreamingStage::Eof;
+                    Poll::Ready(Ok(None))
+                }
+                Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+                Poll::Pending => Poll::Pending,
+            };
         }
 
         state.buffer.poll_stream(cx)?;
 
+        if state.stage == StreamingStage::SkippingPart {
+            let line_ending = match state.buffer.allow_lf_line_endings {
+                true => constants::LF,
+                false => constants::CRLF,
+            };
+            let delimiter = format!("{}{}{}", line_ending, constants::BOUNDARY_EXT, state.boundary);
+
+            match state.buffer.read_to(delimiter.as_bytes()) {
+                Some(skipped) => {
+                    state.buffer.read_exact(line_ending.len());
+                    state.curr_field_size_counter += skipped.len() as u64;
+                    state.record_progress();
+                    state.stage = StreamingStage::ReadingBoundary;
+                }
+                None if state.buffer.eof => return Poll::Ready(Err(Error::IncompleteStream)),
+                None => {
+                    // Keep just enough of the skipped data to find a delimiter
+                    // straddling the next chunk.
+                    let skipped = state.buffer.len().saturating_sub(delimiter.len() - 1);
+                    state.buffer.read_exact(skipped);
+                    if skipped > 0 {
+                        state.curr_field_size_counter += skipped as u64;
+                        state.record_progress();
+                    }
+                    return Poll::Pending;
+                }
+            }
+        }
+
         if state.stage == StreamingStage::FindingFirstBoundary {
+            if state.constraints.strict {
+                helpers::validate_boundary(&state.boundary)?;
+            }
+
             let boundary = &state.boundary;
             let boundary_deriv = format!("{}{}", constants::BOUNDARY_EXT, boundary);
             match state.buffer.read_to(boundary_deriv.as_bytes()) {
-                Some(_) => state.stage = StreamingStage::ReadingBoundary,
+                Some(preamble) => {
+                    let mut end = preamble.len();
+                    if preamble.ends_with(constants::CRLF.as_bytes()) {
+                        end -= constants::CRLF.len();
+                    } else if state.buffer.allow_lf_line_endings && preamble.ends_with(constants::LF.as_bytes()) {
+                        end -= constants::LF.len();
+                    }
+
+                    let limit = state.constraints.size_limit.preamble;
+                    if end as u64 > limit {
+                        return Poll::Ready(Err(Error::PreambleSizeExceeded { limit }));
+                    }
+
+                    state.preamble = Some(preamble.slice(..end));
+                    state.stage = StreamingStage::ReadingBoundary;
+                }
                 None => {
+                    // The first boundary would have been found if the preamble was within the
+                    // limit.
+                    let limit = state.constraints.size_limit.preamble;
+                    let max_len = limit.saturating_add((constants::CRLF.len() + boundary_deriv.len()) as u64);
+                    if state.buffer.len() as u64 >= max_len {
+                        return Poll::Ready(Err(Error::PreambleSizeExceeded { limit }));
+                    }
+
                     state.buffer.poll_stream(cx)?;
                     if state.buffer.eof {
                         return Poll::Ready(Err(Error::IncompleteStream));
@@ -275,14 +896,18 @@ impl<'r> Multipart<'r> {
             {
                 Some((done, bytes)) => {
                     state.curr_field_size_counter += bytes.len() as u64;
+                    state.track_charset_field(&bytes, done);
 
-                    if state.curr_field_size_counter > state.curr_field_size_limit {
+                    if state.curr_field_size_counter > state.curr_field_size_limit && !state.curr_field_truncate {
                         return Poll::Ready(Err(Error::FieldSizeExceeded {
                             limit: state.curr_field_size_limit,
                             field_name: state.curr_field_name.clone(),
                         }));
                     }
 
+                    state.check_empty_file(done)?;
+                    state.record_progress();
+
                     if done {
                         state.stage = StreamingStage::ReadingBoundary;
                     } else {
@@ -330,15 +955,31 @@ impl<'r> Multipart<'r> {
                 }
             };
 
-            if next_bytes == constants::BOUNDARY_EXT.as_bytes() {
+            let is_closing = next_bytes == constants::BOUNDARY_EXT.as_bytes();
+            if is_closing {
+                if state.constraints.reject_transport_padding {
+                    let eof = state.buffer.eof;
+                    match state.buffer.peek_exact(ext_len + 1) {
+                        Some([.., b' ' | b'\t']) => return Poll::Ready(Err(Error::UnexpectedTransportPadding)),
+                        Some(_) => {}
+                        None if eof => {}
+                        None => return Poll::Pending,
+                    }
+                }
+
                 state.stage = StreamingStage::Eof;
-                return Poll::Ready(Ok(None));
+                return Poll::Ready(state.check_required_fields().map(|_| None));
             } else {
                 state.stage = StreamingStage::ReadingTransportPadding;
             }
         }
 
         if state.stage == StreamingStage::ReadingTransportPadding {
+            if state.constraints.reject_transport_padding && matches!(state.buffer.peek_exact(1), Some([b' ' | b'\t']))
+            {
+                return Poll::Ready(Err(Error::UnexpectedTransportPadding));
+            }
+
             if !state.buffer.advance_past_transport_padding() {
                 return if state.buffer.eof {
                     Poll::Ready(Err(Error::IncompleteStream))
@@ -347,8 +988,7 @@ impl<'r> Multipart<'r> {
                 };
             }
 
-            let crlf_len = constants::CRLF.len();
-            let crlf_bytes = match state.buffer.read_exact(crlf_len) {
+            let crlf_bytes = match state.buffer.read_line_ending() {
                 Some(bytes) => bytes,
                 None => {
                     return if state.buffer.eof {
@@ -359,7 +999,7 @@ impl<'r> Multipart<'r> {
                 }
             };
 
-            if &crlf_bytes[..] == constants::CRLF.as_bytes() {
+            if &crlf_bytes[..] == constants::CRLF.as_bytes() || &crlf_bytes[..] == constants::LF.as_bytes() {
                 state.stage = StreamingStage::ReadingFieldHeaders;
             } else {
                 return Poll::Ready(Err(Error::IncompleteStream));
@@ -367,8 +1007,24 @@ impl<'r> Multipart<'r> {
         }
 
         if state.stage == StreamingStage::ReadingFieldHeaders {
-            let header_bytes = match state.buffer.read_until(constants::CRLF_CRLF.as_bytes()) {
+            let max_fields = state.constraints.size_limit.max_fields;
+            if state.next_field_idx >= max_fields {
+                return Poll::Ready(Err(Error::FieldCountExceeded { limit: max_fields }));
+            }
+
+            let headers_size_limit = state.constraints.size_limit.per_field_headers;
+            let header_bytes = match state.buffer.read_header_section() {
+                Some(bytes) if bytes.len() as u64 > headers_size_limit => {
+                    return Poll::Ready(Err(Error::HeadersSizeExceeded {
+                        limit: headers_size_limit,
+                    }));
+                }
                 Some(bytes) => bytes,
+                None if state.buffer.len() as u64 > headers_size_limit => {
+                    return Poll::Ready(Err(Error::HeadersSizeExceeded {
+                        limit: headers_size_limit,
+                    }));
+                }
                 None => {
                     return if state.buffer.eof {
                         return Poll::Ready(Err(Error::IncompleteStream));
@@ -378,11 +1034,16 @@ impl<'r> Multipart<'r> {
                 }
             };
 
+            let header_bytes = match state.constraints.allow_folded_headers {
+                true => helpers::unfold_headers(header_bytes),
+                false => header_bytes,
+            };
+
             let mut headers = [httparse::EMPTY_HEADER; constants::MAX_HEADERS];
 
             let headers = match httparse::parse_headers(&header_bytes, &mut headers).map_err(Error::ReadHeaderFailed)? {
                 httparse::Status::Complete((_, raw_headers)) => {
-                    match helpers::convert_raw_headers_to_header_map(raw_headers) {
+                    match helpers::convert_raw_headers_to_header_map(raw_headers, state.constraints.duplicate_headers) {
                         Ok(headers) => headers,
                         Err(err) => {
                             return Poll::Ready(Err(err));
@@ -399,15 +1060,63 @@ impl<'r> Multipart<'r> {
             let field_idx = state.next_field_idx;
             state.next_field_idx += 1;
 
-            let content_disposition = ContentDisposition::parse(&headers);
+            let mut content_disposition = ContentDisposition::parse(&headers, state.constraints.latin1_fallback);
+            if state.constraints.decode_encoded_words {
+                content_disposition.decode_encoded_words();
+            }
+            #[cfg(feature = "unicode-normalization")]
+            if state.constraints.normalize_nfc {
+                content_disposition.normalize_nfc();
+            }
+            if state.constraints.positional_names {
+                content_disposition.name_by_position(field_idx);
+            }
+
+            if let Some(file_name) = content_disposition.file_name.as_deref() {
+                let max_files = state.constraints.size_limit.max_files;
+                if state.file_count >= max_files {
+                    return Poll::Ready(Err(Error::FileCountExceeded { limit: max_files }));
+                }
+                state.file_count += 1;
+
+                if let Some(limit) = state.constraints.size_limit.check_file_name_length(file_name) {
+                    return Poll::Ready(Err(Error::FileNameTooLong {
+                        limit,
+                        field_name: content_disposition.field_name.clone(),
+                    }));
+                }
+            }
+
             let field_size_limit = state
                 .constraints
                 .size_limit
                 .extract_size_limit_for(content_disposition.field_name.as_deref());
 
+            if let Some(field_name) = content_disposition.field_name.as_deref() {
+                state.missing_required_fields.retain(|name| name != field_name);
+            }
+
             state.curr_field_name = content_disposition.field_name.clone();
             state.curr_field_size_limit = field_size_limit;
+            #[cfg(feature = "tokio-time")]
+            {
+                state.buffer.field_rate_limit = content_disposition
+                    .field_name
+                    .as_deref()
+                    .and_then(|field_name| state.constraints.rate_limit_map.get(field_name))
+                    .map(|(bytes_per_sec, burst)| TokenBucket::new(*bytes_per_sec, *burst));
+            }
+            state.curr_field_reject_empty =
+                state.constraints.reject_empty_files && content_disposition.file_name.is_some();
+            state.curr_field_truncate = content_disposition
+                .field_name
+                .as_deref()
+                .is_some_and(|field_name| state.constraints.is_truncated_field(field_name));
             state.curr_field_size_counter = 0;
+            state.curr_field_expected_size = match state.constraints.verify_content_length {
+                true => helpers::parse_content_length(&headers),
+                false => None,
+            };
 
             let field_name = content_disposition.field_name.as_deref();
             if !state.constraints.is_it_allowed(field_name) {
@@ -416,14 +1125,412 @@ impl<'r> Multipart<'r> {
                 }));
             }
 
+            if let Some(field_name) = field_name {
+                if state.constraints.reject_duplicate_fields
+                    && !state.seen_field_names.insert(field_name.to_owned())
+                    && !state.constraints.is_duplicate_allowed(field_name)
+                {
+                    return Poll::Ready(Err(Error::DuplicateField {
+                        field_name: field_name.to_owned(),
+                    }));
+                }
+            }
+
+            if let Some(field_name) = field_name {
+                if state.constraints.is_file_field(field_name)
+                    && (content_disposition.file_name.is_none() || !headers.contains_key(http::header::CONTENT_TYPE))
+                {
+                    return Poll::Ready(Err(Error::NotAFile {
+                        field_name: field_name.to_owned(),
+                    }));
+                }
+            }
+
+            let content_type = helpers::parse_content_type(&headers);
+            if content_disposition.file_name.is_some()
+                && !state.constraints.is_file_content_type_allowed(content_type.as_ref())
+            {
+                return Poll::Ready(Err(Error::MissingFileContentType {
+                    field_name: field_name.map(str::to_owned),
+                }));
+            }
+
+            if !state
+                .constraints
+                .is_content_type_allowed(field_name, content_type.as_ref())
+            {
+                let content_type = headers
+                    .get(http::header::CONTENT_TYPE)
+                    .map(|val| String::from_utf8_lossy(val.as_bytes()).into_owned());
+                return Poll::Ready(Err(Error::ContentTypeNotAllowed {
+                    field_name: field_name.map(str::to_owned),
+                    content_type,
+                }));
+            }
+
+            if let Some(file_name) = content_disposition.file_name.as_deref() {
+                if !state.constraints.is_file_name_allowed(field_name, file_name) {
+                    return Poll::Ready(Err(Error::FileExtensionNotAllowed {
+                        field_name: field_name.map(str::to_owned),
+                        file_name: file_name.to_owned(),
+                    }));
+                }
+            }
+
+            if state.constraints.require_form_data
+                && content_disposition.disposition_type.as_deref() != Some("form-data")
+            {
+                return Poll::Ready(Err(Error::InvalidDispositionType {
+                    field_name: field_name.map(str::to_owned),
+                    disposition_type: content_disposition.disposition_type.clone(),
+                }));
+            }
+
+            if state.constraints.strict {
+                if let Err(reason) = check_strict(&content_disposition) {
+                    return Poll::Ready(Err(Error::StrictViolation {
+                        field_name: field_name.map(str::to_owned),
+                        reason: reason.to_owned(),
+                    }));
+                }
+            }
+
+            let transfer_encoding = TransferEncoding::parse(&headers);
+            if let TransferEncoding::Unknown(encoding) = &transfer_encoding {
+                if state.constraints.reject_unknown_transfer_encodings {
+                    return Poll::Ready(Err(Error::UnknownTransferEncoding {
+                        field_name: field_name.map(str::to_owned),
+                        encoding: encoding.clone(),
+                    }));
+                }
+            }
+
+            let decoder = transfer_encoding.decoder(&state.constraints);
+
+            state.charset_field = match state.constraints.honor_charset_field && field_name == Some("_charset_") {
+                true => Some(Vec::new()),
+                false => None,
+            };
+
+            #[cfg(feature = "content-encoding")]
+            let content_decoder = match state.constraints.decompress_content_encoding {
+                true => ContentEncoding::parse(&headers)
+                    .decoder(state.constraints.decompressed_size_limit.unwrap_or(u64::MAX)),
+                false => None,
+            };
+
+            let sniffer = match state.constraints.sniff_content_type && content_disposition.file_name.is_some() {
+                true => content_type.as_ref().and_then(ContentSniffer::new),
+                false => None,
+            };
+
+            let strip_bom = state.constraints.strip_utf8_bom;
+            let inspector = state.constraints.inspector.clone();
+            let validators = state.constraints.validators.clone();
             drop(lock); // The lock will be dropped anyway, but let's be explicit.
-            let field = Field::new(self.state.clone(), headers, field_idx, content_disposition);
-            return Poll::Ready(Ok(Some(field)));
+            let field = Field::new(
+                self.state.clone(),
+                headers,
+                field_idx,
+                content_disposition,
+                decoder,
+                strip_bom,
+            );
+            let field = field.with_content_sniffer(sniffer).with_inspector(inspector);
+            #[cfg(feature = "content-encoding")]
+            let field = field.with_content_decoder(content_decoder);
+
+            if validators.is_empty() {
+                return Poll::Ready(Ok(Some(field)));
+            }
+
+            // The field is yielded once it has passed the validators.
+            self.validating = Some(Box::new(Validation {
+                field,
+                validators: validators.into_iter(),
+                pending: None,
+            }));
+            return self.poll_validation(cx);
         }
 
         Poll::Pending
     }
 
+    /// Runs the validators on the headers of the field being validated,
+    /// yielding the field once they all pass.
+    fn poll_validation(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Field<'r>>>> {
+        let validation = self.validating.as_mut().expect("no field is being validated");
+        let result = ready!(validation.poll(cx));
+        let field = self.validating.take().expect("no field is being validated").field;
+
+        match result {
+            Ok(()) => Poll::Ready(Ok(Some(field))),
+            Err(cause) => Poll::Ready(Err(Error::ValidationFailed {
+                field_name: field.name().map(str::to_owned),
+                cause,
+            })),
+        }
+    }
+
+    /// The constraint violation recorded while
+    /// [`Constraints::drain_on_violation()`] is set, if any.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{Constraints, Multipart, SizeLimit};
+    ///
+    /// # async fn run() {
+    /// let data =
+    ///     "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_other_field\"\r\n\r\nefgh\r\n\
+    ///     --X-BOUNDARY--\r\n";
+    /// let constraints = Constraints::new()
+    ///     .size_limit(SizeLimit::new().per_field(2))
+    ///     .drain_on_violation(true);
+    /// let mut multipart = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert!(field.bytes().await.is_err());
+    ///
+    /// // The rest of the body is drained.
+    /// assert!(multipart.next_field().await.unwrap().is_none());
+    /// assert!(matches!(
+    ///     multipart.violation(),
+    ///     Some(multer::Error::FieldSizeExceeded { limit: 2, .. })
+    /// ));
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn violation(&self) -> Option<Error> {
+        self.state
+            .try_lock()
+            .and_then(|state| state.violation.as_ref().and_then(Error::clone_violation))
+    }
+
+    /// The boundary this `Multipart` is parsing the body with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// let multipart = Multipart::with_bytes("", "X-BOUNDARY");
+    /// assert_eq!(multipart.boundary(), "X-BOUNDARY");
+    /// ```
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// The `Content-Type` header value of the body, i.e.
+    /// `multipart/form-data` with the boundary, e.g. to forward the body as
+    /// is.
+    ///
+    /// See [`content_type()`](crate::content_type) for how the boundary is
+    /// quoted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// let multipart = Multipart::with_bytes("", "X-BOUNDARY");
+    /// assert_eq!(multipart.content_type(), "multipart/form-data; boundary=X-BOUNDARY");
+    /// ```
+    pub fn content_type(&self) -> String {
+        crate::content_type(&self.boundary)
+    }
+
+    /// The bytes before the first boundary, without the line ending preceding
+    /// it.
+    ///
+    /// The preamble is ignored by the parser, but some formats stash metadata
+    /// in it. Returns `None` until the first boundary has been found, i.e.
+    /// before the first call to [`next_field()`](Self::next_field).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "This is the preamble.\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; \
+    ///     name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\nThis is the epilogue.";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// while let Some(field) = multipart.next_field().await.unwrap() {
+    ///     assert_eq!(field.text().await.unwrap(), "abcd");
+    /// }
+    ///
+    /// assert_eq!(multipart.preamble().unwrap(), "This is the preamble.");
+    /// assert_eq!(
+    ///     multipart.epilogue().await.unwrap().unwrap(),
+    ///     "This is the epilogue."
+    /// );
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn preamble(&self) -> Option<Bytes> {
+        self.state.try_lock().and_then(|state| state.preamble.clone())
+    }
+
+    /// Takes the raw bytes of the body consumed by the parser since the last
+    /// call, if [`Constraints::capture_raw()`] is set, and an empty [`Bytes`]
+    /// otherwise.
+    ///
+    /// Taken after [`next_field()`](Self::next_field), these are the bytes up
+    /// to the end of the headers of the field, i.e. the preamble or the line
+    /// ending and delimiter preceding it, and its header section. The data of
+    /// the field is then taken with [`Field::take_raw()`] as it is read. Once
+    /// the fields are exhausted, these are the closing delimiter and, after
+    /// [`epilogue()`](Self::epilogue), the epilogue.
+    ///
+    /// Writing out all the bytes taken in order reproduces the body byte for
+    /// byte, whatever decoding is applied to the fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{Constraints, Multipart};
+    ///
+    /// # async fn run() {
+    /// let data = "preamble\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\
+    ///     Content-Transfer-Encoding: base64\r\n\r\nYWJjZA==\r\n--X-BOUNDARY--\r\nepilogue";
+    /// let constraints = Constraints::new().capture_raw(true);
+    /// let mut multipart = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+    ///
+    /// let mut raw = Vec::new();
+    /// while let Some(mut field) = multipart.next_field().await.unwrap() {
+    ///     raw.extend_from_slice(&multipart.take_raw());
+    ///     while let Some(chunk) = field.chunk().await.unwrap() {
+    ///         assert_eq!(chunk, "abcd");
+    ///         raw.extend_from_slice(&field.take_raw());
+    ///     }
+    /// }
+    ///
+    /// multipart.epilogue().await.unwrap();
+    /// raw.extend_from_slice(&multipart.take_raw());
+    /// assert_eq!(raw, data.as_bytes());
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn take_raw(&self) -> Bytes {
+        self.state
+            .try_lock()
+            .map(|mut state| state.buffer.take_raw())
+            .unwrap_or_default()
+    }
+
+    /// The number of parts in the body, once the closing boundary has been
+    /// reached, i.e. after [`next_field()`](Self::next_field) has returned
+    /// `None`.
+    ///
+    /// Parts skipped by the parser, e.g. unknown fields ignored with
+    /// [`UnknownFieldPolicy::Ignore`], are counted too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nefgh\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// drop(field);
+    /// assert_eq!(multipart.parts_seen(), None);
+    ///
+    /// while let Some(field) = multipart.next_field().await.unwrap() {}
+    /// assert_eq!(multipart.parts_seen(), Some(2));
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn parts_seen(&self) -> Option<usize> {
+        let state = self.state.try_lock()?;
+        match state.stage {
+            StreamingStage::Eof => Some(state.next_field_idx),
+            _ => None,
+        }
+    }
+
+    /// Reads the rest of the stream after the closing boundary and returns it,
+    /// without the line ending following the boundary.
+    ///
+    /// The epilogue is ignored by the parser, but some formats stash metadata
+    /// in it. Returns `None` if the closing boundary has not been reached yet,
+    /// i.e. before [`next_field()`](Self::next_field) has returned `None`.
+    ///
+    /// See [`Multipart::preamble()`] for an example.
+    pub async fn epilogue(&mut self) -> Result<Option<Bytes>> {
+        future::poll_fn(|cx| self.poll_epilogue(cx)).await
+    }
+
+    fn poll_epilogue(&mut self, cx: &mut Context<'_>) -> Poll<Result<Option<Bytes>>> {
+        let mut lock = match self.state.try_lock() {
+            Some(lock) => lock,
+            None => return Poll::Ready(Err(Error::LockFailure)),
+        };
+
+        let state = &mut *lock;
+        if state.stage != StreamingStage::Eof {
+            return Poll::Ready(Ok(None));
+        }
+
+        if state.epilogue.is_none() {
+            state.buffer.poll_stream(cx)?;
+
+            let limit = state.constraints.size_limit.epilogue;
+            let allow_lf_line_endings = state.buffer.allow_lf_line_endings;
+            let buf = state.buffer.peek_exact(state.buffer.len()).unwrap_or_default();
+            let start = epilogue_start(buf, allow_lf_line_endings);
+            let size = (buf.len() - start) as u64;
+            if size > limit {
+                return Poll::Ready(Err(Error::EpilogueSizeExceeded { limit, size }));
+            }
+
+            if !state.buffer.eof {
+                return Poll::Pending;
+            }
+
+            let epilogue = state.buffer.read_full_buf();
+            state.epilogue = Some(epilogue.slice(start..));
+        }
+
+        Poll::Ready(Ok(state.epilogue.clone()))
+    }
+
+    /// The charset given by the last `_charset_` field read so far, if
+    /// [`Constraints::honor_charset_field()`] is set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{Constraints, Multipart};
+    ///
+    /// # async fn run() {
+    /// let data =
+    ///     b"--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"_charset_\"\r\n\r\niso-8859-1\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\ncaf\xe9\r\n\
+    ///     --X-BOUNDARY--\r\n";
+    /// let constraints = Constraints::new().honor_charset_field(true);
+    /// let mut multipart =
+    ///     Multipart::with_bytes_with_constraints(&data[..], "X-BOUNDARY", constraints);
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert_eq!(field.text().await.unwrap(), "iso-8859-1");
+    /// assert_eq!(multipart.form_charset().as_deref(), Some("iso-8859-1"));
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// assert_eq!(field.text().await.unwrap(), "café");
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn form_charset(&self) -> Option<String> {
+        self.state.try_lock().and_then(|state| state.form_charset.clone())
+    }
+
     /// Yields the next [`Field`] with their positioning index as a tuple
     /// `(`[`usize`]`, `[`Field`]`)`.
     ///
@@ -457,3 +1564,129 @@ impl<'r> Multipart<'r> {
         self.next_field().await.map(|f| f.map(|field| (field.index(), field)))
     }
 }
+
+type ValidationResult = std::result::Result<(), Box<dyn std::error::Error + Send + Sync>>;
+
+/// A field checked by the validators set with [`Constraints::validate()`] and
+/// [`Constraints::validate_async()`], in the order they were added.
+struct Validation<'r> {
+    field: Field<'r>,
+    validators: std::vec::IntoIter<Validator>,
+    /// The future returned by the asynchronous validator being run.
+    pending: Option<BoxFuture<'static, ValidationResult>>,
+}
+
+impl Validation<'_> {
+    fn poll(&mut self, cx: &mut Context<'_>) -> Poll<ValidationResult> {
+        loop {
+            if let Some(pending) = &mut self.pending {
+                ready!(pending.as_mut().poll(cx))?;
+                self.pending = None;
+            }
+
+            let field = &self.field;
+            match self.validators.next() {
+                Some(Validator::Sync(validator)) => {
+                    validator(field.name(), field.file_name(), field.content_type(), field.headers())?
+                }
+                Some(Validator::Async(validator)) => {
+                    self.pending = Some(validator(
+                        field.name(),
+                        field.file_name(),
+                        field.content_type(),
+                        field.headers(),
+                    ))
+                }
+                None => return Poll::Ready(Ok(())),
+            }
+        }
+    }
+}
+
+impl Debug for Validation<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Validation")
+            .field("field", &self.field)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Passes the progress recorded while the state was locked to the callback
+/// set with [`Constraints::on_progress()`], so that the callback doesn't run
+/// under the lock.
+pub(crate) fn report_progress(state: &Mutex<MultipartState<'_>>) {
+    let progress = match state.try_lock() {
+        Some(mut state) if !state.progress.is_empty() => std::mem::take(&mut state.progress),
+        _ => return,
+    };
+
+    for progress in progress {
+        progress.report();
+    }
+}
+
+/// Yields the fields like [`Multipart::next_field()`], to be used with
+/// `StreamExt` and `TryStreamExt` combinators.
+///
+/// Unlike `next_field()`, polling the stream while the previous field is
+/// still live doesn't fail: the stream waits for the field to be dropped
+/// before parsing the next one, so the fields are still processed one at a
+/// time.
+impl<'r> Stream for Multipart<'r> {
+    type Item = Result<Field<'r>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(field) = self.peeked.take() {
+            return Poll::Ready(Some(Ok(field)));
+        }
+
+        // The field being validated is held by the `Multipart` itself.
+        if self.validating.is_none() && Arc::strong_count(&self.state) != 1 {
+            let field_waker = self.state.lock().field_waker.clone();
+            *field_waker.lock() = Some(cx.waker().clone());
+
+            // The field may have released the state since it was checked, in
+            // which case it found no waker to wake.
+            if Arc::strong_count(&self.state) != 1 {
+                return Poll::Pending;
+            }
+        }
+
+        self.get_mut().poll_next_field(cx).map(Result::transpose)
+    }
+}
+
+/// The offset of the epilogue in the rest of the stream following the closing
+/// boundary, skipping its `--`, transport padding and line ending.
+fn epilogue_start(buf: &[u8], allow_lf_line_endings: bool) -> usize {
+    let mut start = constants::BOUNDARY_EXT.len().min(buf.len());
+    start += buf[start..].iter().take_while(|b| **b == b' ' || **b == b'\t').count();
+
+    if buf[start..].starts_with(constants::CRLF.as_bytes()) {
+        start += constants::CRLF.len();
+    } else if allow_lf_line_endings && buf[start..].starts_with(constants::LF.as_bytes()) {
+        start += constants::LF.len();
+    }
+
+    start
+}
+
+/// Checks the `Content-Disposition` of a part against RFC 7578, returning the
+/// reason if it doesn't conform.
+fn check_strict(content_disposition: &ContentDisposition) -> std::result::Result<(), &'static str> {
+    match content_disposition.disposition_type.as_deref() {
+        Some("form-data") => {}
+        Some(_) => return Err("disposition type is not form-data"),
+        None => return Err("missing Content-Disposition header"),
+    }
+
+    if content_disposition.field_name.is_none() || content_disposition.has_positional_name {
+        return Err("missing name parameter");
+    }
+
+    if content_disposition.has_extended_file_name {
+        return Err("filename* parameter is not allowed");
+    }
+
+    Ok(())
+}
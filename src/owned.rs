@@ -0,0 +1,312 @@
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+
+use bytes::{Bytes, BytesMut};
+use encoding_rs::{Encoding, UTF_8};
+use futures_util::future;
+use futures_util::stream::Stream;
+use http::header::HeaderMap;
+use spin::mutex::spin::SpinMutex as Mutex;
+
+use crate::error::Error;
+use crate::field::Field;
+use crate::multipart::Multipart;
+
+/// The fields of a [`Multipart`] as [`OwnedField`]s, which are `'static` and
+/// can be held, or sent to other tasks, while the next fields are parsed.
+///
+/// The fields are returned by [`next_field()`](Self::next_field), or by
+/// polling the `OwnedFields` as a [`Stream`].
+///
+/// The body is still parsed in order: when the next field is requested while
+/// the previous one hasn't been read to its end, the rest of its data is
+/// buffered in memory until it's read, within the size limits of the field.
+/// Processing fields concurrently may thus buffer up to the size of all of
+/// them.
+///
+/// Please refer [`Multipart::into_owned_fields()`] for more info.
+#[derive(Debug)]
+pub struct OwnedFields {
+    shared: Arc<Mutex<Shared>>,
+}
+
+/// A field of a multipart body which doesn't borrow its parser, as returned
+/// by [`OwnedFields`].
+///
+/// Reading the field drives the shared parser, unless its data has already
+/// been buffered by a call for the next field.
+#[derive(Debug)]
+pub struct OwnedField {
+    shared: Arc<Mutex<Shared>>,
+    idx: usize,
+    name: Option<String>,
+    file_name: Option<String>,
+    content_type: Option<mime::Mime>,
+    headers: HeaderMap,
+}
+
+#[derive(Debug)]
+struct Shared {
+    multipart: Multipart<'static>,
+    /// The field being read from the parser, if any.
+    current: Option<Field<'static>>,
+    /// The buffered data of the live fields, by index.
+    buffers: HashMap<usize, FieldBuffer>,
+    /// The task waiting for the current field to be read to its end.
+    waker: Option<Waker>,
+}
+
+#[derive(Debug, Default)]
+struct FieldBuffer {
+    chunks: VecDeque<Bytes>,
+    error: Option<Error>,
+    done: bool,
+    waker: Option<Waker>,
+}
+
+impl FieldBuffer {
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Multipart<'static> {
+    /// Turns the `Multipart` into a stream of [`OwnedField`]s, which can be
+    /// processed later or concurrently, e.g. in spawned tasks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::Multipart;
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n\
+    ///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nefgh\r\n--X-BOUNDARY--\r\n";
+    /// let mut fields = Multipart::with_bytes(data, "X-BOUNDARY").into_owned_fields();
+    ///
+    /// let mut tasks = Vec::new();
+    /// while let Some(field) = fields.next_field().await.unwrap() {
+    ///     tasks.push(tokio::spawn(field.text()));
+    /// }
+    ///
+    /// assert_eq!(tasks.remove(0).await.unwrap().unwrap(), "abcd");
+    /// assert_eq!(tasks.remove(0).await.unwrap().unwrap(), "efgh");
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn into_owned_fields(self) -> OwnedFields {
+        OwnedFields {
+            shared: Arc::new(Mutex::new(Shared {
+                multipart: self,
+                current: None,
+                buffers: HashMap::new(),
+                waker: None,
+            })),
+        }
+    }
+}
+
+impl OwnedFields {
+    /// Yields the next [`OwnedField`] if available.
+    ///
+    /// The previous fields don't need to be dropped first.
+    pub async fn next_field(&mut self) -> crate::Result<Option<OwnedField>> {
+        future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx))
+            .await
+            .transpose()
+    }
+}
+
+impl Stream for OwnedFields {
+    type Item = crate::Result<OwnedField>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut lock = self.shared.lock();
+        let shared = &mut *lock;
+
+        // Buffer the rest of the current field for its owner, or skip it if
+        // it has been dropped.
+        while let Some(field) = shared.current.as_mut() {
+            let buffer = match shared.buffers.get_mut(&field.index()) {
+                Some(buffer) => buffer,
+                None => {
+                    shared.current = None;
+                    break;
+                }
+            };
+
+            match Pin::new(field).poll_next(cx) {
+                Poll::Ready(Some(Ok(chunk))) => buffer.chunks.push_back(chunk),
+                Poll::Ready(Some(Err(err))) => {
+                    buffer.error = Some(err);
+                    shared.current = None;
+                }
+                Poll::Ready(None) => {
+                    buffer.done = true;
+                    shared.current = None;
+                }
+                Poll::Pending => {
+                    shared.waker = Some(cx.waker().clone());
+                    return Poll::Pending;
+                }
+            }
+
+            buffer.wake();
+        }
+
+        let field = match shared.multipart.poll_next_field(cx) {
+            Poll::Ready(Ok(Some(field))) => field,
+            Poll::Ready(Ok(None)) => return Poll::Ready(None),
+            Poll::Ready(Err(err)) => return Poll::Ready(Some(Err(err))),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        let owned = OwnedField {
+            shared: self.shared.clone(),
+            idx: field.index(),
+            name: field.name().map(str::to_owned),
+            file_name: field.file_name().map(str::to_owned),
+            content_type: field.content_type().cloned(),
+            headers: field.headers().clone(),
+        };
+
+        shared.buffers.insert(field.index(), FieldBuffer::default());
+        shared.current = Some(field);
+
+        Poll::Ready(Some(Ok(owned)))
+    }
+}
+
+impl OwnedField {
+    /// The field name found in the `Content-Disposition` header, see
+    /// [`Field::name()`].
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The file name found in the `Content-Disposition` header, see
+    /// [`Field::file_name()`].
+    pub fn file_name(&self) -> Option<&str> {
+        self.file_name.as_deref()
+    }
+
+    /// Get the content type of the field.
+    pub fn content_type(&self) -> Option<&mime::Mime> {
+        self.content_type.as_ref()
+    }
+
+    /// Get a map of headers as [`HeaderMap`].
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Get the index of this field in order they appeared in the stream.
+    pub fn index(&self) -> usize {
+        self.idx
+    }
+
+    /// Stream a chunk of the field data.
+    ///
+    /// When the field data has been exhausted, this will return [`None`].
+    pub async fn chunk(&mut self) -> crate::Result<Option<Bytes>> {
+        future::poll_fn(|cx| Pin::new(&mut *self).poll_next(cx))
+            .await
+            .transpose()
+    }
+
+    /// Get the full data of the field as [`Bytes`].
+    pub async fn bytes(mut self) -> crate::Result<Bytes> {
+        let mut buf = BytesMut::new();
+        while let Some(bytes) = self.chunk().await? {
+            buf.extend_from_slice(&bytes);
+        }
+
+        Ok(buf.freeze())
+    }
+
+    /// Get the full field data as text, decoded with the `charset` parameter
+    /// of its `Content-Type` header, or as UTF-8 otherwise.
+    pub async fn text(self) -> crate::Result<String> {
+        let encoding = self
+            .content_type()
+            .and_then(|mime| mime.get_param(mime::CHARSET))
+            .and_then(|charset| Encoding::for_label(charset.as_str().as_bytes()))
+            .unwrap_or(UTF_8);
+
+        let bytes = self.bytes().await?;
+        Ok(encoding.decode(&bytes).0.into_owned())
+    }
+}
+
+impl Stream for OwnedField {
+    type Item = crate::Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut lock = self.shared.lock();
+        let shared = &mut *lock;
+
+        let buffer = match shared.buffers.get_mut(&self.idx) {
+            Some(buffer) => buffer,
+            None => return Poll::Ready(None),
+        };
+
+        if let Some(chunk) = buffer.chunks.pop_front() {
+            return Poll::Ready(Some(Ok(chunk)));
+        }
+
+        if let Some(err) = buffer.error.take() {
+            buffer.done = true;
+            return Poll::Ready(Some(Err(err)));
+        }
+
+        if buffer.done {
+            return Poll::Ready(None);
+        }
+
+        let field = match shared.current.as_mut() {
+            Some(field) if field.index() == self.idx => field,
+            _ => {
+                buffer.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        };
+
+        let poll = Pin::new(field).poll_next(cx);
+        match &poll {
+            Poll::Ready(Some(Ok(_))) => {}
+            Poll::Ready(Some(Err(_))) | Poll::Ready(None) => {
+                buffer.done = true;
+                shared.current = None;
+            }
+            Poll::Pending => {
+                buffer.waker = Some(cx.waker().clone());
+                return Poll::Pending;
+            }
+        }
+
+        // The task buffering this field may be waiting on the parser too.
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+
+        poll
+    }
+}
+
+impl Drop for OwnedField {
+    fn drop(&mut self) {
+        let mut shared = self.shared.lock();
+        shared.buffers.remove(&self.idx);
+
+        if shared.current.as_ref().is_some_and(|field| field.index() == self.idx) {
+            shared.current = None;
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
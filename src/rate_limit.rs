@@ -0,0 +1,90 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::time::{Instant, Sleep};
+
+/// A token bucket throttling the rate at which data is read from the stream.
+///
+/// Chunks are charged as they arrive, possibly overdrawing the bucket, and
+/// reading is paused until the bucket has refilled.
+#[derive(Debug)]
+pub(crate) struct TokenBucket {
+    bytes_per_sec: u64,
+    burst: u64,
+    tokens: f64,
+    updated: Instant,
+    // Created on first wait, as the timer requires a runtime.
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl TokenBucket {
+    pub fn new(bytes_per_sec: u64, burst: u64) -> TokenBucket {
+        TokenBucket {
+            bytes_per_sec: bytes_per_sec.max(1),
+            burst,
+            tokens: burst as f64,
+            updated: Instant::now(),
+            sleep: None,
+        }
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.bytes_per_sec as f64).min(self.burst as f64);
+        self.updated = now;
+    }
+
+    /// Records a chunk received from the stream.
+    pub fn on_data(&mut self, len: usize) {
+        self.refill();
+        self.tokens -= len as f64;
+    }
+
+    /// Resolves once the bucket is no longer overdrawn, otherwise registers
+    /// the task to be woken up when it's refilled.
+    pub fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        loop {
+            self.refill();
+            if self.tokens >= 0.0 {
+                return Poll::Ready(());
+            }
+
+            let wait = Duration::from_secs_f64(-self.tokens / self.bytes_per_sec as f64);
+            let at = self.updated + wait;
+            match &mut self.sleep {
+                Some(sleep) => sleep.as_mut().reset(at),
+                None => self.sleep = Some(Box::pin(tokio::time::sleep_until(at))),
+            }
+
+            if let Some(sleep) = &mut self.sleep {
+                if sleep.as_mut().poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_token_bucket() {
+        let mut cx = Context::from_waker(futures_util::task::noop_waker_ref());
+
+        let mut bucket = TokenBucket::new(1000, 10);
+        bucket.on_data(10);
+        assert_eq!(bucket.poll_ready(&mut cx), Poll::Ready(()));
+
+        bucket.on_data(20);
+        assert_eq!(bucket.poll_ready(&mut cx), Poll::Pending);
+
+        let start = Instant::now();
+        futures_util::future::poll_fn(|cx| bucket.poll_ready(cx)).await;
+        assert!(start.elapsed() >= Duration::from_millis(15));
+    }
+}
@@ -0,0 +1,123 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::{Buf, Bytes};
+use futures_util::stream::Stream;
+
+use crate::field::Field;
+
+/// An adapter reading the data of a [`Field`] through `AsyncRead`, as
+/// returned by [`Field::into_async_read()`].
+///
+/// It implements `tokio::io::AsyncRead` and `AsyncBufRead` with the
+/// `tokio-io` feature and `futures::io::AsyncRead` and `AsyncBufRead` with
+/// the `futures-io` feature, so that a field can be given to
+/// `tokio::io::copy()`, a decoder or a hasher, or read line by line. The
+/// buffered reads hand out the chunks of the field directly. Errors
+/// of the field are returned as [`io::Error`]s, with the
+/// [`Error`](crate::Error) as their inner error.
+///
+/// # Optional
+///
+/// This requires the optional `tokio-io` or `futures-io` feature to be
+/// enabled.
+#[derive(Debug)]
+pub struct FieldReader<'r> {
+    field: Field<'r>,
+    /// The data of the last chunk which hasn't been read yet.
+    chunk: Bytes,
+}
+
+impl<'r> FieldReader<'r> {
+    pub(crate) fn new(field: Field<'r>) -> FieldReader<'r> {
+        FieldReader {
+            field,
+            chunk: Bytes::new(),
+        }
+    }
+
+    /// Get a reference to the field, e.g. to get its name.
+    pub fn field(&self) -> &Field<'r> {
+        &self.field
+    }
+
+    /// Polls the next chunk of the field until it has data, or returns an
+    /// empty slice once the field has been exhausted.
+    fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        while self.chunk.is_empty() {
+            match Pin::new(&mut self.field).poll_next(cx) {
+                Poll::Ready(Some(Ok(bytes))) => self.chunk = bytes,
+                Poll::Ready(Some(Err(err))) => return Poll::Ready(Err(io::Error::other(err))),
+                Poll::Ready(None) => break,
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        Poll::Ready(Ok(&self.chunk))
+    }
+
+    fn consume_chunk(&mut self, amt: usize) {
+        self.chunk.advance(amt);
+    }
+}
+
+#[cfg(feature = "tokio-io")]
+impl tokio::io::AsyncRead for FieldReader<'_> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut tokio::io::ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let len = match this.poll_chunk(cx) {
+            Poll::Ready(Ok(chunk)) => {
+                let len = chunk.len().min(buf.remaining());
+                buf.put_slice(&chunk[..len]);
+                len
+            }
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        this.consume_chunk(len);
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio-io")]
+impl tokio::io::AsyncBufRead for FieldReader<'_> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.get_mut().poll_chunk(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().consume_chunk(amt);
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl futures_util::io::AsyncRead for FieldReader<'_> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let len = match this.poll_chunk(cx) {
+            Poll::Ready(Ok(chunk)) => {
+                let len = chunk.len().min(buf.len());
+                buf[..len].copy_from_slice(&chunk[..len]);
+                len
+            }
+            Poll::Ready(Err(err)) => return Poll::Ready(Err(err)),
+            Poll::Pending => return Poll::Pending,
+        };
+
+        this.consume_chunk(len);
+        Poll::Ready(Ok(len))
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl futures_util::io::AsyncBufRead for FieldReader<'_> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.get_mut().poll_chunk(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().consume_chunk(amt);
+    }
+}
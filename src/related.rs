@@ -0,0 +1,244 @@
+use bytes::Bytes;
+use futures_util::stream::Stream;
+use http::header::HeaderMap;
+
+use crate::constants;
+use crate::constraints::Constraints;
+use crate::error::Error;
+use crate::helpers;
+use crate::multipart::Multipart;
+
+/// Represents a `multipart/related` body (RFC 2387), such as an MTOM/XOP
+/// message, whose parts reference each other by `Content-ID`.
+///
+/// The root part is the one identified by the `start` parameter of the
+/// `Content-Type`, or the first part if there is none. Use
+/// [`parts()`](Self::parts) to buffer all parts and resolve them by
+/// `Content-ID`.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::Infallible;
+///
+/// use bytes::Bytes;
+/// use futures_util::stream::once;
+/// use multer::RelatedMultipart;
+///
+/// # async fn run() {
+/// let data = "--X-BOUNDARY\r\nContent-ID: <root@example.com>\r\nContent-Type: application/xop+xml\r\n\r\n\
+///     <doc><xop:Include href=\"cid:image@example.com\"/></doc>\r\n\
+///     --X-BOUNDARY\r\nContent-ID: <image@example.com>\r\nContent-Type: image/png\r\n\r\nPNG\r\n\
+///     --X-BOUNDARY--\r\n";
+/// let content_type = "multipart/related; boundary=X-BOUNDARY; type=\"application/xop+xml\"; \
+///     start=\"<root@example.com>\"";
+///
+/// let stream = once(async move { Result::<Bytes, Infallible>::Ok(Bytes::from(data)) });
+/// let related = RelatedMultipart::new(stream, content_type).unwrap();
+///
+/// let parts = related.parts().await.unwrap();
+/// assert_eq!(parts.root().content_id(), Some("root@example.com"));
+/// assert_eq!(parts.get("cid:image@example.com").unwrap().data(), "PNG");
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+/// ```
+#[derive(Debug)]
+pub struct RelatedMultipart<'r> {
+    multipart: Multipart<'r>,
+    start: Option<String>,
+    root_type: Option<String>,
+}
+
+impl<'r> RelatedMultipart<'r> {
+    /// Construct a new `RelatedMultipart` instance with the given [`Bytes`]
+    /// stream and the value of the `Content-Type` header.
+    ///
+    /// # Errors
+    ///
+    /// This method fails if the `Content-Type` is not `multipart/related` or
+    /// has no `boundary` parameter.
+    pub fn new<S, O, E, C>(stream: S, content_type: C) -> crate::Result<Self>
+    where
+        S: Stream<Item = Result<O, E>> + Send + 'r,
+        O: Into<Bytes> + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + 'r,
+        C: AsRef<str>,
+    {
+        RelatedMultipart::with_constraints(stream, content_type, Constraints::default())
+    }
+
+    /// Construct a new `RelatedMultipart` instance with the given [`Bytes`]
+    /// stream, the value of the `Content-Type` header and the constraints.
+    pub fn with_constraints<S, O, E, C>(stream: S, content_type: C, constraints: Constraints) -> crate::Result<Self>
+    where
+        S: Stream<Item = Result<O, E>> + Send + 'r,
+        O: Into<Bytes> + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + 'r,
+        C: AsRef<str>,
+    {
+        let m = content_type
+            .as_ref()
+            .parse::<mime::Mime>()
+            .map_err(Error::DecodeContentType)?;
+
+        if !(m.type_() == mime::MULTIPART && m.subtype() == "related") {
+            return Err(Error::NoMultipart);
+        }
+
+        let boundary = m.get_param(mime::BOUNDARY).ok_or(Error::NoBoundary)?;
+        let start = m.get_param("start").map(|start| normalize_content_id(start.as_str()));
+        let root_type = m.get_param("type").map(|root_type| root_type.as_str().to_owned());
+
+        Ok(RelatedMultipart {
+            multipart: Multipart::with_constraints(stream, boundary.as_str(), constraints),
+            start,
+            root_type,
+        })
+    }
+
+    /// The `Content-ID` of the root part given by the `start` parameter,
+    /// without the enclosing angle brackets.
+    pub fn start(&self) -> Option<&str> {
+        self.start.as_deref()
+    }
+
+    /// The media type of the root part given by the `type` parameter.
+    pub fn root_type(&self) -> Option<&str> {
+        self.root_type.as_deref()
+    }
+
+    /// Get the underlying [`Multipart`] to stream the parts in order instead
+    /// of buffering them.
+    pub fn into_multipart(self) -> Multipart<'r> {
+        self.multipart
+    }
+
+    /// Buffer all parts, and identify the root part.
+    ///
+    /// # Errors
+    ///
+    /// Besides parsing errors, this method fails with
+    /// [`Error::MissingRelatedRoot`] if there are no parts, or if no part
+    /// carries the `Content-ID` given by the `start` parameter.
+    pub async fn parts(mut self) -> crate::Result<RelatedParts> {
+        let mut parts = Vec::new();
+        while let Some(field) = self.multipart.next_field().await? {
+            let headers = field.headers().clone();
+            let data = field.bytes().await?;
+            parts.push(RelatedPart::new(headers, data));
+        }
+
+        let root = match &self.start {
+            Some(start) => parts.iter().position(|part| part.content_id() == Some(start.as_str())),
+            None if parts.is_empty() => None,
+            None => Some(0),
+        };
+
+        match root {
+            Some(root) => Ok(RelatedParts { parts, root }),
+            None => Err(Error::MissingRelatedRoot { content_id: self.start }),
+        }
+    }
+}
+
+/// The buffered parts of a [`RelatedMultipart`].
+#[derive(Debug)]
+pub struct RelatedParts {
+    parts: Vec<RelatedPart>,
+    root: usize,
+}
+
+impl RelatedParts {
+    /// The root part.
+    pub fn root(&self) -> &RelatedPart {
+        &self.parts[self.root]
+    }
+
+    /// Get the part with the given `Content-ID`.
+    ///
+    /// The id may be given bare, in angle brackets as in the `Content-ID`
+    /// header, or as a `cid:` URL as used to reference parts from the root.
+    pub fn get(&self, content_id: &str) -> Option<&RelatedPart> {
+        let content_id = match content_id.get(..4) {
+            Some(scheme) if scheme.eq_ignore_ascii_case("cid:") => {
+                let decoded = constants::percent_decode(&content_id.as_bytes()[4..]);
+                normalize_content_id(&String::from_utf8_lossy(&decoded))
+            }
+            _ => normalize_content_id(content_id),
+        };
+
+        self.parts
+            .iter()
+            .find(|part| part.content_id() == Some(content_id.as_str()))
+    }
+
+    /// Iterate over all parts in the order they appeared in the stream.
+    pub fn iter(&self) -> impl Iterator<Item = &RelatedPart> {
+        self.parts.iter()
+    }
+
+    /// The number of parts.
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// Whether there are no parts, which is never the case for a successfully
+    /// parsed body as it has at least the root part.
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+}
+
+/// A buffered part of a [`RelatedMultipart`].
+#[derive(Debug)]
+pub struct RelatedPart {
+    headers: HeaderMap,
+    content_id: Option<String>,
+    content_type: Option<mime::Mime>,
+    data: Bytes,
+}
+
+impl RelatedPart {
+    fn new(headers: HeaderMap, data: Bytes) -> Self {
+        RelatedPart {
+            content_id: helpers::parse_content_id(&headers),
+            content_type: helpers::parse_content_type(&headers),
+            headers,
+            data,
+        }
+    }
+
+    /// The `Content-ID` of the part, without the enclosing angle brackets.
+    pub fn content_id(&self) -> Option<&str> {
+        self.content_id.as_deref()
+    }
+
+    /// Get the content type of the part.
+    pub fn content_type(&self) -> Option<&mime::Mime> {
+        self.content_type.as_ref()
+    }
+
+    /// Get a map of headers as [`HeaderMap`].
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Get the data of the part.
+    pub fn data(&self) -> &Bytes {
+        &self.data
+    }
+
+    /// Consume the part and return its data.
+    pub fn into_data(self) -> Bytes {
+        self.data
+    }
+}
+
+pub(crate) fn normalize_content_id(content_id: &str) -> String {
+    let content_id = content_id.trim();
+    content_id
+        .strip_prefix('<')
+        .and_then(|id| id.strip_suffix('>'))
+        .unwrap_or(content_id)
+        .to_owned()
+}
@@ -0,0 +1,66 @@
+use http::header::CONTENT_TYPE;
+use http::Request;
+
+use crate::constraints::Constraints;
+use crate::error::Error;
+use crate::multipart::Multipart;
+
+/// An extension trait for [`http::Request`], parsing its body as a
+/// `multipart/form-data` body.
+///
+/// # Optional
+///
+/// This requires the optional `http-body` feature to be enabled.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::Bytes;
+/// use http_body_util::Full;
+/// use multer::{Constraints, RequestExt};
+///
+/// # async fn run() {
+/// let data =
+///     "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+/// let req = http::Request::builder()
+///     .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+///     .body(Full::new(Bytes::from(data)))
+///     .unwrap();
+///
+/// let mut multipart = req.into_multipart(Constraints::new()).unwrap();
+/// while let Some(field) = multipart.next_field().await.unwrap() {
+///     assert_eq!(field.text().await.unwrap(), "abcd");
+/// }
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+/// ```
+pub trait RequestExt<'r> {
+    /// Extracts the boundary from the `Content-Type` header with
+    /// [`parse_boundary()`](crate::parse_boundary), and wraps the body in a
+    /// [`Multipart`] with the given constraints.
+    ///
+    /// Fails with [`Error::NoMultipart`] if the header is missing, or with the
+    /// error of `parse_boundary()`.
+    fn into_multipart(self, constraints: Constraints) -> crate::Result<Multipart<'r>>;
+}
+
+impl<'r, B> RequestExt<'r> for Request<B>
+where
+    B: http_body::Body + Send + 'r,
+    B::Error: Into<Box<dyn std::error::Error + Send + Sync>> + 'r,
+{
+    fn into_multipart(self, constraints: Constraints) -> crate::Result<Multipart<'r>> {
+        let boundary = self
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(Error::NoMultipart)
+            .and_then(crate::parse_boundary)?;
+
+        Ok(Multipart::from_body_with_constraints(
+            self.into_body(),
+            boundary,
+            constraints,
+        ))
+    }
+}
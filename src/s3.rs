@@ -0,0 +1,139 @@
+use crate::error::Error;
+use crate::field::Field;
+use crate::multipart::Multipart;
+
+const FILE_FIELD: &str = "file";
+const KEY_FIELD: &str = "key";
+const POLICY_FIELD: &str = "policy";
+const SIGNATURE_FIELDS: [&str; 2] = ["x-amz-signature", "signature"];
+
+/// The form fields of an S3 browser-based `POST` upload, followed by the file.
+///
+/// S3 expects the upload form to carry its policy fields (`key`, `policy`,
+/// the signature and friends) _before_ the `file` field, which must come last;
+/// anything after the file is ignored. This type holds the fields that
+/// preceded the file, so they can be checked before the file body is
+/// streamed.
+///
+/// Field names are matched case-insensitively, as S3 does.
+///
+/// # Examples
+///
+/// ```
+/// use std::convert::Infallible;
+///
+/// use bytes::Bytes;
+/// use futures_util::stream::once;
+/// use multer::Multipart;
+///
+/// # async fn run() {
+/// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"key\"\r\n\r\nuploads/a.txt\r\n\
+///     --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\r\nabcd\r\n\
+///     --X-BOUNDARY--\r\n";
+///
+/// let stream = once(async move { Result::<Bytes, Infallible>::Ok(Bytes::from(data)) });
+/// let mut multipart = Multipart::new(stream, "X-BOUNDARY");
+///
+/// let form = multipart.s3_post_form().await.unwrap();
+/// assert_eq!(form.key(), "uploads/a.txt");
+///
+/// let file = form.into_file();
+/// assert_eq!(file.text().await.unwrap(), "abcd");
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+/// ```
+#[derive(Debug)]
+pub struct S3PostForm<'r> {
+    fields: Vec<(String, String)>,
+    file: Field<'r>,
+}
+
+impl<'r> S3PostForm<'r> {
+    /// The object key the file is uploaded under.
+    pub fn key(&self) -> &str {
+        // Presence of `key` is validated while parsing the form.
+        self.get(KEY_FIELD).unwrap_or_default()
+    }
+
+    /// The base64-encoded policy document, if provided.
+    pub fn policy(&self) -> Option<&str> {
+        self.get(POLICY_FIELD)
+    }
+
+    /// The request signature, either `x-amz-signature` (SigV4) or `signature`
+    /// (SigV2), if provided.
+    pub fn signature(&self) -> Option<&str> {
+        SIGNATURE_FIELDS.iter().find_map(|name| self.get(name))
+    }
+
+    /// Get the value of a form field that preceded the file.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields
+            .iter()
+            .find(|(field_name, _)| field_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Iterate over all form fields that preceded the file, in the order they
+    /// appeared in the stream.
+    pub fn fields(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.fields.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    /// Get a mutable reference to the file field.
+    pub fn file(&mut self) -> &mut Field<'r> {
+        &mut self.file
+    }
+
+    /// Consume the form and return the file field.
+    pub fn into_file(self) -> Field<'r> {
+        self.file
+    }
+}
+
+impl<'r> Multipart<'r> {
+    /// Parses the fields of an S3 browser-based `POST` upload up to the `file`
+    /// field, and returns them with the not yet consumed file field.
+    ///
+    /// See [`S3PostForm`] for details.
+    ///
+    /// # Errors
+    ///
+    /// This method fails with [`Error::MissingS3PostField`] if the stream ends
+    /// before the `file` field, if the `key` field does not precede the file,
+    /// or if only one of the `policy` and signature fields is present.
+    pub async fn s3_post_form(&mut self) -> crate::Result<S3PostForm<'r>> {
+        let mut fields = Vec::new();
+
+        while let Some(field) = self.next_field().await? {
+            let name = field.name().unwrap_or_default().to_owned();
+            if name.eq_ignore_ascii_case(FILE_FIELD) {
+                let form = S3PostForm { fields, file: field };
+                return validate(form);
+            }
+
+            let value = field.text().await?;
+            fields.push((name, value));
+        }
+
+        Err(missing(FILE_FIELD))
+    }
+}
+
+fn validate(form: S3PostForm<'_>) -> crate::Result<S3PostForm<'_>> {
+    if form.get(KEY_FIELD).is_none() {
+        return Err(missing(KEY_FIELD));
+    }
+
+    match (form.policy().is_some(), form.signature().is_some()) {
+        (true, false) => Err(missing(SIGNATURE_FIELDS[0])),
+        (false, true) => Err(missing(POLICY_FIELD)),
+        _ => Ok(form),
+    }
+}
+
+fn missing(field_name: &str) -> Error {
+    Error::MissingS3PostField {
+        field_name: field_name.to_owned(),
+    }
+}
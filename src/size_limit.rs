@@ -1,15 +1,23 @@
 use std::collections::HashMap;
 
-use crate::constants;
+use crate::{constants, helpers};
 
 /// Represents size limit of the stream to prevent DoS attacks.
 ///
 /// Please refer [`Constraints`](crate::Constraints) for more info.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SizeLimit {
     pub(crate) whole_stream: u64,
     pub(crate) per_field: u64,
+    pub(crate) per_field_headers: u64,
+    pub(crate) preamble: u64,
+    pub(crate) epilogue: u64,
+    pub(crate) max_fields: usize,
+    pub(crate) max_files: usize,
+    pub(crate) file_name_bytes: usize,
+    pub(crate) file_name_chars: usize,
     pub(crate) field_map: HashMap<String, u64>,
+    pub(crate) field_patterns: Vec<(String, u64)>,
 }
 
 impl SizeLimit {
@@ -31,6 +39,79 @@ impl SizeLimit {
         self
     }
 
+    /// Sets size limit for the header section of each field, including the
+    /// empty line terminating it.
+    ///
+    /// This bounds the buffering of the headers before the field data starts,
+    /// e.g. of a single, enormous header line.
+    pub fn per_field_headers(mut self, limit: u64) -> SizeLimit {
+        self.per_field_headers = limit;
+        self
+    }
+
+    /// Sets size limit for the preamble, the bytes before the first boundary,
+    /// which are otherwise scanned for the boundary without bound.
+    ///
+    /// The stream is rejected with
+    /// [`Error::PreambleSizeExceeded`](crate::Error::PreambleSizeExceeded) as
+    /// soon as the first boundary can't be within the limit anymore.
+    pub fn preamble(mut self, limit: u64) -> SizeLimit {
+        self.preamble = limit;
+        self
+    }
+
+    /// Sets size limit for the epilogue, the bytes after the closing
+    /// boundary, which are buffered by
+    /// [`Multipart::epilogue()`](crate::Multipart::epilogue).
+    ///
+    /// Reading the epilogue fails with
+    /// [`Error::EpilogueSizeExceeded`](crate::Error::EpilogueSizeExceeded)
+    /// once more bytes are received.
+    pub fn epilogue(mut self, limit: u64) -> SizeLimit {
+        self.epilogue = limit;
+        self
+    }
+
+    /// Sets the maximum number of fields in the stream.
+    ///
+    /// Many tiny fields are cheap to send but costly to process, so the
+    /// stream is rejected with
+    /// [`Error::FieldCountExceeded`](crate::Error::FieldCountExceeded) as soon
+    /// as one more field starts.
+    pub fn max_fields(mut self, limit: usize) -> SizeLimit {
+        self.max_fields = limit;
+        self
+    }
+
+    /// Sets the maximum number of fields carrying a file name in the stream,
+    /// independently of [`max_fields`](Self::max_fields).
+    ///
+    /// The stream is rejected with
+    /// [`Error::FileCountExceeded`](crate::Error::FileCountExceeded) as soon
+    /// as the headers of one more file field are read.
+    pub fn max_files(mut self, limit: usize) -> SizeLimit {
+        self.max_files = limit;
+        self
+    }
+
+    /// Sets the maximum length in bytes of a file name, once decoded.
+    ///
+    /// The stream is rejected with
+    /// [`Error::FileNameTooLong`](crate::Error::FileNameTooLong) when the
+    /// headers of the field are read, before its data is consumed.
+    pub fn file_name_bytes(mut self, limit: usize) -> SizeLimit {
+        self.file_name_bytes = limit;
+        self
+    }
+
+    /// Sets the maximum length in characters of a file name, once decoded.
+    ///
+    /// Please refer [`file_name_bytes`](Self::file_name_bytes) for more info.
+    pub fn file_name_chars(mut self, limit: usize) -> SizeLimit {
+        self.file_name_chars = limit;
+        self
+    }
+
     /// Sets size limit for a specific field, it overrides the
     /// [`per_field`](Self::per_field) value for this field.
     ///
@@ -42,9 +123,43 @@ impl SizeLimit {
         self
     }
 
+    /// Sets size limit for the fields whose names match a pattern, where `*`
+    /// matches any sequence of characters, e.g. `attachments[*]` for the
+    /// `attachments[0]`, `attachments[1]`, ... fields.
+    ///
+    /// A limit set with [`for_field`](Self::for_field) takes precedence, and
+    /// the first matching pattern wins if several of them match a field. It
+    /// overrides the [`per_field`](Self::per_field) value for these fields.
+    pub fn for_fields_matching<P: Into<String>>(mut self, pattern: P, limit: u64) -> SizeLimit {
+        self.field_patterns.push((pattern.into(), limit));
+        self
+    }
+
+    /// Returns the exceeded limit if the file name is too long.
+    pub(crate) fn check_file_name_length(&self, file_name: &str) -> Option<usize> {
+        if file_name.len() > self.file_name_bytes {
+            Some(self.file_name_bytes)
+        } else if self.file_name_chars < file_name.len() && file_name.chars().count() > self.file_name_chars {
+            Some(self.file_name_chars)
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn extract_size_limit_for(&self, field: Option<&str>) -> u64 {
-        field
-            .and_then(|field| self.field_map.get(&field.to_owned()))
+        let field = match field {
+            Some(field) => field,
+            None => return self.per_field,
+        };
+
+        self.field_map
+            .get(field)
+            .or_else(|| {
+                self.field_patterns
+                    .iter()
+                    .find(|(pattern, _)| helpers::matches_wildcard(pattern, field))
+                    .map(|(_, limit)| limit)
+            })
             .copied()
             .unwrap_or(self.per_field)
     }
@@ -55,7 +170,39 @@ impl Default for SizeLimit {
         SizeLimit {
             whole_stream: constants::DEFAULT_WHOLE_STREAM_SIZE_LIMIT,
             per_field: constants::DEFAULT_PER_FIELD_SIZE_LIMIT,
+            per_field_headers: constants::DEFAULT_PER_FIELD_HEADERS_SIZE_LIMIT,
+            preamble: constants::DEFAULT_PREAMBLE_SIZE_LIMIT,
+            epilogue: constants::DEFAULT_EPILOGUE_SIZE_LIMIT,
+            max_fields: constants::DEFAULT_MAX_FIELDS,
+            max_files: constants::DEFAULT_MAX_FILES,
+            file_name_bytes: constants::DEFAULT_FILE_NAME_LENGTH_LIMIT,
+            file_name_chars: constants::DEFAULT_FILE_NAME_LENGTH_LIMIT,
             field_map: HashMap::default(),
+            field_patterns: Vec::new(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_size_limit_for_patterns() {
+        let size_limit = SizeLimit::new()
+            .per_field(100)
+            .for_field("attachments[0]", 10)
+            .for_fields_matching("attachments[*]", 20)
+            .for_fields_matching("*.txt", 30)
+            .for_fields_matching("a*b*c", 40);
+
+        assert_eq!(size_limit.extract_size_limit_for(None), 100);
+        assert_eq!(size_limit.extract_size_limit_for(Some("attachments[0]")), 10);
+        assert_eq!(size_limit.extract_size_limit_for(Some("attachments[12]")), 20);
+        assert_eq!(size_limit.extract_size_limit_for(Some("attachments[]")), 20);
+        assert_eq!(size_limit.extract_size_limit_for(Some("attachments")), 100);
+        assert_eq!(size_limit.extract_size_limit_for(Some("notes.txt")), 30);
+        assert_eq!(size_limit.extract_size_limit_for(Some("abxbxc")), 40);
+        assert_eq!(size_limit.extract_size_limit_for(Some("abxbxcd")), 100);
+    }
+}
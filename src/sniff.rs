@@ -0,0 +1,140 @@
+use bytes::Bytes;
+
+/// The leading bytes identifying a file format, as a list of byte strings
+/// expected at the given offsets.
+#[derive(Debug)]
+struct Signature {
+    content_types: &'static [&'static str],
+    magic: &'static [(usize, &'static [u8])],
+}
+
+const SIGNATURES: &[Signature] = &[
+    Signature {
+        content_types: &["image/png"],
+        magic: &[(0, b"\x89PNG\r\n\x1a\n")],
+    },
+    Signature {
+        content_types: &["image/jpeg", "image/jpg", "image/pjpeg"],
+        magic: &[(0, b"\xff\xd8\xff")],
+    },
+    Signature {
+        content_types: &["image/gif"],
+        magic: &[(0, b"GIF8")],
+    },
+    Signature {
+        content_types: &["image/webp"],
+        magic: &[(0, b"RIFF"), (8, b"WEBP")],
+    },
+    Signature {
+        content_types: &["application/pdf"],
+        magic: &[(0, b"%PDF-")],
+    },
+    Signature {
+        content_types: &["application/zip", "application/x-zip-compressed"],
+        magic: &[(0, b"PK")],
+    },
+    Signature {
+        content_types: &["application/gzip", "application/x-gzip"],
+        magic: &[(0, b"\x1f\x8b")],
+    },
+];
+
+impl Signature {
+    fn find(content_type: &str) -> Option<&'static Signature> {
+        SIGNATURES
+            .iter()
+            .find(|sig| sig.content_types.iter().any(|ty| ty.eq_ignore_ascii_case(content_type)))
+    }
+
+    fn len(&self) -> usize {
+        self.magic
+            .iter()
+            .map(|(offset, bytes)| offset + bytes.len())
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn matches(&self, data: &[u8]) -> bool {
+        self.magic
+            .iter()
+            .all(|(offset, bytes)| data.get(*offset..(offset + bytes.len())) == Some(bytes))
+    }
+}
+
+/// Checks the first bytes of a field against the signature of its declared
+/// `Content-Type`.
+#[derive(Debug)]
+pub(crate) struct ContentSniffer {
+    signature: &'static Signature,
+    prefix: Vec<u8>,
+}
+
+impl ContentSniffer {
+    /// Returns `None` if the signature of the content type is unknown.
+    pub fn new(content_type: &mime::Mime) -> Option<ContentSniffer> {
+        Some(ContentSniffer {
+            signature: Signature::find(content_type.essence_str())?,
+            prefix: Vec::new(),
+        })
+    }
+
+    /// Buffers the data until enough of it has been received to be checked.
+    ///
+    /// Returns the buffered data once it matches the signature, or the
+    /// content type it looks like otherwise, if any.
+    pub fn sniff(&mut self, bytes: Bytes, done: bool) -> Result<Option<Bytes>, Option<&'static str>> {
+        let data = match self.prefix.is_empty() {
+            true => bytes,
+            false => {
+                self.prefix.extend_from_slice(&bytes);
+                Bytes::from(std::mem::take(&mut self.prefix))
+            }
+        };
+
+        if !done && data.len() < self.signature.len() {
+            self.prefix = data.to_vec();
+            return Ok(None);
+        }
+
+        match self.signature.matches(&data) {
+            true => Ok(Some(data)),
+            false => Err(SIGNATURES
+                .iter()
+                .find(|sig| sig.matches(&data))
+                .map(|sig| sig.content_types[0])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_in_chunks() {
+        let mut sniffer = ContentSniffer::new(&"image/webp".parse().unwrap()).unwrap();
+        assert_eq!(sniffer.sniff(Bytes::from_static(b"RIFF"), false), Ok(None));
+        assert_eq!(sniffer.sniff(Bytes::from_static(b"\0\0\0\0WE"), false), Ok(None));
+        assert_eq!(
+            sniffer.sniff(Bytes::from_static(b"BPVP8 "), false),
+            Ok(Some(Bytes::from_static(b"RIFF\0\0\0\0WEBPVP8 ")))
+        );
+    }
+
+    #[test]
+    fn test_sniff_mismatch() {
+        assert!(ContentSniffer::new(&mime::TEXT_PLAIN).is_none());
+
+        let mut sniffer = ContentSniffer::new(&mime::IMAGE_PNG).unwrap();
+        assert_eq!(
+            sniffer.sniff(Bytes::from_static(b"%PDF-1.7\n"), false),
+            Err(Some("application/pdf"))
+        );
+
+        let mut sniffer = ContentSniffer::new(&mime::IMAGE_JPEG).unwrap();
+        assert_eq!(sniffer.sniff(Bytes::from_static(b"<?php"), false), Err(None));
+
+        let mut sniffer = ContentSniffer::new(&mime::APPLICATION_PDF).unwrap();
+        assert_eq!(sniffer.sniff(Bytes::from_static(b"%PD"), true), Err(None));
+    }
+}
@@ -0,0 +1,150 @@
+use std::path::{Path, PathBuf};
+
+use bytes::{Bytes, BytesMut};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+use crate::error::Error;
+use crate::field::Field;
+
+/// The data of a field collected by [`Field::spool()`]: kept in memory if it
+/// is small, or spilled to a temporary file otherwise.
+///
+/// The temporary file is not removed automatically, it's up to the caller to
+/// move or delete it once it has been processed.
+///
+/// # Optional
+///
+/// This requires the optional `tokio-fs` feature to be enabled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpooledField {
+    /// The field data didn't exceed the threshold.
+    Memory(Bytes),
+    /// The field data exceeded the threshold, and was written to the file at
+    /// this path.
+    File(PathBuf),
+}
+
+impl SpooledField {
+    /// Returns `true` if the field data is held in memory.
+    pub fn is_in_memory(&self) -> bool {
+        matches!(self, SpooledField::Memory(_))
+    }
+
+    /// Returns the field data if it is held in memory.
+    pub fn as_bytes(&self) -> Option<&Bytes> {
+        match self {
+            SpooledField::Memory(bytes) => Some(bytes),
+            SpooledField::File(_) => None,
+        }
+    }
+
+    /// Returns the path of the temporary file if the field data was spilled
+    /// to disk.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            SpooledField::Memory(_) => None,
+            SpooledField::File(path) => Some(path),
+        }
+    }
+}
+
+impl Field<'_> {
+    /// Collect the field data in memory up to `threshold` bytes, spilling it
+    /// to a temporary file in [`std::env::temp_dir()`] above that, like
+    /// Python's `SpooledTemporaryFile`.
+    ///
+    /// The size limit of the field is enforced as it is collected. If an error
+    /// occurs after the data was spilled, the temporary file is removed.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-fs` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{Multipart, SpooledField};
+    ///
+    /// # async fn run() {
+    /// let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; \
+    ///     filename=\"a.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    /// let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// match field.spool(1024 * 1024).await.unwrap() {
+    ///     SpooledField::Memory(bytes) => assert_eq!(bytes, "abcd"),
+    ///     SpooledField::File(path) => unreachable!("spilled to {:?}", path),
+    /// }
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-fs")))]
+    pub async fn spool(self, threshold: usize) -> crate::Result<SpooledField> {
+        self.spool_in(threshold, std::env::temp_dir()).await
+    }
+
+    /// Same as [`spool()`](Self::spool), but creates the temporary file in
+    /// the directory `dir`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-fs` feature to be enabled.
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-fs")))]
+    pub async fn spool_in<P: AsRef<Path>>(mut self, threshold: usize, dir: P) -> crate::Result<SpooledField> {
+        let mut buf = BytesMut::new();
+        while let Some(chunk) = self.chunk().await? {
+            if buf.len() + chunk.len() > threshold {
+                let (path, file) = create_temp_file(dir.as_ref()).await?;
+                return match spill(&mut self, file, buf.freeze(), chunk).await {
+                    Ok(()) => Ok(SpooledField::File(path)),
+                    Err(err) => {
+                        let _ = tokio::fs::remove_file(&path).await;
+                        Err(err)
+                    }
+                };
+            }
+
+            buf.extend_from_slice(&chunk);
+        }
+
+        Ok(SpooledField::Memory(buf.freeze()))
+    }
+}
+
+async fn spill(field: &mut Field<'_>, mut file: File, buf: Bytes, chunk: Bytes) -> crate::Result<()> {
+    file.write_all(&buf).await.map_err(Error::WriteFailed)?;
+    file.write_all(&chunk).await.map_err(Error::WriteFailed)?;
+
+    while let Some(chunk) = field.chunk().await? {
+        file.write_all(&chunk).await.map_err(Error::WriteFailed)?;
+    }
+
+    file.flush().await.map_err(Error::WriteFailed)
+}
+
+/// Creates a new file with a random name in `dir`.
+async fn create_temp_file(dir: &Path) -> crate::Result<(PathBuf, File)> {
+    let path = dir.join(temp_file_name());
+    let file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .await
+        .map_err(Error::WriteFailed)?;
+
+    Ok((path, file))
+}
+
+/// A random name for a temporary file.
+pub(crate) fn temp_file_name() -> String {
+    let mut bytes = [0; 16];
+    getrandom::getrandom(&mut bytes).expect("failed to generate a random file name");
+
+    let mut name = String::from("multer-");
+    for b in bytes {
+        name.push_str(&format!("{:02x}", b));
+    }
+
+    name
+}
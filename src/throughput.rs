@@ -0,0 +1,77 @@
+use std::time::{Duration, Instant};
+
+/// Tracks the rate at which the stream delivers data, to reject clients
+/// trickling the body.
+///
+/// Only the time spent waiting on the stream is accounted for, so that an
+/// application slowly consuming the fields doesn't count against the client.
+#[derive(Debug)]
+pub(crate) struct MinThroughput {
+    min_bytes: u64,
+    window: Duration,
+    bytes: u64,
+    waited: Duration,
+    pending_since: Option<Instant>,
+}
+
+impl MinThroughput {
+    pub fn new(min_bytes: u64, window: Duration) -> MinThroughput {
+        MinThroughput {
+            min_bytes,
+            window,
+            bytes: 0,
+            waited: Duration::ZERO,
+            pending_since: None,
+        }
+    }
+
+    /// Records that the stream has no data available yet.
+    pub fn on_pending(&mut self) {
+        self.pending_since.get_or_insert_with(Instant::now);
+    }
+
+    /// Records a chunk received from the stream, failing if less than the
+    /// minimum number of bytes arrived over the last window.
+    pub fn on_data(&mut self, len: usize) -> crate::Result<()> {
+        if let Some(since) = self.pending_since.take() {
+            self.waited += since.elapsed();
+        }
+
+        self.bytes += len as u64;
+        if self.waited < self.window {
+            return Ok(());
+        }
+
+        if self.bytes < self.min_bytes {
+            return Err(crate::Error::TooSlow {
+                min_bytes: self.min_bytes,
+                window: self.window,
+            });
+        }
+
+        self.bytes = 0;
+        self.waited = Duration::ZERO;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_min_throughput() {
+        let mut throughput = MinThroughput::new(10, Duration::from_millis(20));
+        throughput.on_data(5).unwrap();
+        throughput.on_pending();
+        std::thread::sleep(Duration::from_millis(25));
+        throughput.on_data(5).unwrap();
+
+        throughput.on_pending();
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(matches!(
+            throughput.on_data(9),
+            Err(crate::Error::TooSlow { min_bytes: 10, .. })
+        ));
+    }
+}
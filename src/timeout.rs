@@ -0,0 +1,90 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use tokio::time::{Instant, Sleep};
+
+/// The wall-clock deadline of the whole parse, counted from the creation of
+/// the parser.
+#[derive(Debug)]
+pub(crate) struct Deadline {
+    timeout: Duration,
+    at: Instant,
+    // Created on first poll, as the timer requires a runtime.
+    sleep: Option<Pin<Box<Sleep>>>,
+}
+
+impl Deadline {
+    /// Returns `None` if the deadline is too far in the future to be
+    /// represented.
+    pub fn new(timeout: Duration) -> Option<Deadline> {
+        Some(Deadline {
+            timeout,
+            at: Instant::now().checked_add(timeout)?,
+            sleep: None,
+        })
+    }
+
+    /// Fails once the deadline has passed, otherwise registers the task to be
+    /// woken up when it does.
+    pub fn poll(&mut self, cx: &mut Context<'_>) -> crate::Result<()> {
+        let at = self.at;
+        let sleep = self.sleep.get_or_insert_with(|| Box::pin(tokio::time::sleep_until(at)));
+
+        match sleep.as_mut().poll(cx) {
+            Poll::Ready(()) => Err(crate::Error::DeadlineExceeded { timeout: self.timeout }),
+            Poll::Pending => Ok(()),
+        }
+    }
+}
+
+/// The maximum time to wait for the next chunk of the stream.
+#[derive(Debug)]
+pub(crate) struct IdleTimeout {
+    timeout: Duration,
+    sleep: Option<Pin<Box<Sleep>>>,
+    waiting: bool,
+}
+
+impl IdleTimeout {
+    pub fn new(timeout: Duration) -> IdleTimeout {
+        IdleTimeout {
+            timeout,
+            sleep: None,
+            waiting: false,
+        }
+    }
+
+    /// Records a chunk received from the stream.
+    pub fn on_data(&mut self) {
+        self.waiting = false;
+    }
+
+    /// Records that the stream has no data available yet, starting the timer
+    /// if it had data the last time, and fails once the timer has expired.
+    pub fn poll_pending(&mut self, cx: &mut Context<'_>) -> crate::Result<()> {
+        if !self.waiting {
+            self.waiting = true;
+
+            // Never expires if too far in the future to be represented.
+            let at = match Instant::now().checked_add(self.timeout) {
+                Some(at) => at,
+                None => {
+                    self.sleep = None;
+                    return Ok(());
+                }
+            };
+
+            match &mut self.sleep {
+                Some(sleep) => sleep.as_mut().reset(at),
+                None => self.sleep = Some(Box::pin(tokio::time::sleep_until(at))),
+            }
+        }
+
+        match self.sleep.as_mut().map(|sleep| sleep.as_mut().poll(cx)) {
+            Some(Poll::Ready(())) => Err(crate::Error::ReadTimeout { timeout: self.timeout }),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig, STANDARD};
+use base64::engine::{DecodePaddingMode, Engine};
+use bytes::{BufMut, Bytes, BytesMut};
+use http::header::HeaderMap;
+
+use crate::constants;
+use crate::constraints::Constraints;
+
+/// Decodes both padded and unpadded trailing groups.
+pub(crate) const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// The `Content-Transfer-Encoding` declared by a part.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum TransferEncoding {
+    /// No header, or one of `7bit`, `8bit` and `binary`.
+    Identity,
+    Base64,
+    QuotedPrintable,
+    Unknown(String),
+}
+
+impl TransferEncoding {
+    pub fn parse(headers: &HeaderMap) -> TransferEncoding {
+        let value = match headers.get("content-transfer-encoding") {
+            Some(value) => value,
+            None => return TransferEncoding::Identity,
+        };
+
+        let value = String::from_utf8_lossy(value.as_bytes());
+        let value = value.trim();
+        if ["7bit", "8bit", "binary"].iter().any(|e| value.eq_ignore_ascii_case(e)) {
+            TransferEncoding::Identity
+        } else if value.eq_ignore_ascii_case("base64") {
+            TransferEncoding::Base64
+        } else if value.eq_ignore_ascii_case("quoted-printable") {
+            TransferEncoding::QuotedPrintable
+        } else {
+            TransferEncoding::Unknown(value.to_owned())
+        }
+    }
+
+    pub fn decoder(&self, constraints: &Constraints) -> Option<TransferDecoder> {
+        match self {
+            TransferEncoding::Base64 => Some(TransferDecoder::Base64 { pending: Vec::new() }),
+            TransferEncoding::QuotedPrintable => Some(TransferDecoder::QuotedPrintable {
+                pending: Vec::new(),
+                strict: constraints.strict_quoted_printable,
+            }),
+            TransferEncoding::Identity | TransferEncoding::Unknown(_) => None,
+        }
+    }
+}
+
+/// Incrementally decodes the chunks of a transfer-encoded field.
+#[derive(Debug)]
+pub(crate) enum TransferDecoder {
+    /// Holds the trailing base64 characters that don't form a full group yet.
+    Base64 { pending: Vec<u8> },
+    /// Holds a trailing, possibly incomplete escape sequence or whitespace run.
+    QuotedPrintable { pending: Vec<u8>, strict: bool },
+}
+
+impl TransferDecoder {
+    /// Decodes the next chunk of data, buffering any incomplete trailing input.
+    /// With `last` set, the remaining input is decoded too.
+    pub fn decode(&mut self, chunk: &[u8], last: bool) -> Result<Bytes, String> {
+        match self {
+            TransferDecoder::Base64 { pending } => {
+                pending.extend(chunk.iter().filter(|b| !b.is_ascii_whitespace()));
+
+                let len = match last {
+                    true => pending.len(),
+                    false => pending.len() / 4 * 4,
+                };
+
+                let decoded = BASE64.decode(&pending[..len]).map_err(|err| err.to_string())?;
+                pending.drain(..len);
+                Ok(decoded.into())
+            }
+            TransferDecoder::QuotedPrintable { pending, strict } => {
+                pending.extend_from_slice(chunk);
+
+                let mut decoded = Vec::with_capacity(pending.len());
+                let consumed = decode_quoted_printable(pending, last, *strict, &mut decoded)?;
+                pending.drain(..consumed);
+                Ok(decoded.into())
+            }
+        }
+    }
+}
+
+/// Incrementally encodes the chunks of a part in base64, in lines of at most
+/// 76 characters as required by MIME.
+#[derive(Debug, Default)]
+pub(crate) struct Base64Encoder {
+    /// Holds the trailing bytes that don't form a full group yet.
+    pending: Vec<u8>,
+    /// The length of the current line.
+    column: usize,
+}
+
+impl Base64Encoder {
+    const LINE_LEN: usize = 76;
+
+    /// The length of `len` bytes once encoded, line breaks included.
+    pub fn encoded_len(len: u64) -> u64 {
+        let chars = len.div_ceil(3) * 4;
+        match chars {
+            0 => 0,
+            _ => chars + (chars - 1) / Self::LINE_LEN as u64 * 2,
+        }
+    }
+
+    /// Encodes the next chunk of data, buffering any incomplete trailing
+    /// group. With `last` set, the remaining data is encoded too, padded.
+    pub fn encode(&mut self, chunk: &[u8], last: bool) -> Bytes {
+        self.pending.extend_from_slice(chunk);
+
+        let len = match last {
+            true => self.pending.len(),
+            false => self.pending.len() / 3 * 3,
+        };
+
+        let encoded = STANDARD.encode(&self.pending[..len]);
+        self.pending.drain(..len);
+
+        let mut buf = BytesMut::with_capacity(encoded.len() + encoded.len() / Self::LINE_LEN * 2 + 2);
+        let mut rest = encoded.as_bytes();
+        while !rest.is_empty() {
+            if self.column == Self::LINE_LEN {
+                buf.put_slice(constants::CRLF.as_bytes());
+                self.column = 0;
+            }
+
+            let len = rest.len().min(Self::LINE_LEN - self.column);
+            buf.put_slice(&rest[..len]);
+            self.column += len;
+            rest = &rest[len..];
+        }
+
+        buf.freeze()
+    }
+}
+
+/// Decodes quoted-printable `input` into `out` and returns the number of bytes
+/// consumed. Unless `last` is set, an incomplete trailing escape sequence or
+/// whitespace run is left unconsumed.
+///
+/// Soft line breaks are removed, as is the whitespace at the end of lines,
+/// which may have been added in transport. Malformed escape sequences are
+/// passed through literally, or rejected if `strict` is set.
+fn decode_quoted_printable(input: &[u8], last: bool, strict: bool, out: &mut Vec<u8>) -> Result<usize, String> {
+    fn hex_byte(bytes: &[u8]) -> Option<u8> {
+        let hi = (*bytes.first()? as char).to_digit(16)?;
+        let lo = (*bytes.get(1)? as char).to_digit(16)?;
+        Some((hi << 4 | lo) as u8)
+    }
+
+    let mut i = 0;
+    while i < input.len() {
+        match input[i] {
+            b'=' => match &input[(i + 1)..] {
+                [b'\r', b'\n', ..] => i += 3,
+                [b'\n', ..] => i += 2,
+                [] | [_] if !last => return Ok(i),
+                rest => match hex_byte(rest) {
+                    Some(byte) => {
+                        out.push(byte);
+                        i += 3;
+                    }
+                    None if strict => return Err("invalid quoted-printable escape sequence".to_owned()),
+                    None => {
+                        out.push(b'=');
+                        i += 1;
+                    }
+                },
+            },
+            b' ' | b'\t' => {
+                let end = input[i..]
+                    .iter()
+                    .position(|b| *b != b' ' && *b != b'\t')
+                    .map_or(input.len(), |pos| i + pos);
+
+                match &input[end..] {
+                    [] | [b'\r'] if !last => return Ok(i),
+                    [] | [b'\r', b'\n', ..] | [b'\n', ..] => i = end,
+                    _ => {
+                        out.extend_from_slice(&input[i..end]);
+                        i = end;
+                    }
+                }
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+
+    Ok(i)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode_in_chunks(input: &[u8], chunk_size: usize, strict: bool) -> Result<Vec<u8>, String> {
+        let mut decoder = TransferDecoder::QuotedPrintable {
+            pending: Vec::new(),
+            strict,
+        };
+
+        let mut decoded = Vec::new();
+        let chunks: Vec<_> = input.chunks(chunk_size).collect();
+        for (i, chunk) in chunks.iter().enumerate() {
+            decoded.extend_from_slice(&decoder.decode(chunk, i == chunks.len() - 1)?);
+        }
+
+        Ok(decoded)
+    }
+
+    #[test]
+    fn test_base64_encoder() {
+        let input: Vec<u8> = (0..=255).collect();
+        let expected = STANDARD.encode(&input);
+
+        for chunk_size in 1..input.len() {
+            let mut encoder = Base64Encoder::default();
+            let mut encoded = Vec::new();
+            for chunk in input.chunks(chunk_size) {
+                encoded.extend_from_slice(&encoder.encode(chunk, false));
+            }
+            encoded.extend_from_slice(&encoder.encode(&[], true));
+
+            let lines: Vec<_> = encoded.split(|b| *b == b'\n').collect();
+            assert!(lines.iter().all(|line| line.len() <= 77));
+            assert_eq!(encoded.len() as u64, Base64Encoder::encoded_len(input.len() as u64));
+            assert_eq!(String::from_utf8(encoded).unwrap().replace("\r\n", ""), expected);
+        }
+
+        for len in 0..300 {
+            let encoded = Base64Encoder::default().encode(&vec![0; len], true);
+            assert_eq!(encoded.len() as u64, Base64Encoder::encoded_len(len as u64));
+        }
+    }
+
+    #[test]
+    fn test_quoted_printable() {
+        let input = b"Caf=C3=A9 soft=\r\nbreak  \r\nnext=3Dline\t\r\n=E2=82=AC";
+        for chunk_size in 1..input.len() {
+            let decoded = decode_in_chunks(input, chunk_size, true).unwrap();
+            assert_eq!(decoded, "Café softbreak\r\nnext=line\r\n€".as_bytes());
+        }
+
+        let input = b"100% =ZZ =";
+        assert_eq!(decode_in_chunks(input, 3, false).unwrap(), b"100% =ZZ =");
+        assert!(decode_in_chunks(input, 3, true).is_err());
+    }
+}
@@ -0,0 +1,332 @@
+use std::cmp;
+use std::io;
+use std::mem;
+use std::ops::Range;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::Path;
+
+use bytes::BytesMut;
+use tokio_uring::buf::IoBuf;
+use tokio_uring::fs::{File, OpenOptions};
+
+use crate::error::Error;
+use crate::field::Field;
+use crate::spool::{temp_file_name, SpooledField};
+
+const DEFAULT_BUFFER_SIZE: usize = 256 * 1024;
+const DIRECT_IO_ALIGNMENT: usize = 4096;
+
+/// Options to persist a field to disk with io_uring, see
+/// [`Field::save_to_file_uring()`] and [`Field::spool_uring()`].
+///
+/// The write buffer is allocated for each field and isn't registered with
+/// the ring: `tokio-uring` 0.4 has no API to register fixed buffers, so the
+/// writes are submitted as plain `IORING_OP_WRITE` operations.
+///
+/// # Optional
+///
+/// This requires the optional `tokio-uring` feature to be enabled.
+#[derive(Debug, Clone)]
+pub struct UringOptions {
+    buffer_size: usize,
+    direct: bool,
+}
+
+impl UringOptions {
+    /// Creates the default options: a 256 KiB write buffer, without
+    /// `O_DIRECT`.
+    pub fn new() -> UringOptions {
+        UringOptions::default()
+    }
+
+    /// Sets the size of the buffer the field chunks are coalesced into before
+    /// each write is submitted.
+    ///
+    /// With [`direct`](Self::direct) I/O the size is rounded up to a multiple
+    /// of 4 KiB.
+    pub fn buffer_size(mut self, size: usize) -> UringOptions {
+        self.buffer_size = cmp::max(size, 1);
+        self
+    }
+
+    /// Opens the file with `O_DIRECT`, bypassing the page cache.
+    ///
+    /// Writes are then issued from 4 KiB aligned buffers. The last, partial
+    /// block of the field is written through a second, buffered file
+    /// descriptor, as `O_DIRECT` only accepts whole blocks.
+    pub fn direct(mut self, direct: bool) -> UringOptions {
+        self.direct = direct;
+        self
+    }
+
+    fn open_options(&self) -> OpenOptions {
+        let mut open_options = OpenOptions::new();
+        open_options.write(true);
+        if self.direct {
+            open_options.custom_flags(libc::O_DIRECT);
+        }
+
+        open_options
+    }
+
+    fn aligned_buffer_size(&self) -> usize {
+        match self.direct {
+            true => round_up(self.buffer_size, DIRECT_IO_ALIGNMENT),
+            false => self.buffer_size,
+        }
+    }
+}
+
+impl Default for UringOptions {
+    fn default() -> Self {
+        UringOptions {
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            direct: false,
+        }
+    }
+}
+
+impl Field<'_> {
+    /// Stream the field data into the file at `path` using io_uring, and
+    /// return the number of bytes written.
+    ///
+    /// The file is created if it does not exist, and truncated if it does.
+    /// This must be called from within a `tokio-uring` runtime, e.g.
+    /// [`tokio_uring::start()`].
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-uring` feature to be enabled, and is
+    /// only supported on Linux.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use multer::{Multipart, UringOptions};
+    ///
+    /// tokio_uring::start(async {
+    ///     let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; \
+    ///         filename=\"a.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    ///     let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    ///     while let Some(field) = multipart.next_field().await.unwrap() {
+    ///         let written = field
+    ///             .save_to_file_uring("/tmp/a.txt", UringOptions::new().direct(true))
+    ///             .await
+    ///             .unwrap();
+    ///         assert_eq!(written, 4);
+    ///     }
+    /// });
+    /// ```
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-uring")))]
+    pub async fn save_to_file_uring<P: AsRef<Path>>(mut self, path: P, options: UringOptions) -> crate::Result<u64> {
+        let path = path.as_ref();
+        let file = options
+            .open_options()
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .await
+            .map_err(Error::WriteFailed)?;
+
+        let mut writer = UringWriter::new(file, &options);
+        while let Some(chunk) = self.chunk().await? {
+            writer.write(&chunk).await?;
+        }
+
+        writer.finish(path).await
+    }
+
+    /// Same as [`spool()`](Self::spool), but the data spilled to the
+    /// temporary file is written using io_uring.
+    ///
+    /// This must be called from within a `tokio-uring` runtime, e.g.
+    /// [`tokio_uring::start()`].
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-uring` feature to be enabled, and is
+    /// only supported on Linux.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use multer::{Multipart, SpooledField, UringOptions};
+    ///
+    /// tokio_uring::start(async {
+    ///     let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; \
+    ///         filename=\"a.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    ///     let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    ///
+    ///     let field = multipart.next_field().await.unwrap().unwrap();
+    ///     match field.spool_uring(2, UringOptions::new()).await.unwrap() {
+    ///         SpooledField::Memory(data) => println!("{} bytes in memory", data.len()),
+    ///         SpooledField::File(path) => tokio_uring::fs::remove_file(path).await.unwrap(),
+    ///     }
+    /// });
+    /// ```
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-uring")))]
+    pub async fn spool_uring(self, threshold: usize, options: UringOptions) -> crate::Result<SpooledField> {
+        self.spool_in_uring(threshold, std::env::temp_dir(), options).await
+    }
+
+    /// Same as [`spool_uring()`](Self::spool_uring), but creates the
+    /// temporary file in the directory `dir`.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-uring` feature to be enabled, and is
+    /// only supported on Linux.
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-uring")))]
+    pub async fn spool_in_uring<P: AsRef<Path>>(
+        mut self,
+        threshold: usize,
+        dir: P,
+        options: UringOptions,
+    ) -> crate::Result<SpooledField> {
+        let mut buf = BytesMut::new();
+        while let Some(chunk) = self.chunk().await? {
+            if buf.len() + chunk.len() > threshold {
+                let path = dir.as_ref().join(temp_file_name());
+                let file = options
+                    .open_options()
+                    .create_new(true)
+                    .open(&path)
+                    .await
+                    .map_err(Error::WriteFailed)?;
+
+                let mut writer = UringWriter::new(file, &options);
+                let result = async {
+                    writer.write(&buf).await?;
+                    writer.write(&chunk).await?;
+                    while let Some(chunk) = self.chunk().await? {
+                        writer.write(&chunk).await?;
+                    }
+
+                    writer.finish(&path).await
+                }
+                .await;
+
+                return match result {
+                    Ok(_) => Ok(SpooledField::File(path)),
+                    Err(err) => {
+                        let _ = tokio_uring::fs::remove_file(&path).await;
+                        Err(err)
+                    }
+                };
+            }
+
+            buf.extend_from_slice(&chunk);
+        }
+
+        Ok(SpooledField::Memory(buf.freeze()))
+    }
+}
+
+/// Coalesces the data written into a buffer, submitted to io_uring whenever
+/// it's full.
+struct UringWriter {
+    file: File,
+    buf: Vec<u8>,
+    /// The offset of the data in `buf`, aligned for `O_DIRECT`.
+    start: usize,
+    size: usize,
+    len: usize,
+    pos: u64,
+    direct: bool,
+}
+
+impl UringWriter {
+    fn new(file: File, options: &UringOptions) -> UringWriter {
+        let size = options.aligned_buffer_size();
+        let buf = vec![0; size + DIRECT_IO_ALIGNMENT];
+        let start = match options.direct {
+            true => buf.as_ptr().align_offset(DIRECT_IO_ALIGNMENT),
+            false => 0,
+        };
+
+        UringWriter {
+            file,
+            buf,
+            start,
+            size,
+            len: 0,
+            pos: 0,
+            direct: options.direct,
+        }
+    }
+
+    async fn write(&mut self, mut data: &[u8]) -> crate::Result<()> {
+        while !data.is_empty() {
+            let n = cmp::min(self.size - self.len, data.len());
+            let offset = self.start + self.len;
+            self.buf[offset..(offset + n)].copy_from_slice(&data[..n]);
+            data = &data[n..];
+            self.len += n;
+
+            if self.len == self.size {
+                let range = self.start..(self.start + self.size);
+                self.buf = write_all_at(&self.file, mem::take(&mut self.buf), range, self.pos).await?;
+                self.pos += self.size as u64;
+                self.len = 0;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the rest of the buffered data, closes the file at `path` and
+    /// returns the number of bytes written.
+    async fn finish(self, path: &Path) -> crate::Result<u64> {
+        let written = self.pos + self.len as u64;
+
+        // `O_DIRECT` only writes whole blocks, so the last, partial one is
+        // written through a buffered file descriptor instead.
+        let tail_len = match self.direct {
+            true => self.len % DIRECT_IO_ALIGNMENT,
+            false => 0,
+        };
+        let direct_len = self.len - tail_len;
+
+        let mut buf = self.buf;
+        if direct_len > 0 {
+            buf = write_all_at(&self.file, buf, self.start..(self.start + direct_len), self.pos).await?;
+        }
+        self.file.close().await.map_err(Error::WriteFailed)?;
+
+        if tail_len > 0 {
+            let file = OpenOptions::new()
+                .write(true)
+                .open(path)
+                .await
+                .map_err(Error::WriteFailed)?;
+            let range = (self.start + direct_len)..(self.start + self.len);
+            write_all_at(&file, buf, range, self.pos + direct_len as u64).await?;
+            file.close().await.map_err(Error::WriteFailed)?;
+        }
+
+        Ok(written)
+    }
+}
+
+async fn write_all_at(file: &File, mut buf: Vec<u8>, mut range: Range<usize>, mut pos: u64) -> crate::Result<Vec<u8>> {
+    while !range.is_empty() {
+        let (res, slice) = file.write_at(buf.slice(range.clone()), pos).await;
+        buf = slice.into_inner();
+
+        match res {
+            Ok(0) => return Err(Error::WriteFailed(io::ErrorKind::WriteZero.into())),
+            Ok(n) => {
+                range.start += n;
+                pos += n as u64;
+            }
+            Err(err) => return Err(Error::WriteFailed(err)),
+        }
+    }
+
+    Ok(buf)
+}
+
+fn round_up(n: usize, alignment: usize) -> usize {
+    n.div_ceil(alignment) * alignment
+}
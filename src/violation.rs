@@ -0,0 +1,155 @@
+use std::convert::TryInto;
+
+/// The rule of [`Constraints`](crate::Constraints) or limit of
+/// [`SizeLimit`](crate::SizeLimit) violated by a multipart body.
+///
+/// Please refer [`ConstraintViolation`] for more info.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Constraint {
+    /// [`SizeLimit::whole_stream()`](crate::SizeLimit::whole_stream).
+    WholeStreamSize,
+    /// [`SizeLimit::per_field()`](crate::SizeLimit::per_field) or a limit set
+    /// for the specific field.
+    FieldSize,
+    /// [`SizeLimit::per_field_headers()`](crate::SizeLimit::per_field_headers).
+    FieldHeadersSize,
+    /// [`SizeLimit::preamble()`](crate::SizeLimit::preamble).
+    PreambleSize,
+    /// [`SizeLimit::epilogue()`](crate::SizeLimit::epilogue).
+    EpilogueSize,
+    /// [`SizeLimit::max_fields()`](crate::SizeLimit::max_fields).
+    FieldCount,
+    /// [`SizeLimit::max_files()`](crate::SizeLimit::max_files).
+    FileCount,
+    /// [`SizeLimit::file_name_bytes()`](crate::SizeLimit::file_name_bytes) or
+    /// [`SizeLimit::file_name_chars()`](crate::SizeLimit::file_name_chars).
+    FileNameLength,
+    /// [`Constraints::reject_empty_files()`](crate::Constraints::reject_empty_files).
+    EmptyFiles,
+    /// The `Constraints::decompressed_size_limit()` of the optional
+    /// `content-encoding` feature.
+    DecompressedSize,
+    /// [`Constraints::verify_content_length()`](crate::Constraints::verify_content_length).
+    ContentLength,
+    /// [`Constraints::max_depth()`](crate::Constraints::max_depth).
+    NestingDepth,
+    /// [`Constraints::min_throughput()`](crate::Constraints::min_throughput).
+    MinThroughput,
+    /// The `Constraints::deadline()` of the optional `tokio-time` feature.
+    Deadline,
+    /// The `Constraints::read_timeout()` of the optional `tokio-time`
+    /// feature.
+    ReadTimeout,
+    /// [`Constraints::memory_budget()`](crate::Constraints::memory_budget).
+    MemoryBudget,
+    /// [`Constraints::allowed_fields()`](crate::Constraints::allowed_fields).
+    AllowedFields,
+    /// [`Constraints::required_fields()`](crate::Constraints::required_fields).
+    RequiredFields,
+    /// [`Constraints::reject_duplicate_fields()`](crate::Constraints::reject_duplicate_fields).
+    DuplicateFields,
+    /// [`Constraints::file_fields()`](crate::Constraints::file_fields).
+    FileFields,
+    /// [`Constraints::allowed_content_types()`](crate::Constraints::allowed_content_types).
+    AllowedContentTypes,
+    /// [`Constraints::file_content_type()`](crate::Constraints::file_content_type).
+    FileContentType,
+    /// [`Constraints::sniff_content_type()`](crate::Constraints::sniff_content_type).
+    ContentTypeSignature,
+    /// [`Constraints::file_extensions()`](crate::Constraints::file_extensions)
+    /// or [`Constraints::file_extensions_for()`](crate::Constraints::file_extensions_for).
+    FileExtensions,
+    /// [`Constraints::require_form_data()`](crate::Constraints::require_form_data).
+    FormData,
+    /// A validator added with
+    /// [`Constraints::validate()`](crate::Constraints::validate).
+    Validator,
+    /// A [`FieldInspector`](crate::FieldInspector) set with
+    /// [`Constraints::inspect()`](crate::Constraints::inspect).
+    Inspector,
+}
+
+/// The details of a violated constraint, as returned by
+/// [`Error::violation()`](crate::Error::violation), to build a precise reply
+/// to the client.
+///
+/// # Examples
+///
+/// ```
+/// use multer::{Constraint, Constraints, Multipart, SizeLimit};
+///
+/// # async fn run() {
+/// let data =
+///     "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+/// let constraints = Constraints::new().size_limit(SizeLimit::new().per_field(2));
+/// let mut multipart = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+///
+/// let field = multipart.next_field().await.unwrap().unwrap();
+/// let violation = field.bytes().await.unwrap_err().violation().unwrap();
+///
+/// assert_eq!(violation.constraint(), Constraint::FieldSize);
+/// assert_eq!(violation.field_name(), Some("my_text_field"));
+/// assert_eq!(violation.limit(), Some(2));
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConstraintViolation {
+    pub(crate) constraint: Constraint,
+    pub(crate) field_name: Option<String>,
+    pub(crate) limit: Option<u64>,
+    pub(crate) observed: Option<u64>,
+}
+
+impl ConstraintViolation {
+    pub(crate) fn new(constraint: Constraint) -> ConstraintViolation {
+        ConstraintViolation {
+            constraint,
+            field_name: None,
+            limit: None,
+            observed: None,
+        }
+    }
+
+    pub(crate) fn with_field_name(mut self, field_name: Option<&str>) -> ConstraintViolation {
+        self.field_name = field_name.map(str::to_owned);
+        self
+    }
+
+    pub(crate) fn with_limit<L: TryInto<u64>>(mut self, limit: L) -> ConstraintViolation {
+        self.limit = limit.try_into().ok();
+        self
+    }
+
+    pub(crate) fn with_observed<L: TryInto<u64>>(mut self, observed: L) -> ConstraintViolation {
+        self.observed = observed.try_into().ok();
+        self
+    }
+
+    /// The violated constraint.
+    pub fn constraint(&self) -> Constraint {
+        self.constraint
+    }
+
+    /// The name of the offending field, if the constraint applies to a single
+    /// field and it has a name.
+    pub fn field_name(&self) -> Option<&str> {
+        self.field_name.as_deref()
+    }
+
+    /// The value of the limit, if the constraint is a numeric one: a number
+    /// of bytes for sizes and rates, characters or bytes for file name
+    /// lengths, milliseconds for timeouts, and a count otherwise.
+    pub fn limit(&self) -> Option<u64> {
+        self.limit
+    }
+
+    /// The value observed in the body, if known.
+    ///
+    /// Sizes are checked as data arrive, so the exceeded size isn't known,
+    /// while a count of fields is exceeded by one.
+    pub fn observed(&self) -> Option<u64> {
+        self.observed
+    }
+}
@@ -0,0 +1,162 @@
+//! A [warp](https://docs.rs/warp) filter parsing the request body with
+//! multer, and the rejection replying to the errors of a multipart body with
+//! a `400 Bad Request` or `413 Payload Too Large` status.
+//!
+//! # Optional
+//!
+//! This requires the optional `warp` feature to be enabled.
+//!
+//! # Examples
+//!
+//! ```
+//! use multer::{Constraints, Multipart, SizeLimit};
+//! use warp::Filter;
+//!
+//! async fn upload(mut multipart: Multipart<'static>) -> Result<String, warp::Rejection> {
+//!     let mut names = Vec::new();
+//!     while let Some(field) = multipart.next_field().await.map_err(multer::warp::reject)? {
+//!         names.extend(field.name().map(str::to_owned));
+//!     }
+//!
+//!     Ok(names.join(", "))
+//! }
+//!
+//! let constraints = Constraints::new().size_limit(SizeLimit::new().per_field(1024 * 1024));
+//! let route = warp::post()
+//!     .and(multer::warp::multipart(constraints))
+//!     .and_then(upload)
+//!     .recover(multer::warp::recover);
+//! # let _ = route;
+//! ```
+
+use std::fmt::{self, Display, Formatter};
+
+use bytes::Buf;
+use futures_util::{Stream, TryStreamExt};
+use warp::http::StatusCode;
+use warp::{Filter, Rejection, Reply};
+
+use crate::constraints::Constraints;
+use crate::error::Error;
+use crate::multipart::Multipart;
+use crate::violation::Constraint;
+
+/// Returns a filter extracting a [`Multipart`] from the body of
+/// `multipart/form-data` requests, parsed with the given constraints.
+///
+/// Requests without a `multipart/form-data` content type with a boundary
+/// are rejected with a [`MultipartRejection`].
+///
+/// # Optional
+///
+/// This requires the optional `warp` feature to be enabled.
+pub fn multipart(constraints: Constraints) -> impl Filter<Extract = (Multipart<'static>,), Error = Rejection> + Clone {
+    warp::header::optional::<String>("content-type")
+        .and(warp::body::stream())
+        .and_then(move |content_type: Option<String>, body| {
+            let constraints = constraints.clone();
+            async move {
+                let boundary = content_type
+                    .ok_or(Error::NoMultipart)
+                    .and_then(crate::parse_boundary)
+                    .map_err(reject)?;
+
+                Ok::<_, Rejection>(from_body(body, boundary, constraints))
+            }
+        })
+}
+
+fn from_body<S, B>(body: S, boundary: String, constraints: Constraints) -> Multipart<'static>
+where
+    S: Stream<Item = Result<B, warp::Error>> + Send + 'static,
+    B: Buf,
+{
+    let stream = body.map_ok(|mut buf| buf.copy_to_bytes(buf.remaining()));
+    Multipart::with_constraints(stream, boundary, constraints)
+}
+
+/// Turns an error of a multipart body into a [`Rejection`], e.g. the errors
+/// returned by a [`Multipart`] in a handler, so that [`recover()`] replies to
+/// it.
+///
+/// # Optional
+///
+/// This requires the optional `warp` feature to be enabled.
+pub fn reject(err: Error) -> Rejection {
+    warp::reject::custom(MultipartRejection(err))
+}
+
+/// Replies to a [`MultipartRejection`] with its [`status()`] and the error
+/// message, to be used with `Filter::recover()`.
+///
+/// Other rejections are passed through.
+///
+/// [`status()`]: MultipartRejection::status
+///
+/// # Optional
+///
+/// This requires the optional `warp` feature to be enabled.
+pub async fn recover(rejection: Rejection) -> Result<warp::reply::Response, Rejection> {
+    match rejection.find::<MultipartRejection>() {
+        Some(rejection) => Ok(rejection.to_reply()),
+        None => Err(rejection),
+    }
+}
+
+/// A rejection caused by an error of a multipart body.
+///
+/// # Optional
+///
+/// This requires the optional `warp` feature to be enabled.
+#[derive(Debug)]
+pub struct MultipartRejection(Error);
+
+impl MultipartRejection {
+    /// Returns the error the request was rejected with.
+    pub fn error(&self) -> &Error {
+        &self.0
+    }
+
+    /// The status of the reply: `413 Payload Too Large` if a size or count
+    /// limit was exceeded, and `400 Bad Request` otherwise.
+    pub fn status(&self) -> StatusCode {
+        let constraint = self.0.violation().map(|violation| violation.constraint());
+        match constraint {
+            Some(
+                Constraint::WholeStreamSize
+                | Constraint::FieldSize
+                | Constraint::FieldHeadersSize
+                | Constraint::PreambleSize
+                | Constraint::EpilogueSize
+                | Constraint::FieldCount
+                | Constraint::FileCount
+                | Constraint::DecompressedSize,
+            ) => StatusCode::PAYLOAD_TOO_LARGE,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    fn to_reply(&self) -> warp::reply::Response {
+        warp::reply::with_status(self.0.to_string(), self.status()).into_response()
+    }
+}
+
+impl Display for MultipartRejection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+impl std::error::Error for MultipartRejection {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl warp::reject::Reject for MultipartRejection {}
+
+impl Reply for MultipartRejection {
+    fn into_response(self) -> warp::reply::Response {
+        self.to_reply()
+    }
+}
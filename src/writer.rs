@@ -0,0 +1,1004 @@
+use std::fmt::{self, Debug, Formatter};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use futures_util::stream::{Stream, TryStreamExt};
+use http::header::{self, HeaderMap, HeaderName, HeaderValue};
+#[cfg(feature = "tokio-io")]
+use {tokio::io::AsyncRead, tokio_util::io::ReaderStream};
+
+use crate::error::Error;
+use crate::transfer_encoding::Base64Encoder;
+use crate::{constants, helpers};
+
+/// Serializes fields into a `multipart/form-data` body, the counterpart of
+/// [`Multipart`](crate::Multipart).
+///
+/// The body is produced as a [`MultipartStream`] of [`Bytes`] chunks, to be
+/// sent along with the `Content-Type` given by
+/// [`content_type()`](Self::content_type).
+///
+/// # Examples
+///
+/// ```
+/// use multer::{Multipart, MultipartWriter, Part};
+///
+/// # async fn run() {
+/// let writer = MultipartWriter::new().text("my_text_field", "abcd").part(
+///     Part::bytes("my_file_field", "Hello world")
+///         .file_name("a-text-file.txt")
+///         .content_type(mime::TEXT_PLAIN),
+/// );
+///
+/// let boundary = writer.boundary().to_owned();
+/// let mut multipart = Multipart::new(writer.into_stream(), boundary);
+///
+/// let field = multipart.next_field().await.unwrap().unwrap();
+/// assert_eq!(field.name(), Some("my_text_field"));
+/// assert_eq!(field.text().await.unwrap(), "abcd");
+///
+/// let field = multipart.next_field().await.unwrap().unwrap();
+/// assert_eq!(field.file_name(), Some("a-text-file.txt"));
+/// assert_eq!(field.content_type(), Some(&mime::TEXT_PLAIN));
+/// assert_eq!(field.text().await.unwrap(), "Hello world");
+/// # }
+/// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+/// ```
+#[derive(Debug)]
+pub struct MultipartWriter {
+    boundary: String,
+    parts: Vec<Part>,
+    mixed: bool,
+    random_boundary: bool,
+    on_progress: Option<ProgressHandler>,
+}
+
+impl MultipartWriter {
+    /// Creates a writer with a randomly generated boundary.
+    ///
+    /// The boundary is generated from the random number generator of the
+    /// operating system, and generated again if it occurs in the data of a
+    /// [bytes](Part::bytes) part added later, so it can't collide with them.
+    /// The data of other parts can't be checked in advance.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the random number generator of the operating system fails.
+    pub fn new() -> MultipartWriter {
+        MultipartWriter {
+            random_boundary: true,
+            ..MultipartWriter::with_boundary(random_boundary())
+        }
+    }
+
+    /// Creates a writer with the given boundary.
+    ///
+    /// The boundary must not occur in the data of any part, or the body
+    /// would be cut short when parsed.
+    pub fn with_boundary<B: Into<String>>(boundary: B) -> MultipartWriter {
+        MultipartWriter {
+            boundary: boundary.into(),
+            parts: Vec::new(),
+            mixed: false,
+            random_boundary: false,
+            on_progress: None,
+        }
+    }
+
+    /// Creates a writer of a `multipart/mixed` body with a randomly generated
+    /// boundary, to be embedded in a form with [`Part::nested()`].
+    ///
+    /// Its parts are written with a `file` disposition, as legacy clients
+    /// send several files under a single field, and without a `name`
+    /// parameter if their name is empty.
+    pub fn mixed() -> MultipartWriter {
+        MultipartWriter {
+            mixed: true,
+            ..MultipartWriter::new()
+        }
+    }
+
+    /// The boundary delimiting the parts of the body.
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// The value of the `Content-Type` header to send along with the body,
+    /// i.e. `multipart/form-data`, or `multipart/mixed` for a
+    /// [`mixed()`](Self::mixed) body, with the boundary.
+    pub fn content_type(&self) -> String {
+        let subtype = match self.mixed {
+            true => "mixed",
+            false => "form-data",
+        };
+        let boundary = helpers::quote_param_value(&self.boundary);
+        format!("multipart/{}; boundary={}", subtype, boundary)
+    }
+
+    /// Adds a text field.
+    pub fn text<N: Into<String>, V: Into<String>>(self, name: N, value: V) -> MultipartWriter {
+        self.part(Part::text(name, value))
+    }
+
+    /// Adds a part, see [`Part`].
+    pub fn part(mut self, part: Part) -> MultipartWriter {
+        self.parts.push(part);
+
+        // Only the new part needs to be checked, unless the boundary changes.
+        let mut parts = &self.parts[(self.parts.len() - 1)..];
+        while self.random_boundary && parts.iter().any(|part| part.contains(&self.boundary)) {
+            self.boundary = random_boundary();
+            parts = &self.parts;
+        }
+
+        self
+    }
+
+    /// Calls `on_progress` with the number of bytes emitted so far for the
+    /// current part and overall, each time the body emits a chunk, e.g. to
+    /// drive a progress bar.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::MultipartWriter;
+    ///
+    /// let writer = MultipartWriter::new()
+    ///     .text("my_text_field", "abcd")
+    ///     .on_progress(|progress| {
+    ///         if let Some(total_length) = progress.total_length() {
+    ///             println!("{}/{} bytes sent", progress.total_bytes(), total_length);
+    ///         }
+    ///     });
+    /// ```
+    pub fn on_progress<F>(mut self, on_progress: F) -> MultipartWriter
+    where
+        F: Fn(&WriteProgress<'_>) + Send + Sync + 'static,
+    {
+        self.on_progress = Some(ProgressHandler(Arc::new(on_progress)));
+        self
+    }
+
+    /// The exact length of the serialized body, delimiters and headers
+    /// included, to be sent as `Content-Length` instead of using a chunked
+    /// transfer encoding.
+    ///
+    /// Returns `None` if the length of a stream or reader part isn't known,
+    /// see [`Part::length()`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{MultipartWriter, Part};
+    ///
+    /// # async fn run() {
+    /// let writer = MultipartWriter::new()
+    ///     .text("my_text_field", "abcd")
+    ///     .part(Part::bytes("my_file_field", "Hello world").file_name("a-text-file.txt"));
+    ///
+    /// let content_length = writer.content_length().unwrap();
+    ///
+    /// let chunks: Vec<bytes::Bytes> = futures_util::TryStreamExt::try_collect(writer.into_stream())
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(chunks.concat().len() as u64, content_length);
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn content_length(&self) -> Option<u64> {
+        let boundary = self.boundary.len() as u64;
+
+        let mut len = 0;
+        for part in &self.parts {
+            let mut headers = BytesMut::new();
+            part.write_headers(&mut headers, self.mixed);
+
+            // A delimiter is preceded by a line break, except the first one.
+            if len > 0 {
+                len += 2;
+            }
+            len += boundary + 4 + headers.len() as u64 + part.encoded_length()?;
+        }
+
+        if len > 0 {
+            len += 2;
+        }
+        Some(len + boundary + 6)
+    }
+
+    /// Clones the writer, e.g. to send the body again after a transient
+    /// failure, unless one of its parts can't be cloned, see
+    /// [`Part::try_clone()`].
+    pub fn try_clone(&self) -> Option<MultipartWriter> {
+        Some(MultipartWriter {
+            boundary: self.boundary.clone(),
+            parts: self.parts.iter().map(Part::try_clone).collect::<Option<_>>()?,
+            mixed: self.mixed,
+            random_boundary: self.random_boundary,
+            on_progress: self.on_progress.clone(),
+        })
+    }
+
+    /// Turns the writer into the stream of the serialized body.
+    pub fn into_stream(self) -> MultipartStream {
+        let total_length = self.on_progress.as_ref().and_then(|_| self.content_length());
+
+        MultipartStream {
+            on_progress: self.on_progress,
+            part_name: None,
+            part_index: None,
+            part_start: 0,
+            total_bytes: 0,
+            total_length,
+            boundary: self.boundary,
+            parts: self.parts.into_iter(),
+            mixed: self.mixed,
+            body: None,
+            remaining: None,
+            encoder: None,
+            started: false,
+            done: false,
+        }
+    }
+}
+
+impl Default for MultipartWriter {
+    fn default() -> Self {
+        MultipartWriter::new()
+    }
+}
+
+/// The progress of a [`MultipartStream`], as reported to
+/// [`MultipartWriter::on_progress()`].
+#[derive(Debug, Clone, Copy)]
+pub struct WriteProgress<'a> {
+    part_name: Option<&'a str>,
+    part_index: Option<usize>,
+    part_bytes: u64,
+    total_bytes: u64,
+    total_length: Option<u64>,
+}
+
+impl WriteProgress<'_> {
+    /// The name of the part being written, or `None` once the closing
+    /// delimiter is.
+    pub fn part_name(&self) -> Option<&str> {
+        self.part_name
+    }
+
+    /// The index of the part being written, or `None` once the closing
+    /// delimiter is.
+    pub fn part_index(&self) -> Option<usize> {
+        self.part_index
+    }
+
+    /// The number of bytes of the data of the current part emitted so far,
+    /// once encoded.
+    pub fn part_bytes(&self) -> u64 {
+        self.part_bytes
+    }
+
+    /// The number of bytes of the body emitted so far, delimiters and headers
+    /// included.
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// The length of the whole body, if known, see
+    /// [`MultipartWriter::content_length()`].
+    pub fn total_length(&self) -> Option<u64> {
+        self.total_length
+    }
+}
+
+/// The callback added with [`MultipartWriter::on_progress()`].
+#[derive(Clone)]
+struct ProgressHandler(Arc<dyn Fn(&WriteProgress<'_>) + Send + Sync>);
+
+impl Debug for ProgressHandler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("ProgressHandler")
+    }
+}
+
+/// A part of a body built with [`MultipartWriter`].
+///
+/// # Examples
+///
+/// ```
+/// use multer::Part;
+///
+/// let part = Part::bytes("avatar", vec![0x89, b'P', b'N', b'G'])
+///     .file_name("avatar.png")
+///     .content_type(mime::IMAGE_PNG);
+/// ```
+#[derive(Debug)]
+pub struct Part {
+    name: String,
+    file_name: Option<String>,
+    content_type: Option<mime::Mime>,
+    headers: HeaderMap,
+    length: Option<u64>,
+    base64: bool,
+    body: PartBody,
+}
+
+type BoxStream = Pin<Box<dyn Stream<Item = crate::Result<Bytes>> + Send>>;
+
+enum PartBody {
+    Bytes(Bytes),
+    Stream(BoxStream),
+    /// Opens the stream of the data when the part is written.
+    Reopen(Arc<dyn Fn() -> BoxStream + Send + Sync>),
+    Nested(Box<MultipartWriter>),
+}
+
+impl PartBody {
+    /// Opens the body as either bytes or a stream.
+    fn open(self) -> PartBody {
+        match self {
+            PartBody::Reopen(open) => PartBody::Stream(open()),
+            PartBody::Nested(body) => PartBody::Stream(Box::pin(body.into_stream())),
+            body => body,
+        }
+    }
+}
+
+impl Debug for PartBody {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PartBody::Bytes(bytes) => f.debug_tuple("Bytes").field(bytes).finish(),
+            PartBody::Stream(_) => f.debug_tuple("Stream").finish(),
+            PartBody::Reopen(_) => f.debug_tuple("Reopen").finish(),
+            PartBody::Nested(body) => f.debug_tuple("Nested").field(body).finish(),
+        }
+    }
+}
+
+/// Maps the items of a stream given to a [`Part`].
+fn box_stream<S, O, E>(stream: S) -> BoxStream
+where
+    S: Stream<Item = Result<O, E>> + Send + 'static,
+    O: Into<Bytes> + 'static,
+    E: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+{
+    Box::pin(
+        stream
+            .map_ok(|b| b.into())
+            .map_err(|err| Error::StreamReadFailed(err.into())),
+    )
+}
+
+impl Part {
+    /// Creates a text field.
+    pub fn text<N: Into<String>, V: Into<String>>(name: N, value: V) -> Part {
+        Part::bytes(name, value.into())
+    }
+
+    /// Creates a field with the given data, e.g. the content of a file along
+    /// with its [`file_name`](Self::file_name).
+    pub fn bytes<N: Into<String>, D: Into<Bytes>>(name: N, data: D) -> Part {
+        Part {
+            name: name.into(),
+            file_name: None,
+            content_type: None,
+            headers: HeaderMap::new(),
+            length: None,
+            base64: false,
+            body: PartBody::Bytes(data.into()),
+        }
+    }
+
+    /// Creates a field whose data is read from the given [`Bytes`] stream as
+    /// the body is sent, so that large files don't need to be buffered.
+    ///
+    /// An error of the stream is yielded by the [`MultipartStream`] as
+    /// [`Error::StreamReadFailed`](crate::Error::StreamReadFailed), which ends
+    /// the body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::Infallible;
+    ///
+    /// use bytes::Bytes;
+    /// use futures_util::stream;
+    /// use multer::Part;
+    ///
+    /// let chunks = vec![Bytes::from("Hello "), Bytes::from("world")];
+    /// let part = Part::stream(
+    ///     "my_file_field",
+    ///     stream::iter(chunks.into_iter().map(Ok::<_, Infallible>)),
+    /// )
+    /// .file_name("a-text-file.txt");
+    /// ```
+    pub fn stream<N, S, O, E>(name: N, stream: S) -> Part
+    where
+        N: Into<String>,
+        S: Stream<Item = Result<O, E>> + Send + 'static,
+        O: Into<Bytes> + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+    {
+        Part {
+            name: name.into(),
+            file_name: None,
+            content_type: None,
+            headers: HeaderMap::new(),
+            length: None,
+            base64: false,
+            body: PartBody::Stream(box_stream(stream)),
+        }
+    }
+
+    /// Creates a field whose data is read from a stream opened with `open`
+    /// as the body is sent, e.g. to read a file again.
+    ///
+    /// Unlike a [stream](Part::stream) part, it can be cloned with
+    /// [`try_clone()`](Part::try_clone), which opens a new stream each time
+    /// the data is written, so that a request can be sent again after a
+    /// transient failure.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::convert::Infallible;
+    ///
+    /// use bytes::Bytes;
+    /// use futures_util::stream;
+    /// use multer::{MultipartWriter, Part};
+    ///
+    /// let chunks = vec![Bytes::from("Hello "), Bytes::from("world")];
+    /// let part = Part::reopenable("my_file_field", move || {
+    ///     stream::iter(chunks.clone().into_iter().map(Ok::<_, Infallible>))
+    /// });
+    ///
+    /// let writer = MultipartWriter::new().part(part);
+    /// let retry = writer.try_clone().unwrap();
+    /// ```
+    pub fn reopenable<N, F, S, O, E>(name: N, open: F) -> Part
+    where
+        N: Into<String>,
+        F: Fn() -> S + Send + Sync + 'static,
+        S: Stream<Item = Result<O, E>> + Send + 'static,
+        O: Into<Bytes> + 'static,
+        E: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+    {
+        Part {
+            body: PartBody::Reopen(Arc::new(move || box_stream(open()))),
+            ..Part::bytes(name, Bytes::new())
+        }
+    }
+
+    /// Creates a field whose data is read from the given [`AsyncRead`] reader
+    /// as the body is sent, e.g. an open file.
+    ///
+    /// # Optional
+    ///
+    /// This requires the optional `tokio-io` feature to be enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use multer::Part;
+    ///
+    /// # async fn run() -> std::io::Result<()> {
+    /// let file = tokio::fs::File::open("video.mp4").await?;
+    /// let part = Part::reader("my_file_field", file)
+    ///     .file_name("video.mp4")
+    ///     .content_type("video/mp4".parse().unwrap());
+    /// # Ok(())
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run()).unwrap();
+    /// ```
+    #[cfg(feature = "tokio-io")]
+    #[cfg_attr(nightly, doc(cfg(feature = "tokio-io")))]
+    pub fn reader<N, R>(name: N, reader: R) -> Part
+    where
+        N: Into<String>,
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        Part::stream(name, ReaderStream::new(reader))
+    }
+
+    /// Creates a field whose data is a nested multipart body, usually a
+    /// [`MultipartWriter::mixed()`] one with several files, along with its
+    /// `Content-Type`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use multer::{Multipart, MultipartWriter, Part};
+    ///
+    /// # async fn run() {
+    /// let files = MultipartWriter::mixed()
+    ///     .part(Part::bytes("", "abcd").file_name("a.txt"))
+    ///     .part(Part::bytes("", "efgh").file_name("b.txt"));
+    /// let writer = MultipartWriter::new().part(Part::nested("files", files));
+    ///
+    /// let boundary = writer.boundary().to_owned();
+    /// let mut multipart = Multipart::new(writer.into_stream(), boundary);
+    ///
+    /// let field = multipart.next_field().await.unwrap().unwrap();
+    /// let mut nested = field.into_nested_multipart().unwrap();
+    ///
+    /// let file = nested.next_field().await.unwrap().unwrap();
+    /// assert_eq!(file.file_name(), Some("a.txt"));
+    /// assert_eq!(file.text().await.unwrap(), "abcd");
+    /// # }
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(run());
+    /// ```
+    pub fn nested<N: Into<String>>(name: N, body: MultipartWriter) -> Part {
+        Part {
+            name: name.into(),
+            file_name: None,
+            content_type: body.content_type().parse().ok(),
+            headers: HeaderMap::new(),
+            length: body.content_length(),
+            base64: false,
+            body: PartBody::Nested(Box::new(body)),
+        }
+    }
+
+    /// Sets the file name of the part, which makes it a file field.
+    ///
+    /// A non-ASCII file name is sent as an RFC 5987 `filename*` parameter,
+    /// with the non-ASCII characters replaced by `_` in the plain `filename`.
+    pub fn file_name<F: Into<String>>(mut self, file_name: F) -> Part {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Sets the `Content-Type` of the part.
+    pub fn content_type(mut self, content_type: mime::Mime) -> Part {
+        self.content_type = Some(content_type);
+        self
+    }
+
+    /// Declares the length of the data of a [stream](Part::stream) or
+    /// `reader` part, so that the length of the whole body is known, see
+    /// [`MultipartWriter::content_length()`].
+    ///
+    /// The body fails with
+    /// [`Error::StreamReadFailed`](crate::Error::StreamReadFailed) if the
+    /// stream turns out to be shorter or longer. This has no effect on other
+    /// parts, whose length is always known.
+    pub fn length(mut self, length: u64) -> Part {
+        self.length = Some(length);
+        self
+    }
+
+    /// Encodes the data of the part in base64, with a
+    /// `Content-Transfer-Encoding: base64` header, for receivers which don't
+    /// accept binary data in a MIME body.
+    ///
+    /// The data are encoded as they're streamed, without being buffered. This
+    /// has no effect on a [nested](Part::nested) part, which can't be
+    /// transfer-encoded. A `Content-Transfer-Encoding` header added with
+    /// [`header()`](Part::header) is ignored then, so that the part isn't
+    /// mislabelled.
+    pub fn base64(mut self) -> Part {
+        self.base64 = true;
+        self
+    }
+
+    /// Adds a header to the part, e.g. `Content-ID` or a checksum, written
+    /// after the generated ones.
+    ///
+    /// A `Content-Disposition` or `Content-Type` header added this way
+    /// replaces the generated one, to reproduce a part verbatim. Header names
+    /// and values can't contain line breaks, so that a header can't be
+    /// injected through them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use http::header::{HeaderName, HeaderValue};
+    /// use multer::Part;
+    ///
+    /// let part = Part::bytes("avatar", vec![0x89, b'P', b'N', b'G'])
+    ///     .file_name("avatar.png")
+    ///     .header(
+    ///         HeaderName::from_static("x-checksum"),
+    ///         HeaderValue::from_static("sha256=..."),
+    ///     );
+    /// ```
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Part {
+        self.headers.append(name, value);
+        self
+    }
+
+    /// Writes the header section of the part, up to and including the empty
+    /// line ending it.
+    fn write_headers(&self, buf: &mut BytesMut, mixed: bool) {
+        if !self.headers.contains_key(header::CONTENT_DISPOSITION) {
+            self.write_content_disposition(buf, mixed);
+        }
+
+        if let Some(content_type) = self
+            .content_type
+            .as_ref()
+            .filter(|_| !self.headers.contains_key(header::CONTENT_TYPE))
+        {
+            buf.put_slice(b"Content-Type: ");
+            buf.put_slice(content_type.as_ref().as_bytes());
+            buf.put_slice(constants::CRLF.as_bytes());
+        }
+
+        let base64 = self.is_base64();
+        if base64 {
+            buf.put_slice(b"Content-Transfer-Encoding: base64");
+            buf.put_slice(constants::CRLF.as_bytes());
+        }
+
+        for (name, value) in &self.headers {
+            if base64 && name == "content-transfer-encoding" {
+                continue;
+            }
+
+            buf.put_slice(name.as_str().as_bytes());
+            buf.put_slice(b": ");
+            buf.put_slice(value.as_bytes());
+            buf.put_slice(constants::CRLF.as_bytes());
+        }
+
+        buf.put_slice(constants::CRLF.as_bytes());
+    }
+
+    /// Clones the part, unless its data is read from a
+    /// [stream](Part::stream) or a reader, which can only be read once.
+    pub fn try_clone(&self) -> Option<Part> {
+        let body = match &self.body {
+            PartBody::Bytes(bytes) => PartBody::Bytes(bytes.clone()),
+            PartBody::Stream(_) => return None,
+            PartBody::Reopen(open) => PartBody::Reopen(open.clone()),
+            PartBody::Nested(body) => PartBody::Nested(Box::new(body.try_clone()?)),
+        };
+
+        Some(Part {
+            name: self.name.clone(),
+            file_name: self.file_name.clone(),
+            content_type: self.content_type.clone(),
+            headers: self.headers.clone(),
+            length: self.length,
+            base64: self.base64,
+            body,
+        })
+    }
+
+    /// Whether the data of the part are known to contain the given boundary,
+    /// including the delimiters and parts of a nested body.
+    fn contains(&self, boundary: &str) -> bool {
+        match &self.body {
+            PartBody::Bytes(bytes) => memchr::memmem::find(bytes, boundary.as_bytes()).is_some(),
+            PartBody::Nested(body) => {
+                body.boundary.contains(boundary) || body.parts.iter().any(|part| part.contains(boundary))
+            }
+            PartBody::Stream(_) | PartBody::Reopen(_) => false,
+        }
+    }
+
+    /// The length of the data of the part once encoded, if known.
+    fn encoded_length(&self) -> Option<u64> {
+        let len = match &self.body {
+            PartBody::Bytes(bytes) => bytes.len() as u64,
+            PartBody::Stream(_) | PartBody::Reopen(_) | PartBody::Nested(_) => self.length?,
+        };
+
+        match self.is_base64() {
+            true => Some(Base64Encoder::encoded_len(len)),
+            false => Some(len),
+        }
+    }
+
+    fn is_base64(&self) -> bool {
+        self.base64 && !matches!(self.body, PartBody::Nested(_))
+    }
+
+    fn write_content_disposition(&self, buf: &mut BytesMut, mixed: bool) {
+        match mixed {
+            true => buf.put_slice(b"Content-Disposition: file"),
+            false => buf.put_slice(b"Content-Disposition: form-data"),
+        }
+
+        if !mixed || !self.name.is_empty() {
+            buf.put_slice(b"; name=\"");
+            write_quoted(buf, &self.name);
+            buf.put_u8(b'"');
+        }
+
+        // A non-ASCII file name is written as an extended value, along with an
+        // ASCII fallback for older receivers.
+        if let Some(file_name) = &self.file_name {
+            buf.put_slice(b"; filename=\"");
+            match file_name.is_ascii() {
+                true => write_quoted(buf, file_name),
+                false => write_quoted(buf, &file_name.replace(|ch: char| !ch.is_ascii(), "_")),
+            }
+            buf.put_u8(b'"');
+
+            if !file_name.is_ascii() {
+                buf.put_slice(b"; filename*=");
+                buf.put_slice(constants::encode_ext_value(file_name).as_bytes());
+            }
+        }
+        buf.put_slice(constants::CRLF.as_bytes());
+    }
+}
+
+/// Writes the content of a quoted-string, escaping quotes and backslashes,
+/// and percent-encoding line breaks as browsers do.
+fn write_quoted(buf: &mut BytesMut, value: &str) {
+    for b in value.bytes() {
+        match b {
+            b'"' | b'\\' => buf.put_slice(&[b'\\', b]),
+            b'\r' => buf.put_slice(b"%0D"),
+            b'\n' => buf.put_slice(b"%0A"),
+            b => buf.put_u8(b),
+        }
+    }
+}
+
+/// Generates a boundary from 128 random bits.
+fn random_boundary() -> String {
+    let mut bytes = [0; 16];
+    getrandom::getrandom(&mut bytes).expect("failed to generate a random boundary");
+
+    let mut boundary = String::from("multer-");
+    for b in bytes {
+        boundary.push_str(&format!("{:02x}", b));
+    }
+
+    boundary
+}
+
+/// The serialized body of a [`MultipartWriter`], as a stream of [`Bytes`].
+#[derive(Debug)]
+pub struct MultipartStream {
+    boundary: String,
+    parts: std::vec::IntoIter<Part>,
+    mixed: bool,
+    body: Option<PartBody>,
+    /// The declared length of the current stream part left to be read.
+    remaining: Option<u64>,
+    encoder: Option<Base64Encoder>,
+    started: bool,
+    done: bool,
+    on_progress: Option<ProgressHandler>,
+    part_name: Option<String>,
+    part_index: Option<usize>,
+    /// The number of bytes emitted before the data of the current part.
+    part_start: u64,
+    total_bytes: u64,
+    total_length: Option<u64>,
+}
+
+impl MultipartStream {
+    /// Writes the delimiter preceding a part, or the close delimiter if there
+    /// is none.
+    fn write_delimiter(&mut self, buf: &mut BytesMut, close: bool) {
+        if self.started {
+            buf.put_slice(constants::CRLF.as_bytes());
+        }
+        self.started = true;
+
+        buf.put_slice(constants::BOUNDARY_EXT.as_bytes());
+        buf.put_slice(self.boundary.as_bytes());
+        if close {
+            buf.put_slice(constants::BOUNDARY_EXT.as_bytes());
+        }
+        buf.put_slice(constants::CRLF.as_bytes());
+    }
+
+    /// Polls the next chunk of data, or of delimiters and headers.
+    fn poll_chunk(&mut self, cx: &mut Context<'_>) -> Poll<Option<crate::Result<Bytes>>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        // Emit the data of the current part before moving on to the next one.
+        loop {
+            let poll = match self.body.as_mut() {
+                Some(PartBody::Bytes(bytes)) => match std::mem::take(bytes) {
+                    bytes if bytes.is_empty() => Poll::Ready(None),
+                    bytes => Poll::Ready(Some(Ok(bytes))),
+                },
+                Some(PartBody::Stream(stream)) => stream.as_mut().poll_next(cx),
+                Some(PartBody::Reopen(_)) | Some(PartBody::Nested(_)) => {
+                    self.body = self.body.take().map(PartBody::open);
+                    continue;
+                }
+                None => break,
+            };
+
+            match poll {
+                Poll::Ready(Some(Ok(bytes))) => {
+                    if let Some(remaining) = self.remaining.as_mut() {
+                        match remaining.checked_sub(bytes.len() as u64) {
+                            Some(rest) => *remaining = rest,
+                            None => return Poll::Ready(Some(Err(self.length_mismatch()))),
+                        }
+                    }
+
+                    let bytes = match self.encoder.as_mut() {
+                        Some(encoder) => encoder.encode(&bytes, false),
+                        None => bytes,
+                    };
+                    if !bytes.is_empty() {
+                        return Poll::Ready(Some(Ok(bytes)));
+                    }
+                }
+                Poll::Ready(Some(Err(err))) => {
+                    self.body = None;
+                    self.done = true;
+                    return Poll::Ready(Some(Err(err)));
+                }
+                Poll::Ready(None) => {
+                    if self.remaining.take().is_some_and(|remaining| remaining > 0) {
+                        return Poll::Ready(Some(Err(self.length_mismatch())));
+                    }
+
+                    self.body = None;
+                    if let Some(mut encoder) = self.encoder.take() {
+                        let bytes = encoder.encode(&[], true);
+                        if !bytes.is_empty() {
+                            return Poll::Ready(Some(Ok(bytes)));
+                        }
+                    }
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+
+        let mut buf = BytesMut::new();
+        match self.parts.next() {
+            Some(part) => {
+                self.write_delimiter(&mut buf, false);
+                part.write_headers(&mut buf, self.mixed);
+                self.part_index = Some(self.part_index.map_or(0, |idx| idx + 1));
+                self.part_start = self.total_bytes + buf.len() as u64;
+                self.encoder = part.is_base64().then(Base64Encoder::default);
+                self.remaining = match part.body {
+                    PartBody::Stream(_) | PartBody::Reopen(_) => part.length,
+                    _ => None,
+                };
+                self.part_name = Some(part.name);
+                self.body = Some(part.body);
+            }
+            None => {
+                self.write_delimiter(&mut buf, true);
+                self.part_index = None;
+                self.part_name = None;
+                self.part_start = self.total_bytes + buf.len() as u64;
+                self.done = true;
+            }
+        }
+
+        Poll::Ready(Some(Ok(buf.freeze())))
+    }
+
+    fn length_mismatch(&mut self) -> Error {
+        self.body = None;
+        self.done = true;
+
+        let err = std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "the data of a part don't match its declared length",
+        );
+        Error::StreamReadFailed(err.into())
+    }
+}
+
+impl Stream for MultipartStream {
+    type Item = crate::Result<Bytes>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let poll = this.poll_chunk(cx);
+
+        if let Poll::Ready(Some(Ok(bytes))) = &poll {
+            this.total_bytes += bytes.len() as u64;
+
+            if let Some(ProgressHandler(on_progress)) = &this.on_progress {
+                on_progress(&WriteProgress {
+                    part_name: this.part_name.as_deref(),
+                    part_index: this.part_index,
+                    part_bytes: this.total_bytes.saturating_sub(this.part_start),
+                    total_bytes: this.total_bytes,
+                    total_length: this.total_length,
+                });
+            }
+        }
+
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.done {
+            true => (0, Some(0)),
+            false => (1, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_headers() {
+        let mut buf = BytesMut::new();
+        Part::text("a\"b", "")
+            .file_name("c\\d\r\n.txt")
+            .content_type(mime::TEXT_PLAIN_UTF_8)
+            .write_headers(&mut buf, false);
+
+        assert_eq!(
+            buf,
+            "Content-Disposition: form-data; name=\"a\\\"b\"; filename=\"c\\\\d%0D%0A.txt\"\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn test_write_non_ascii_file_name() {
+        let mut buf = BytesMut::new();
+        Part::text("a", "")
+            .file_name("ça va.txt")
+            .write_headers(&mut buf, false);
+
+        assert_eq!(
+            buf,
+            "Content-Disposition: form-data; name=\"a\"; filename=\"_a va.txt\"; filename*=utf-8''%C3%A7a%20va.txt\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn test_random_boundary() {
+        let boundary = random_boundary();
+        assert_eq!(boundary.len(), 39);
+        assert_ne!(boundary, random_boundary());
+        assert!(helpers::validate_boundary(&boundary).is_ok());
+    }
+
+    #[test]
+    fn test_random_boundary_collision() {
+        let writer = MultipartWriter::new().text("a", "abcd");
+        let boundary = writer.boundary().to_owned();
+
+        let writer = writer.text("b", format!("\r\n--{}\r\n", boundary));
+        assert_ne!(writer.boundary(), boundary);
+        assert!(!writer.parts.iter().any(|part| part.contains(writer.boundary())));
+
+        let writer = MultipartWriter::with_boundary("X-BOUNDARY").text("a", "--X-BOUNDARY");
+        assert_eq!(writer.boundary(), "X-BOUNDARY");
+    }
+
+    #[test]
+    fn test_random_boundary_collision_nested() {
+        let writer = MultipartWriter::new().text("a", "abcd");
+        let boundary = writer.boundary().to_owned();
+
+        let nested = MultipartWriter::mixed().part(Part::bytes("", format!("\r\n--{}\r\n", boundary)));
+        let writer = writer.part(Part::nested("b", nested));
+        assert_ne!(writer.boundary(), boundary);
+        assert!(!writer.parts.iter().any(|part| part.contains(writer.boundary())));
+
+        let nested = MultipartWriter::with_boundary(format!("{}-nested", boundary));
+        let writer = MultipartWriter::with_boundary("X-BOUNDARY").part(Part::nested("a", nested));
+        assert!(!writer.parts[0].contains("X-BOUNDARY"));
+        assert!(writer.parts[0].contains(&boundary));
+    }
+
+    #[test]
+    fn test_content_type() {
+        let writer = MultipartWriter::with_boundary("X-BOUNDARY");
+        assert_eq!(writer.content_type(), "multipart/form-data; boundary=X-BOUNDARY");
+
+        let writer = MultipartWriter::with_boundary("a b:c/d?e=f");
+        let content_type = writer.content_type();
+        assert_eq!(content_type, "multipart/form-data; boundary=\"a b:c/d?e=f\"");
+        assert_eq!(crate::parse_boundary(&content_type).unwrap(), "a b:c/d?e=f");
+    }
+}
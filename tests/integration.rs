@@ -1,6 +1,9 @@
 use bytes::Bytes;
 use futures_util::{stream, Stream};
-use multer::{Constraints, Multipart, SizeLimit};
+use multer::{
+    Constraints, DuplicateHeaderPolicy, ExtensionPolicy, FileContentTypePolicy, Multipart, RelatedMultipart, SizeLimit,
+    UnknownFieldPolicy,
+};
 
 fn str_stream(string: &'static str) -> impl Stream<Item = multer::Result<Bytes>> {
     stream::iter(
@@ -266,3 +269,2932 @@ async fn test_multiaccess_caught() {
     assert!(matches!(field2.unwrap_err(), multer::Error::LockFailure));
     assert!(field1.is_ok());
 }
+
+#[tokio::test]
+async fn test_multipart_s3_post_form() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"key\"\r\n\r\nuploads/a.txt\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"Policy\"\r\n\r\neyJ9\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"X-Amz-Signature\"\r\n\r\nabc123\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\r\nHello world\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+
+    let form = m.s3_post_form().await.unwrap();
+    assert_eq!(form.key(), "uploads/a.txt");
+    assert_eq!(form.policy(), Some("eyJ9"));
+    assert_eq!(form.signature(), Some("abc123"));
+    assert_eq!(form.fields().count(), 3);
+    assert_eq!(form.into_file().text().await.unwrap(), "Hello world");
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"key\"\r\n\r\nuploads/a.txt\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"policy\"\r\n\r\neyJ9\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\r\nHello world\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    assert!(m.s3_post_form().await.is_err());
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"a.txt\"\r\n\r\nHello world\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"key\"\r\n\r\nuploads/a.txt\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    assert!(m.s3_post_form().await.is_err());
+}
+
+#[tokio::test]
+async fn test_multipart_with_bytes_zero_copy() {
+    let data = Bytes::from_static(b"--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a-text-file.txt\"\r\nContent-Type: text/plain\r\n\r\nHello world\nHello\r\nWorld\rAgain\r\n--X-BOUNDARY--\r\n");
+    let range = data.as_ptr_range();
+    let mut m = Multipart::with_bytes(data.clone(), "X-BOUNDARY");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    let bytes = field.bytes().await.unwrap();
+    assert_eq!(bytes, "abcd");
+    assert!(range.contains(&bytes.as_ptr()));
+
+    let mut field = m.next_field().await.unwrap().unwrap();
+    let chunk = field.chunk().await.unwrap().unwrap();
+    assert_eq!(chunk, "Hello world\nHello\r\nWorld\rAgain");
+    assert!(range.contains(&chunk.as_ptr()));
+    drop(field);
+
+    assert!(m.next_field().await.unwrap().is_none());
+}
+
+#[cfg(feature = "tokio-uring")]
+#[test]
+fn test_field_save_to_file_uring() {
+    let path = std::env::temp_dir().join("multer_test_field_save_to_file_uring.txt");
+
+    tokio_uring::start(async {
+        let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a-text-file.txt\"\r\nContent-Type: text/plain\r\n\r\nHello world\nHello\r\nWorld\rAgain\r\n--X-BOUNDARY--\r\n";
+        let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+
+        let field = m.next_field().await.unwrap().unwrap();
+        let options = multer::UringOptions::new().buffer_size(4);
+        assert_eq!(field.save_to_file_uring(&path, options).await.unwrap(), 30);
+    });
+
+    assert_eq!(std::fs::read(&path).unwrap(), b"Hello world\nHello\r\nWorld\rAgain");
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "tokio-uring")]
+#[test]
+fn test_field_spool_uring() {
+    use multer::{SpooledField, UringOptions};
+
+    tokio_uring::start(async {
+        let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n\
+            --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"; filename=\"b.txt\"\r\n\r\nHello world\nHello\r\nWorld\rAgain\r\n\
+            --X-BOUNDARY--\r\n";
+        let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+
+        let field = m.next_field().await.unwrap().unwrap();
+        let spooled = field.spool_uring(8, UringOptions::new()).await.unwrap();
+        assert_eq!(spooled, SpooledField::Memory("abcd".into()));
+
+        let field = m.next_field().await.unwrap().unwrap();
+        let path = match field.spool_uring(8, UringOptions::new().buffer_size(4)).await.unwrap() {
+            SpooledField::File(path) => path,
+            spooled => panic!("expected a temporary file, got {:?}", spooled),
+        };
+        assert_eq!(std::fs::read(&path).unwrap(), b"Hello world\nHello\r\nWorld\rAgain");
+        tokio_uring::fs::remove_file(path).await.unwrap();
+    });
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn test_field_save_to_file() {
+    let path = std::env::temp_dir().join("multer_test_field_save_to_file.txt");
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a-text-file.txt\"\r\n\r\nHello world\nHello\r\nWorld\rAgain\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let constraints = Constraints::new().size_limit(SizeLimit::new().for_field("my_text_field", 2));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.save_to_file(&path).await.unwrap(), 30);
+    assert_eq!(std::fs::read(&path).unwrap(), b"Hello world\nHello\r\nWorld\rAgain");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    let err = field.save_to_file(&path).await.unwrap_err();
+    assert!(matches!(err, multer::Error::FieldSizeExceeded { limit: 2, .. }));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "tokio-fs")]
+#[tokio::test]
+async fn test_field_spool() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a-text-file.txt\"\r\n\r\nHello world\nHello\r\nWorld\rAgain\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    let spooled = field.spool(10).await.unwrap();
+    assert_eq!(spooled.as_bytes().map(|b| &b[..]), Some(&b"abcd"[..]));
+
+    let field = m.next_field().await.unwrap().unwrap();
+    let spooled = field.spool(10).await.unwrap();
+    assert!(!spooled.is_in_memory());
+    let path = spooled.path().unwrap();
+    assert_eq!(std::fs::read(path).unwrap(), b"Hello world\nHello\r\nWorld\rAgain");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn test_multipart_rfc2231_file_name() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename*0*=utf-8''a%20long; filename*1=\" file name.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("my_file_field"));
+    assert_eq!(field.file_name(), Some("a long file name.txt"));
+}
+
+#[tokio::test]
+async fn test_multipart_transfer_encoding_base64() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a-text-file.txt\"\r\nContent-Transfer-Encoding: BASE64\r\n\r\nSGVsbG8gd29y\r\nbGQKSGVsbG8NCldvcmxkDUFnYWlu\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\nContent-Transfer-Encoding: base64\r\n\r\nYWJjZA\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "Hello world\nHello\r\nWorld\rAgain");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcd");
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\nContent-Transfer-Encoding: base64\r\n\r\nYW!jZA==\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(field.bytes().await.is_err());
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_unknown_transfer_encoding() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\nContent-Transfer-Encoding: x-uuencode\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcd");
+
+    let constraints = Constraints::new().reject_unknown_transfer_encodings(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert!(m.next_field().await.is_err());
+}
+
+#[tokio::test]
+async fn test_multipart_transfer_encoding_quoted_printable() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Transfer-Encoding: quoted-printable\r\n\r\nCaf=C3=A9 au =\r\nlait =3D 2=E2=82=AC\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "Café au lait = 2€");
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\nContent-Transfer-Encoding: quoted-printable\r\n\r\n100% =ZZ\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "100% =ZZ");
+
+    let constraints = Constraints::new().strict_quoted_printable(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(field.text().await.is_err());
+}
+
+#[tokio::test]
+async fn test_multipart_nested_multipart() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"files\"\r\nContent-Type: multipart/mixed; boundary=Y-BOUNDARY\r\n\r\n--Y-BOUNDARY\r\nContent-Disposition: file; filename=\"a.txt\"\r\nContent-Type: text/plain\r\n\r\nHello world\r\n--Y-BOUNDARY\r\nContent-Disposition: file; filename=\"b.txt\"\r\nContent-Type: text/plain\r\n\r\nHello\r\nWorld\r\n--Y-BOUNDARY--\r\n\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(field.into_nested_multipart().is_err());
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("files"));
+
+    let mut nested = field.into_nested_multipart().unwrap();
+    let file = nested.next_field().await.unwrap().unwrap();
+    assert_eq!(file.file_name(), Some("a.txt"));
+    assert_eq!(file.text().await.unwrap(), "Hello world");
+
+    let file = nested.next_field().await.unwrap().unwrap();
+    assert_eq!(file.file_name(), Some("b.txt"));
+    assert_eq!(file.text().await.unwrap(), "Hello\r\nWorld");
+
+    assert!(nested.next_field().await.unwrap().is_none());
+    drop(nested);
+
+    assert!(m.next_field().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_multipart_related() {
+    let data = "--X-BOUNDARY\r\nContent-ID: <image%40example.com>\r\nContent-Type: image/png\r\n\r\nPNG\r\n--X-BOUNDARY\r\nContent-ID: <root@example.com>\r\nContent-Type: application/xop+xml\r\n\r\n<doc/>\r\n--X-BOUNDARY--\r\n";
+    let content_type =
+        "multipart/related; boundary=X-BOUNDARY; type=\"application/xop+xml\"; start=\"<root@example.com>\"";
+
+    let related = RelatedMultipart::new(str_stream(data), content_type).unwrap();
+    assert_eq!(related.start(), Some("root@example.com"));
+    assert_eq!(related.root_type(), Some("application/xop+xml"));
+
+    let parts = related.parts().await.unwrap();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts.root().data(), "<doc/>");
+    assert_eq!(parts.get("<root@example.com>").unwrap().data(), "<doc/>");
+    assert_eq!(parts.get("cid:image%2540example.com").unwrap().data(), "PNG");
+    assert_eq!(
+        parts.get("image%40example.com").unwrap().content_type(),
+        Some(&mime::IMAGE_PNG)
+    );
+    assert!(parts.get("missing@example.com").is_none());
+
+    let content_type = "multipart/related; boundary=X-BOUNDARY";
+    let parts = RelatedMultipart::new(str_stream(data), content_type)
+        .unwrap()
+        .parts()
+        .await
+        .unwrap();
+    assert_eq!(parts.root().data(), "PNG");
+
+    let content_type = "multipart/related; boundary=X-BOUNDARY; start=\"<missing@example.com>\"";
+    let related = RelatedMultipart::new(str_stream(data), content_type).unwrap();
+    assert!(matches!(
+        related.parts().await,
+        Err(multer::Error::MissingRelatedRoot { .. })
+    ));
+
+    assert!(RelatedMultipart::new(str_stream(data), "multipart/form-data; boundary=X-BOUNDARY").is_err());
+}
+
+#[tokio::test]
+async fn test_multipart_byteranges() {
+    let data = "--X-BOUNDARY\r\nContent-Type: text/plain\r\nContent-Range: bytes 0-3/12\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Type: text/plain\r\nContent-Range: bytes 8-11/*\r\n\r\nijkl\r\n--X-BOUNDARY\r\nContent-Range: bytes 5-4/12\r\n\r\n\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::byteranges(str_stream(data), "multipart/byteranges; boundary=X-BOUNDARY").unwrap();
+
+    let field = m.next_field().await.unwrap().unwrap();
+    let range = field.content_range().unwrap();
+    assert_eq!((range.start(), range.end(), range.complete_length()), (0, 3, Some(12)));
+    assert_eq!(range.len(), 4);
+    assert_eq!(field.bytes().await.unwrap(), "abcd");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    let range = field.content_range().unwrap();
+    assert_eq!((range.start(), range.end(), range.complete_length()), (8, 11, None));
+    assert_eq!(field.bytes().await.unwrap(), "ijkl");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(field.content_range().is_none());
+    drop(field);
+
+    assert!(m.next_field().await.unwrap().is_none());
+
+    assert!(Multipart::byteranges(str_stream(data), "multipart/form-data; boundary=X-BOUNDARY").is_err());
+}
+
+#[tokio::test]
+async fn test_multipart_lf_line_endings() {
+    let data = "--X-BOUNDARY\nContent-Disposition: form-data; name=\"my_text_field\"\n\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.txt\"\nContent-Type: text/plain\r\n\nHello\r\nWorld\r\n\n--X-BOUNDARY--\n";
+
+    let constraints = Constraints::new().allow_lf_line_endings(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("my_text_field"));
+    assert_eq!(field.text().await.unwrap(), "abcd");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("my_file_field"));
+    assert_eq!(field.content_type(), Some(&mime::TEXT_PLAIN));
+    assert_eq!(field.text().await.unwrap(), "Hello\r\nWorld\r\n");
+
+    assert!(m.next_field().await.unwrap().is_none());
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    assert!(m.next_field().await.is_err());
+}
+
+#[tokio::test]
+async fn test_multipart_preamble_epilogue() {
+    let data = "preamble\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY-- \r\nepilogue\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    assert!(m.preamble().is_none());
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(m.preamble().unwrap(), "preamble");
+    assert!(m.epilogue().await.unwrap().is_none());
+    assert_eq!(field.text().await.unwrap(), "abcd");
+
+    assert!(m.next_field().await.unwrap().is_none());
+    assert_eq!(m.epilogue().await.unwrap().unwrap(), "epilogue\r\n");
+    assert_eq!(m.epilogue().await.unwrap().unwrap(), "epilogue\r\n");
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    while let Some(field) = m.next_field().await.unwrap() {
+        field.bytes().await.unwrap();
+    }
+
+    assert_eq!(m.preamble().unwrap(), "");
+    assert_eq!(m.epilogue().await.unwrap().unwrap(), "");
+}
+
+#[tokio::test]
+async fn test_multipart_honor_charset_field() {
+    let data: &[u8] = b"--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"_charset_\"\r\n\r\nshift_jis\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"utf8_field\"\r\nContent-Type: text/plain; charset=utf-8\r\n\r\n\xe3\x81\x82\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"sjis_field\"\r\n\r\n\x82\xa0\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().honor_charset_field(true);
+    let mut m = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+    assert!(m.form_charset().is_none());
+
+    // The `_charset_` value is tracked even if the field is not read.
+    drop(m.next_field().await.unwrap().unwrap());
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(m.form_charset().as_deref(), Some("shift_jis"));
+    assert_eq!(field.text().await.unwrap(), "\u{3042}");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "\u{3042}");
+
+    let mut m = Multipart::with_bytes(data, "X-BOUNDARY");
+    while let Some(field) = m.next_field().await.unwrap() {
+        if field.name() == Some("sjis_field") {
+            assert_eq!(field.text().await.unwrap(), "\u{fffd}\u{fffd}");
+        }
+    }
+    assert!(m.form_charset().is_none());
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_strict() {
+    let valid = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::with_constraints(str_stream(valid), "X-BOUNDARY", Constraints::new().strict(true));
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcd");
+    assert!(m.next_field().await.unwrap().is_none());
+
+    let invalid = [
+        "--X-BOUNDARY\r\nContent-Type: text/plain\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n",
+        "--X-BOUNDARY\r\nContent-Disposition: attachment; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n",
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; filename=\"a.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n",
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename*=utf-8''a.txt\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n",
+    ];
+
+    for data in invalid {
+        let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+        assert!(m.next_field().await.unwrap().is_some());
+
+        let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", Constraints::new().strict(true));
+        assert!(matches!(
+            m.next_field().await,
+            Err(multer::Error::StrictViolation { .. })
+        ));
+    }
+
+    let data =
+        "--X{BOUNDARY}\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X{BOUNDARY}--\r\n";
+    let mut m = Multipart::with_constraints(str_stream(data), "X{BOUNDARY}", Constraints::new().strict(true));
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::InvalidBoundary {
+            character: Some('{'),
+            ..
+        })
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_per_field_headers_size_limit() {
+    let data =
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().size_limit(SizeLimit::new().per_field_headers(56));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcd");
+
+    let constraints = Constraints::new().size_limit(SizeLimit::new().per_field_headers(55));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::HeadersSizeExceeded { limit: 55 })
+    ));
+
+    // An unterminated header line is rejected, rather than buffered until the end
+    // of the stream.
+    let data = stream::iter(
+        std::iter::once(Ok::<_, std::convert::Infallible>(Bytes::from_static(
+            b"--X-BOUNDARY\r\nX-Header: ",
+        )))
+        .chain(std::iter::repeat_n(Ok(Bytes::from_static(b"aaaaaaaa")), 1024)),
+    );
+    let constraints = Constraints::new().size_limit(SizeLimit::new().per_field_headers(1024));
+    let mut m = Multipart::with_constraints(data, "X-BOUNDARY", constraints);
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::HeadersSizeExceeded { limit: 1024 })
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_duplicate_headers() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"first\"\r\nContent-Type: text/plain\r\nContent-Disposition: form-data; name=\"last\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("last"));
+
+    let constraints = Constraints::new().duplicate_headers(DuplicateHeaderPolicy::FirstWins);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("first"));
+    assert_eq!(field.headers().get_all("content-disposition").iter().count(), 1);
+    assert_eq!(field.text().await.unwrap(), "abcd");
+
+    let constraints = Constraints::new().duplicate_headers(DuplicateHeaderPolicy::Reject);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::DuplicateHeader { name }) if name == "content-disposition"
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_latin1_fallback() {
+    let data: &[u8] = b"--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"caf\xe9.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+
+    let mut m = Multipart::with_bytes(data, "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("my_file_field"));
+    assert_eq!(field.file_name(), None);
+
+    let constraints = Constraints::new().latin1_fallback(true);
+    let mut m = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("my_file_field"));
+    assert_eq!(field.file_name(), Some("café.txt"));
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_strip_utf8_bom() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\n\u{feff}abcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"short\"\r\n\r\n\u{feff}\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"no_bom\"\r\n\r\n\u{ef}a\r\n--X-BOUNDARY--\r\n";
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.bytes().await.unwrap(), "\u{feff}abcd");
+
+    let constraints = Constraints::new().strip_utf8_bom(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.bytes().await.unwrap(), "abcd");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.bytes().await.unwrap(), "");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.bytes().await.unwrap(), "\u{ef}a");
+}
+
+#[tokio::test]
+async fn test_multipart_text_content_type_charset() {
+    let data: &[u8] = b"--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"cp1252\"\r\nContent-Type: text/plain; charset=windows-1252\r\n\r\n\x80 caf\xe9\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"unknown\"\r\nContent-Type: text/plain; charset=x-unknown\r\n\r\ncaf\xe9\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::with_bytes(data, "X-BOUNDARY");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "€ café");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text_with_charset("latin1").await.unwrap(), "café");
+}
+
+#[cfg(feature = "content-encoding")]
+#[tokio::test]
+async fn test_multipart_decompress_content_encoding() {
+    use std::io::Write;
+
+    let text = "Hello world ".repeat(64);
+
+    let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    gzip.write_all(text.as_bytes()).unwrap();
+    let gzip = gzip.finish().unwrap();
+
+    let mut br = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+    br.write_all(text.as_bytes()).unwrap();
+    let br = br.into_inner();
+
+    let mut data = Vec::new();
+    for (name, encoding, body) in [
+        ("gzip", "gzip", &gzip[..]),
+        ("br", "br", &br[..]),
+        ("plain", "zstd", b"raw"),
+    ] {
+        data.extend_from_slice(b"--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"");
+        data.extend_from_slice(name.as_bytes());
+        data.extend_from_slice(b"\"\r\nContent-Encoding: ");
+        data.extend_from_slice(encoding.as_bytes());
+        data.extend_from_slice(b"\r\n\r\n");
+        data.extend_from_slice(body);
+        data.extend_from_slice(b"\r\n");
+    }
+    data.extend_from_slice(b"--X-BOUNDARY--\r\n");
+
+    let mut m = Multipart::with_bytes(data.clone(), "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.bytes().await.unwrap(), gzip);
+
+    let constraints = Constraints::new().decompress_content_encoding(true);
+    let mut m = Multipart::with_bytes_with_constraints(data.clone(), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), text);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), text);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "raw");
+
+    let constraints = Constraints::new()
+        .decompress_content_encoding(true)
+        .decompressed_size_limit(100);
+    let mut m = Multipart::with_bytes_with_constraints(data.clone(), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(matches!(
+        field.bytes().await,
+        Err(multer::Error::DecompressedSizeExceeded { limit: 100, .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_verify_content_length() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"exact\"\r\nContent-Length: 4\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"short\"\r\nContent-Length: 6\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"long\"\r\nContent-Length: 2\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.content_length(), Some(4));
+    drop(field);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.content_length(), Some(6));
+    assert_eq!(field.bytes().await.unwrap(), "abcd");
+
+    let constraints = Constraints::new().verify_content_length(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.bytes().await.unwrap(), "abcd");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(matches!(
+        field.bytes().await,
+        Err(multer::Error::ContentLengthMismatch {
+            expected: 6,
+            actual: 4,
+            ..
+        })
+    ));
+
+    let constraints = Constraints::new().verify_content_length(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("exact"));
+    drop(field);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("short"));
+    drop(field);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(matches!(
+        field.bytes().await,
+        Err(multer::Error::ContentLengthMismatch {
+            expected: 2,
+            actual,
+            ..
+        }) if actual > 2
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_require_form_data() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: Attachment; name=\"my_file_field\"; filename=\"a.txt\"\r\n\r\nefgh\r\n--X-BOUNDARY--\r\n";
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.disposition_type(), Some("form-data"));
+    drop(field);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.disposition_type(), Some("attachment"));
+    assert_eq!(field.bytes().await.unwrap(), "efgh");
+
+    let constraints = Constraints::new().require_form_data(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcd");
+
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::InvalidDispositionType {
+            disposition_type: Some(ref disposition_type),
+            ..
+        }) if disposition_type == "attachment"
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_allow_folded_headers() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data;\r\n name=\"my_file_field\";\r\n\t filename=\"a-text-file.txt\"\r\nContent-Type:\r\n text/plain\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    assert!(matches!(m.next_field().await, Err(multer::Error::ReadHeaderFailed(_))));
+
+    let constraints = Constraints::new().allow_folded_headers(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("my_file_field"));
+    assert_eq!(field.file_name(), Some("a-text-file.txt"));
+    assert_eq!(field.content_type(), Some(&mime::TEXT_PLAIN));
+    assert_eq!(field.text().await.unwrap(), "abcd");
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_reject_transport_padding() {
+    let data =
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let constraints = Constraints::new().reject_transport_padding(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "abcd");
+    assert!(m.next_field().await.unwrap().is_none());
+
+    let data =
+        "--X-BOUNDARY \t\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let constraints = Constraints::new().reject_transport_padding(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::UnexpectedTransportPadding)
+    ));
+
+    let data =
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY-- \r\n";
+    let constraints = Constraints::new().reject_transport_padding(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "abcd");
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::UnexpectedTransportPadding)
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_max_depth() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"outer\"\r\nContent-Type: multipart/mixed; boundary=Y-BOUNDARY\r\n\r\n--Y-BOUNDARY\r\nContent-Type: multipart/mixed; boundary=Z-BOUNDARY\r\n\r\n--Z-BOUNDARY\r\nContent-Type: text/plain\r\n\r\nabcd\r\n--Z-BOUNDARY--\r\n\r\n--Y-BOUNDARY--\r\n\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().max_depth(0);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(matches!(
+        field.into_nested_multipart(),
+        Err(multer::Error::NestingDepthExceeded { depth: 1, limit: 0 })
+    ));
+
+    let constraints = Constraints::new().max_depth(1);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    let mut nested = field
+        .into_nested_multipart_with_constraints(Constraints::new().max_depth(5))
+        .unwrap();
+    let field = nested.next_field().await.unwrap().unwrap();
+    assert!(matches!(
+        field.into_nested_multipart(),
+        Err(multer::Error::NestingDepthExceeded { depth: 2, limit: 1 })
+    ));
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    let mut nested = field.into_nested_multipart().unwrap();
+    let field = nested.next_field().await.unwrap().unwrap();
+    let mut nested = field.into_nested_multipart().unwrap();
+    let field = nested.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcd");
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_skip_malformed_parts() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition form-data\r\n\r\nxyz\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nefgh\r\n--X-BOUNDARYzz\r\nContent-Disposition: form-data; name=\"c\"\r\n\r\nlost\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"d\"\r\n\r\nijkl\r\n--X-BOUNDARY--\r\n";
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "abcd");
+    assert!(matches!(m.next_field().await, Err(multer::Error::ReadHeaderFailed(_))));
+
+    let skipped = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let on_skip = skipped.clone();
+    let constraints = Constraints::new().skip_malformed_parts(move |err| on_skip.lock().unwrap().push(err));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let mut fields = Vec::new();
+    while let Some(field) = m.next_field().await.unwrap() {
+        let name = field.name().unwrap().to_owned();
+        fields.push((name, field.text().await.unwrap()));
+    }
+
+    assert_eq!(
+        fields,
+        [("a", "abcd"), ("b", "efgh"), ("d", "ijkl")].map(|(name, text)| (name.to_owned(), text.to_owned()))
+    );
+
+    let skipped = std::mem::take(&mut *skipped.lock().unwrap());
+    assert_eq!(skipped.len(), 2);
+    assert!(matches!(skipped[0], multer::Error::ReadHeaderFailed(_)));
+    assert!(matches!(skipped[1], multer::Error::IncompleteStream));
+
+    let truncated = &data[..data.find("lost").unwrap()];
+    let constraints = Constraints::new().skip_malformed_parts(|_| {});
+    let mut m = Multipart::with_constraints(str_stream(truncated), "X-BOUNDARY", constraints);
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "abcd");
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "efgh");
+    assert!(matches!(m.next_field().await, Err(multer::Error::IncompleteStream)));
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_positional_names() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Type: text/plain\r\n\r\nefgh\r\n--X-BOUNDARY--\r\n";
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    drop(m.next_field().await.unwrap().unwrap());
+    assert_eq!(m.next_field().await.unwrap().unwrap().name(), None);
+
+    let constraints = Constraints::new()
+        .positional_names(true)
+        .allowed_fields(vec!["my_text_field", "part-1"]);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("my_text_field"));
+    drop(field);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("part-1"));
+    assert_eq!(field.name_bytes(), None);
+    assert_eq!(field.text().await.unwrap(), "efgh");
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_decode_encoded_words() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"=?UTF-8?B?5L2g5aW9LnR4dA==?=\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.file_name(), Some("=?UTF-8?B?5L2g5aW9LnR4dA==?="));
+    drop(field);
+
+    let constraints = Constraints::new().decode_encoded_words(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("my_file_field"));
+    assert_eq!(field.file_name(), Some("你好.txt"));
+    assert_eq!(field.disposition_param("filename"), Some("你好.txt"));
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[tokio::test]
+async fn test_multipart_constraint_normalize_nfc() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"re\u{301}sume\u{301}\"; filename=\"cafe\u{301}.txt\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().normalize_nfc(true).allowed_fields(vec!["résumé"]);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("résumé"));
+    assert_eq!(field.file_name(), Some("café.txt"));
+    assert_eq!(field.name_bytes(), Some("re\u{301}sume\u{301}".as_bytes()));
+}
+
+#[tokio::test]
+async fn test_multipart_file_name_sanitized() {
+    let file_names = [
+        (r"C:\Users\me\photo.jpg", Some("photo.jpg")),
+        (r"\\server\share\photo.jpg", Some("photo.jpg")),
+        ("/home/me/photo.jpg", Some("photo.jpg")),
+        ("C:photo.jpg", Some("photo.jpg")),
+        ("photo.jpg", Some("photo.jpg")),
+        (r"C:\", None),
+        ("../..", None),
+    ];
+
+    for (file_name, expected) in file_names {
+        let data = format!(
+            "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"{}\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n",
+            file_name
+        );
+
+        let mut m = Multipart::with_bytes(data, "X-BOUNDARY");
+        let field = m.next_field().await.unwrap().unwrap();
+        assert_eq!(field.file_name_sanitized(), expected);
+        drop(field);
+
+        let data = format!(
+            "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"{}\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n",
+            file_name.replace('\\', "\\\\")
+        );
+
+        let mut m = Multipart::with_bytes(data, "X-BOUNDARY");
+        let field = m.next_field().await.unwrap().unwrap();
+        assert_eq!(field.file_name(), Some(file_name));
+        assert_eq!(field.file_name_sanitized(), expected);
+    }
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_allowed_content_types() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"a.png\"\r\nContent-Type: image/PNG\r\n\r\nPNG\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.txt\"\r\nContent-Type: text/plain; charset=utf-8\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"a.exe\"\r\nContent-Type: application/octet-stream\r\n\r\nMZ\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new()
+        .allowed_content_types("avatar", vec!["image/png", "image/jpeg"])
+        .allowed_content_types("doc", vec!["text/*"]);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    assert_eq!(m.next_field().await.unwrap().unwrap().bytes().await.unwrap(), "PNG");
+    assert_eq!(m.next_field().await.unwrap().unwrap().bytes().await.unwrap(), "abcd");
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::ContentTypeNotAllowed {
+            field_name: Some(ref name),
+            content_type: Some(ref content_type),
+        }) if name == "avatar" && content_type == "application/octet-stream"
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_file_extensions() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"me.PNG\"\r\n\r\nPNG\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"shell.php.txt\"\r\n\r\n<?php\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new()
+        .file_extensions(ExtensionPolicy::deny(vec![".exe", "php"]))
+        .file_extensions_for("avatar", ExtensionPolicy::allow(vec!["png", "jpg"]));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    assert_eq!(m.next_field().await.unwrap().unwrap().bytes().await.unwrap(), "PNG");
+    assert_eq!(m.next_field().await.unwrap().unwrap().bytes().await.unwrap(), "abcd");
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::FileExtensionNotAllowed { ref file_name, .. }) if file_name == "shell.php.txt"
+    ));
+
+    let constraints = Constraints::new().file_extensions_for("avatar", ExtensionPolicy::allow(vec!["jpg"]));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::FileExtensionNotAllowed { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_max_fields() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n1\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\n2\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"c\"\r\n\r\n3\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().size_limit(SizeLimit::new().max_fields(3));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    for _ in 0..3 {
+        assert!(m.next_field().await.unwrap().is_some());
+    }
+    assert!(m.next_field().await.unwrap().is_none());
+
+    let constraints = Constraints::new().size_limit(SizeLimit::new().max_fields(2));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert!(m.next_field().await.unwrap().is_some());
+    assert!(m.next_field().await.unwrap().is_some());
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::FieldCountExceeded { limit: 2 })
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_max_files() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"; filename=\"a.txt\"\r\n\r\n1\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\n2\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"c\"; filename=\"c.txt\"\r\n\r\n3\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().size_limit(SizeLimit::new().max_files(1));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert!(m.next_field().await.unwrap().is_some());
+    assert!(m.next_field().await.unwrap().is_some());
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::FileCountExceeded { limit: 1 })
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_required_fields() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n1\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\n2\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().required_fields(vec!["a", "b"]);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    while m.next_field().await.unwrap().is_some() {}
+
+    let constraints = Constraints::new().required_fields(vec!["a", "c", "d"]);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert!(m.next_field().await.unwrap().is_some());
+    assert!(m.next_field().await.unwrap().is_some());
+    for _ in 0..2 {
+        assert!(matches!(
+            m.next_field().await,
+            Err(multer::Error::MissingRequiredFields { ref field_names }) if field_names == &["c", "d"]
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_reject_duplicate_fields() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"files\"; filename=\"a.txt\"\r\n\r\n1\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"files\"; filename=\"b.txt\"\r\n\r\n2\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"role\"\r\n\r\nuser\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"role\"\r\n\r\nadmin\r\n--X-BOUNDARY--\r\n";
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let mut count = 0;
+    while m.next_field().await.unwrap().is_some() {
+        count += 1;
+    }
+    assert_eq!(count, 4);
+
+    let constraints = Constraints::new()
+        .reject_duplicate_fields(true)
+        .allow_duplicates_for(vec!["files"]);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    for _ in 0..3 {
+        assert!(m.next_field().await.unwrap().is_some());
+    }
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::DuplicateField { ref field_name }) if field_name == "role"
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_file_name_length_limit() {
+    // The second file name is 10 characters long but takes 16 bytes.
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"; filename=\"short.txt\"\r\n\r\n1\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"; filename=\"\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}\u{e9}.txt\"\r\n\r\n2\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().size_limit(SizeLimit::new().file_name_chars(10));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    while m.next_field().await.unwrap().is_some() {}
+
+    for size_limit in [
+        SizeLimit::new().file_name_bytes(12),
+        SizeLimit::new().file_name_chars(9),
+    ] {
+        let constraints = Constraints::new().size_limit(size_limit);
+        let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+        assert!(m.next_field().await.unwrap().is_some());
+        assert!(matches!(
+            m.next_field().await,
+            Err(multer::Error::FileNameTooLong { field_name: Some(ref name), .. }) if name == "b"
+        ));
+    }
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_validate() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"a.png\"\r\nContent-Type: image/png\r\nX-Scanned: yes\r\n\r\n1\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"b.png\"\r\nContent-Type: image/png\r\n\r\n2\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().validate(|field_name, file_name, content_type, headers| {
+        assert_eq!(field_name, Some("avatar"));
+        assert!(file_name.is_some());
+        assert_eq!(content_type, Some(&mime::IMAGE_PNG));
+        match headers.contains_key("x-scanned") {
+            true => Ok(()),
+            false => Err("file was not scanned"),
+        }
+    });
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.bytes().await.unwrap(), "1");
+
+    let err = m.next_field().await.unwrap_err();
+    assert!(matches!(err, multer::Error::ValidationFailed { field_name: Some(ref name), .. } if name == "avatar"));
+    assert_eq!(
+        std::error::Error::source(&err).unwrap().to_string(),
+        "file was not scanned"
+    );
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_validate_async() {
+    use std::sync::{Arc, Mutex};
+
+    use futures_util::StreamExt;
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n1\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\n2\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"c\"\r\n\r\n3\r\n--X-BOUNDARY--\r\n";
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let record = |calls: &Arc<Mutex<Vec<String>>>, call: &str| calls.lock().unwrap().push(call.to_owned());
+    let constraints = Constraints::new()
+        .validate({
+            let calls = calls.clone();
+            move |field_name, _, _, _| {
+                record(&calls, &format!("sync {}", field_name.unwrap()));
+                Ok::<_, std::convert::Infallible>(())
+            }
+        })
+        .validate_async({
+            let calls = calls.clone();
+            move |field_name, _, _, _| {
+                let calls = calls.clone();
+                let field_name = field_name.unwrap().to_owned();
+                async move {
+                    tokio::task::yield_now().await;
+                    record(&calls, &format!("async {}", field_name));
+                    match field_name.as_str() {
+                        "b" => Err("field b is rejected"),
+                        _ => Ok(()),
+                    }
+                }
+            }
+        })
+        .validate({
+            let calls = calls.clone();
+            move |field_name, _, _, _| {
+                record(&calls, &format!("last {}", field_name.unwrap()));
+                Ok::<_, std::convert::Infallible>(())
+            }
+        });
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "1");
+    assert_eq!(*calls.lock().unwrap(), ["sync a", "async a", "last a"]);
+
+    let err = m.next_field().await.unwrap_err();
+    assert!(matches!(err, multer::Error::ValidationFailed { field_name: Some(ref name), .. } if name == "b"));
+    assert_eq!(
+        std::error::Error::source(&err).unwrap().to_string(),
+        "field b is rejected"
+    );
+    assert_eq!(calls.lock().unwrap()[3..], ["sync b", "async b"]);
+
+    // The field being validated doesn't hold up the `Stream` of the `Multipart`.
+    let constraints = Constraints::new().validate_async(|_, _, _, _| async {
+        tokio::task::yield_now().await;
+        Ok::<_, std::convert::Infallible>(())
+    });
+    let m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    let texts: Vec<String> = m
+        .then(|field| async move { field.unwrap().text().await.unwrap() })
+        .collect()
+        .await;
+    assert_eq!(texts, ["1", "2", "3"]);
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_min_throughput() {
+    use std::time::Duration;
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcdefghijklmnopqrstuvwxyz\r\n--X-BOUNDARY--\r\n";
+    let slow_stream = || {
+        futures_util::StreamExt::then(str_stream(data), |chunk| async move {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            chunk
+        })
+    };
+
+    let constraints = Constraints::new().min_throughput(1, Duration::from_millis(50));
+    let mut m = Multipart::with_constraints(slow_stream(), "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcdefghijklmnopqrstuvwxyz");
+
+    let constraints = Constraints::new().min_throughput(1000, Duration::from_millis(50));
+    let mut m = Multipart::with_constraints(slow_stream(), "X-BOUNDARY", constraints);
+    let result = async {
+        let field = m.next_field().await?.unwrap();
+        field.text().await
+    };
+    assert!(matches!(
+        result.await,
+        Err(multer::Error::TooSlow { min_bytes: 1000, .. })
+    ));
+}
+
+#[cfg(feature = "tokio-time")]
+#[tokio::test]
+async fn test_multipart_constraint_deadline() {
+    use std::time::Duration;
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd";
+    let stalled_stream = futures_util::StreamExt::chain(str_stream(data), stream::pending());
+
+    let constraints = Constraints::new().deadline(Duration::from_millis(50));
+    let mut m = Multipart::with_constraints(stalled_stream, "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(matches!(
+        field.text().await,
+        Err(multer::Error::DeadlineExceeded { timeout }) if timeout == Duration::from_millis(50)
+    ));
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let constraints = Constraints::new().deadline(Duration::from_millis(50));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcd");
+    assert!(m.next_field().await.unwrap().is_none());
+}
+
+#[cfg(feature = "tokio-time")]
+#[tokio::test]
+async fn test_multipart_constraint_read_timeout() {
+    use std::time::Duration;
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcdefghij\r\n--X-BOUNDARY--\r\n";
+
+    // Slow overall, but never idle for long.
+    let slow_stream = futures_util::StreamExt::then(str_stream(data), |chunk| async move {
+        tokio::time::sleep(Duration::from_millis(2)).await;
+        chunk
+    });
+    let constraints = Constraints::new().read_timeout(Duration::from_millis(50));
+    let mut m = Multipart::with_constraints(slow_stream, "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcdefghij");
+
+    let stalled_stream = futures_util::StreamExt::chain(str_stream(&data[..60]), stream::pending());
+    let constraints = Constraints::new().read_timeout(Duration::from_millis(50));
+    let mut m = Multipart::with_constraints(stalled_stream, "X-BOUNDARY", constraints);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(matches!(
+        field.text().await,
+        Err(multer::Error::ReadTimeout { timeout }) if timeout == Duration::from_millis(50)
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_sniff_content_type() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"doc\"; filename=\"a.pdf\"\r\nContent-Type: application/pdf\r\n\r\n%PDF-1.7\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"note\"; filename=\"a.txt\"\r\nContent-Type: text/plain\r\n\r\nhello\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"a.png\"\r\nContent-Type: image/png\r\n\r\nGIF89a\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().sniff_content_type(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "%PDF-1.7");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "hello");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    let err = field.bytes().await.unwrap_err();
+    assert_eq!(
+        err,
+        multer::Error::ContentTypeMismatch {
+            field_name: Some("avatar".to_owned()),
+            declared: "image/png".to_owned(),
+            detected: Some("image/gif".to_owned()),
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_multipart_preamble_epilogue_size_limit() {
+    let data = "This is the preamble.\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n1\r\n--X-BOUNDARY--\r\nThis is the epilogue.";
+
+    let size_limit = SizeLimit::new().preamble(21).epilogue(21);
+    let mut m = Multipart::with_constraints(
+        str_stream(data),
+        "X-BOUNDARY",
+        Constraints::new().size_limit(size_limit),
+    );
+    while m.next_field().await.unwrap().is_some() {}
+    assert_eq!(m.epilogue().await.unwrap().unwrap(), "This is the epilogue.");
+
+    let size_limit = SizeLimit::new().preamble(20);
+    let mut m = Multipart::with_constraints(
+        str_stream(data),
+        "X-BOUNDARY",
+        Constraints::new().size_limit(size_limit),
+    );
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::PreambleSizeExceeded { limit: 20 })
+    ));
+
+    // Rejected before the stream ends when there is no boundary at all.
+    let garbage = futures_util::StreamExt::chain(str_stream(&data[..21]), stream::pending());
+    let size_limit = SizeLimit::new().preamble(5);
+    let mut m = Multipart::with_constraints(garbage, "X-BOUNDARY", Constraints::new().size_limit(size_limit));
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::PreambleSizeExceeded { limit: 5 })
+    ));
+
+    let size_limit = SizeLimit::new().epilogue(20);
+    let mut m = Multipart::with_constraints(
+        str_stream(data),
+        "X-BOUNDARY",
+        Constraints::new().size_limit(size_limit),
+    );
+    while m.next_field().await.unwrap().is_some() {}
+    let err = m.epilogue().await.unwrap_err();
+    assert!(matches!(err, multer::Error::EpilogueSizeExceeded { limit: 20, .. }));
+    let violation = err.violation().unwrap();
+    assert_eq!(violation.constraint(), multer::Constraint::EpilogueSize);
+    assert_eq!(violation.limit(), Some(20));
+    assert!(violation.observed().unwrap() > 20);
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_drain_on_violation() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcdefghij\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nklmnopqrst\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new()
+        .size_limit(SizeLimit::new().per_field(5))
+        .drain_on_violation(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert!(m.violation().is_none());
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(matches!(
+        field.bytes().await,
+        Err(multer::Error::FieldSizeExceeded { limit: 5, .. })
+    ));
+
+    assert!(m.next_field().await.unwrap().is_none());
+    assert!(m.next_field().await.unwrap().is_none());
+    assert_eq!(
+        m.violation(),
+        Some(multer::Error::FieldSizeExceeded {
+            limit: 5,
+            field_name: Some("a".to_owned())
+        })
+    );
+
+    // Malformed bodies are not drained.
+    let constraints = Constraints::new().drain_on_violation(true);
+    let mut m = Multipart::with_constraints(str_stream("--X-BOUNDARY\r\nabc"), "X-BOUNDARY", constraints);
+    assert!(m.next_field().await.is_err());
+    assert!(m.violation().is_none());
+}
+
+#[tokio::test]
+async fn test_multipart_constraint_violation() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabc\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\ndef\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().size_limit(SizeLimit::new().max_fields(1));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abc");
+
+    let violation = m.next_field().await.unwrap_err().violation().unwrap();
+    assert_eq!(violation.constraint(), multer::Constraint::FieldCount);
+    assert_eq!(violation.field_name(), None);
+    assert_eq!(violation.limit(), Some(1));
+    assert_eq!(violation.observed(), Some(2));
+
+    let constraints = Constraints::new().allowed_fields(vec!["a"]);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abc");
+
+    let violation = m.next_field().await.unwrap_err().violation().unwrap();
+    assert_eq!(violation.constraint(), multer::Constraint::AllowedFields);
+    assert_eq!(violation.field_name(), Some("b"));
+    assert_eq!(violation.limit(), None);
+
+    // Malformed bodies are not violations.
+    let mut m = Multipart::new(str_stream("--X-BOUNDARY\r\nabc"), "X-BOUNDARY");
+    assert!(m.next_field().await.unwrap_err().violation().is_none());
+}
+
+#[tokio::test]
+async fn test_multipart_file_fields() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"a.png\"\r\nContent-Type: image/png\r\n\r\nabc\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"b.png\"\r\n\r\ndef\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().file_fields(vec!["avatar"]);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abc");
+
+    let err = m.next_field().await.unwrap_err();
+    assert_eq!(
+        err.violation().map(|violation| violation.constraint()),
+        Some(multer::Constraint::FileFields)
+    );
+    assert!(matches!(err, multer::Error::NotAFile { field_name } if field_name == "avatar"));
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"avatar\"\r\nContent-Type: image/png\r\n\r\nabc\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().file_fields(vec!["avatar"]);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert!(matches!(m.next_field().await, Err(multer::Error::NotAFile { .. })));
+}
+
+#[tokio::test]
+async fn test_multipart_unknown_fields_ignored() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"utm_source\"\r\n\r\nabc\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\ndef\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"junk\"\r\n\r\n\r\n--X-BOUNDARY--\r\n";
+
+    for policy in [UnknownFieldPolicy::Ignore, UnknownFieldPolicy::SkipAndWarn] {
+        let constraints = Constraints::new().allowed_fields(vec!["a"]).unknown_fields(policy);
+        let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+        let field = m.next_field().await.unwrap().unwrap();
+        assert_eq!(field.name(), Some("a"));
+        assert_eq!(field.index(), 1);
+        assert_eq!(field.text().await.unwrap(), "def");
+
+        assert!(m.next_field().await.unwrap().is_none());
+    }
+
+    let constraints = Constraints::new()
+        .allowed_fields(vec!["a"])
+        .unknown_fields(UnknownFieldPolicy::Reject);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert!(matches!(m.next_field().await, Err(multer::Error::UnknownField { .. })));
+}
+
+#[tokio::test]
+async fn test_multipart_memory_budget() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcdefghij\r\n--X-BOUNDARY--\r\n";
+    let budget = multer::MemoryBudget::new(2 * data.len() as u64);
+
+    let mut m1 = Multipart::with_constraints(
+        str_stream(data),
+        "X-BOUNDARY",
+        Constraints::new().memory_budget(budget.clone()),
+    );
+    let mut m2 = Multipart::with_constraints(
+        str_stream(data),
+        "X-BOUNDARY",
+        Constraints::new().memory_budget(budget.clone()),
+    );
+    let mut m3 = Multipart::with_constraints(
+        str_stream(data),
+        "X-BOUNDARY",
+        Constraints::new().memory_budget(budget.clone()),
+    );
+
+    let field1 = m1.next_field().await.unwrap().unwrap();
+    let field2 = m2.next_field().await.unwrap().unwrap();
+    assert!(budget.used() > 0);
+    let err = m3.next_field().await.unwrap_err();
+    assert!(matches!(err, multer::Error::MemoryBudgetExceeded { .. }));
+    let violation = err.violation().unwrap();
+    assert_eq!(violation.constraint(), multer::Constraint::MemoryBudget);
+    assert_eq!(violation.limit(), Some(budget.limit()));
+    assert!(violation.observed().unwrap() > budget.limit());
+
+    // The violation of the budget is drained like any other.
+    let mut m4 = Multipart::with_constraints(
+        str_stream(data),
+        "X-BOUNDARY",
+        Constraints::new()
+            .memory_budget(budget.clone())
+            .drain_on_violation(true),
+    );
+    assert!(m4.next_field().await.is_err());
+    assert!(m4.next_field().await.unwrap().is_none());
+    assert!(matches!(
+        m4.violation(),
+        Some(multer::Error::MemoryBudgetExceeded { .. })
+    ));
+
+    assert_eq!(field1.text().await.unwrap(), "abcdefghij");
+    assert_eq!(field2.text().await.unwrap(), "abcdefghij");
+    assert!(m1.next_field().await.unwrap().is_none());
+
+    drop(m1);
+    drop(m2);
+    drop(m3);
+    assert_eq!(budget.used(), 0);
+}
+
+#[tokio::test]
+async fn test_multipart_truncate_fields() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"log\"\r\n\r\nabcdefghij\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nklm\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"log\"\r\n\r\nnop\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new()
+        .size_limit(SizeLimit::new().per_field(5))
+        .truncate_fields(vec!["log"]);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let mut field = m.next_field().await.unwrap().unwrap();
+    let mut buffer = Vec::new();
+    while let Some(chunk) = field.chunk().await.unwrap() {
+        buffer.extend_from_slice(&chunk);
+    }
+    assert_eq!(buffer, b"abcde");
+    assert!(field.is_truncated());
+    drop(field);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(!field.is_truncated());
+    assert_eq!(field.text().await.unwrap(), "klm");
+
+    let mut field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.chunk().await.unwrap().unwrap(), "nop");
+    assert!(!field.is_truncated());
+    drop(field);
+
+    assert!(m.next_field().await.unwrap().is_none());
+
+    // An unread truncated field is skipped as well.
+    let constraints = Constraints::new()
+        .size_limit(SizeLimit::new().per_field(5))
+        .truncate_fields(vec!["log"]);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    drop(m.next_field().await.unwrap().unwrap());
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "klm");
+}
+
+#[cfg(feature = "tokio-time")]
+#[tokio::test]
+async fn test_multipart_constraint_rate_limit() {
+    use std::time::{Duration, Instant};
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcdefghij\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"\r\n\r\nklmnopqrstuvwxyz\r\n--X-BOUNDARY--\r\n";
+
+    let start = Instant::now();
+    let constraints = Constraints::new().rate_limit(2000, 50);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    while let Some(field) = m.next_field().await.unwrap() {
+        field.bytes().await.unwrap();
+    }
+    // The body is over the burst by more than 100 bytes, at 2 bytes per
+    // millisecond.
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    let start = Instant::now();
+    // Yields to the runtime before each chunk, so that the body isn't buffered
+    // ahead of the field.
+    let stream = futures_util::StreamExt::then(str_stream(data), |chunk| async move {
+        tokio::task::yield_now().await;
+        chunk
+    });
+    let constraints = Constraints::new().rate_limit_for("file", 100, 1);
+    let mut m = Multipart::with_constraints(stream, "X-BOUNDARY", constraints);
+    assert_eq!(
+        m.next_field().await.unwrap().unwrap().text().await.unwrap(),
+        "abcdefghij"
+    );
+    assert_eq!(
+        m.next_field().await.unwrap().unwrap().text().await.unwrap(),
+        "klmnopqrstuvwxyz"
+    );
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn test_multipart_reject_empty_files() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"\"\r\nContent-Type: application/octet-stream\r\n\r\n\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().reject_empty_files(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    let err = field.bytes().await.unwrap_err();
+    assert!(matches!(err, multer::Error::EmptyFile { field_name: Some(ref name) } if name == "file"));
+    assert_eq!(
+        err.violation().map(|violation| violation.constraint()),
+        Some(multer::Constraint::EmptyFiles)
+    );
+
+    // Skipping the field doesn't bypass the check.
+    let constraints = Constraints::new().reject_empty_files(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    drop(m.next_field().await.unwrap().unwrap());
+    drop(m.next_field().await.unwrap().unwrap());
+    assert!(matches!(m.next_field().await, Err(multer::Error::EmptyFile { .. })));
+
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    drop(m.next_field().await.unwrap().unwrap());
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(field.bytes().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_multipart_file_content_type() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabc\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"; filename=\"b.bin\"\r\nContent-Type: application/octet-stream\r\n\r\ndef\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"c\"; filename=\"c.txt\"\r\n\r\nghi\r\n--X-BOUNDARY--\r\n";
+
+    let constraints = Constraints::new().file_content_type(FileContentTypePolicy::Required);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "abc");
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "def");
+    let err = m.next_field().await.unwrap_err();
+    assert!(matches!(err, multer::Error::MissingFileContentType { field_name: Some(ref name) } if name == "c"));
+    assert_eq!(
+        err.violation().map(|violation| violation.constraint()),
+        Some(multer::Constraint::FileContentType)
+    );
+
+    let constraints = Constraints::new().file_content_type(FileContentTypePolicy::Specific);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "abc");
+    assert!(matches!(
+        m.next_field().await,
+        Err(multer::Error::MissingFileContentType { field_name: Some(ref name) }) if name == "b"
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_writer_round_trip() {
+    let writer = multer::MultipartWriter::with_boundary("X-BOUNDARY")
+        .text("a", "abc")
+        .text("empty", "")
+        .part(
+            multer::Part::bytes("file", Bytes::from_static(b"\x00\x01\r\n--X"))
+                .file_name("x \"quoted\".bin")
+                .content_type(mime::APPLICATION_OCTET_STREAM),
+        );
+    assert_eq!(writer.content_type(), "multipart/form-data; boundary=X-BOUNDARY");
+
+    let chunks: Vec<Bytes> = futures_util::TryStreamExt::try_collect(writer.into_stream())
+        .await
+        .unwrap();
+    let body = String::from_utf8(chunks.concat()).unwrap();
+    assert_eq!(
+        body,
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabc\r\n\
+         --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"empty\"\r\n\r\n\r\n\
+         --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"file\"; filename=\"x \\\"quoted\\\".bin\"\r\n\
+         Content-Type: application/octet-stream\r\n\r\n\x00\x01\r\n--X\r\n--X-BOUNDARY--\r\n"
+    );
+
+    let mut m = Multipart::new(stream::iter(vec![Ok::<_, multer::Error>(body)]), "X-BOUNDARY");
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "abc");
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.file_name(), Some("x \"quoted\".bin"));
+    assert_eq!(field.bytes().await.unwrap(), &b"\x00\x01\r\n--X"[..]);
+    assert!(m.next_field().await.unwrap().is_none());
+
+    // An empty body.
+    let writer = multer::MultipartWriter::new();
+    let boundary = writer.boundary().to_owned();
+    let mut m = Multipart::new(writer.into_stream(), boundary);
+    assert!(m.next_field().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_multipart_writer_stream_parts() {
+    let chunks = vec![Bytes::from("abc"), Bytes::new(), Bytes::from("def")];
+    let writer = multer::MultipartWriter::new()
+        .part(multer::Part::stream(
+            "a",
+            stream::iter(chunks.into_iter().map(Ok::<_, std::convert::Infallible>)),
+        ))
+        .text("b", "ghi");
+    let boundary = writer.boundary().to_owned();
+
+    let mut m = Multipart::new(writer.into_stream(), boundary);
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "abcdef");
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "ghi");
+    assert!(m.next_field().await.unwrap().is_none());
+
+    let failing = stream::iter(vec![Ok(Bytes::from("abc")), Err(std::io::Error::other("disk error"))]);
+    let mut body = multer::MultipartWriter::new()
+        .part(multer::Part::stream("a", failing))
+        .into_stream();
+    let mut results = Vec::new();
+    while let Some(result) = futures_util::StreamExt::next(&mut body).await {
+        results.push(result);
+    }
+    assert_eq!(results.len(), 3);
+    assert!(matches!(results[2], Err(multer::Error::StreamReadFailed(_))));
+}
+
+#[cfg(feature = "tokio-io")]
+#[tokio::test]
+async fn test_multipart_writer_reader_part() {
+    let writer = multer::MultipartWriter::new().part(multer::Part::reader("a", &b"abcdef"[..]).file_name("a.txt"));
+    let boundary = writer.boundary().to_owned();
+
+    let mut m = Multipart::new(writer.into_stream(), boundary);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.file_name(), Some("a.txt"));
+    assert_eq!(field.text().await.unwrap(), "abcdef");
+    assert!(m.next_field().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_multipart_writer_nested() {
+    let files = multer::MultipartWriter::mixed()
+        .part(multer::Part::bytes("", "abcd").file_name("a.txt"))
+        .part(
+            multer::Part::bytes("", "efgh")
+                .file_name("b.txt")
+                .content_type(mime::TEXT_PLAIN),
+        );
+    let writer = multer::MultipartWriter::new()
+        .text("a", "abc")
+        .part(multer::Part::nested("files", files))
+        .text("b", "def");
+    let boundary = writer.boundary().to_owned();
+
+    let mut m = Multipart::new(writer.into_stream(), boundary);
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "abc");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("files"));
+    assert_eq!(field.content_type().map(|mime| mime.subtype().as_str()), Some("mixed"));
+
+    let mut nested = field.into_nested_multipart().unwrap();
+    let file = nested.next_field().await.unwrap().unwrap();
+    assert_eq!(file.name(), None);
+    assert_eq!(file.file_name(), Some("a.txt"));
+    assert_eq!(file.text().await.unwrap(), "abcd");
+    let file = nested.next_field().await.unwrap().unwrap();
+    assert_eq!(file.file_name(), Some("b.txt"));
+    assert_eq!(file.content_type(), Some(&mime::TEXT_PLAIN));
+    assert_eq!(file.text().await.unwrap(), "efgh");
+    assert!(nested.next_field().await.unwrap().is_none());
+    drop(nested);
+
+    assert_eq!(m.next_field().await.unwrap().unwrap().text().await.unwrap(), "def");
+    assert!(m.next_field().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_multipart_writer_custom_headers() {
+    use http::header::{HeaderName, HeaderValue};
+
+    let writer = multer::MultipartWriter::with_boundary("X-BOUNDARY")
+        .part(
+            multer::Part::bytes("a", "abc")
+                .content_type(mime::TEXT_PLAIN)
+                .header(
+                    HeaderName::from_static("content-id"),
+                    HeaderValue::from_static("<a@example.com>"),
+                )
+                .header(HeaderName::from_static("x-checksum"), HeaderValue::from_static("1234")),
+        )
+        .part(
+            multer::Part::bytes("ignored", "def")
+                .content_type(mime::TEXT_PLAIN)
+                .header(
+                    http::header::CONTENT_DISPOSITION,
+                    HeaderValue::from_static("form-data; name=\"b\"; filename=\"b.txt\""),
+                )
+                .header(http::header::CONTENT_TYPE, HeaderValue::from_static("text/csv")),
+        );
+
+    let chunks: Vec<Bytes> = futures_util::TryStreamExt::try_collect(writer.into_stream())
+        .await
+        .unwrap();
+    let body = String::from_utf8(chunks.concat()).unwrap();
+    assert_eq!(
+        body,
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\nContent-Type: text/plain\r\n\
+         content-id: <a@example.com>\r\nx-checksum: 1234\r\n\r\nabc\r\n\
+         --X-BOUNDARY\r\ncontent-disposition: form-data; name=\"b\"; filename=\"b.txt\"\r\ncontent-type: text/csv\r\n\r\n\
+         def\r\n--X-BOUNDARY--\r\n"
+    );
+
+    let mut m = Multipart::with_bytes(body, "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.headers()["x-checksum"], "1234");
+    drop(field);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("b"));
+    assert_eq!(field.file_name(), Some("b.txt"));
+    assert_eq!(field.content_type(), Some(&mime::TEXT_CSV));
+
+    assert!(HeaderValue::from_str("abc\r\nx-injected: 1").is_err());
+}
+
+#[tokio::test]
+async fn test_multipart_writer_base64() {
+    use http::header::{HeaderName, HeaderValue};
+
+    let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+    let chunks = data
+        .chunks(7)
+        .map(|chunk| Ok::<_, std::convert::Infallible>(Bytes::copy_from_slice(chunk)))
+        .collect::<Vec<_>>();
+
+    let writer = multer::MultipartWriter::with_boundary("X-BOUNDARY")
+        .part(
+            multer::Part::stream("file", futures_util::stream::iter(chunks))
+                .file_name("data.bin")
+                .content_type(mime::APPLICATION_OCTET_STREAM)
+                .base64(),
+        )
+        .part(
+            multer::Part::text("labelled", "cd")
+                .header(
+                    HeaderName::from_static("content-transfer-encoding"),
+                    HeaderValue::from_static("binary"),
+                )
+                .base64(),
+        )
+        .part(multer::Part::text("short", "ab").base64());
+
+    let chunks: Vec<Bytes> = futures_util::TryStreamExt::try_collect(writer.into_stream())
+        .await
+        .unwrap();
+    let body = chunks.concat();
+    assert!(body.split(|b| *b == b'\n').all(|line| line.len() <= 77));
+    assert!(String::from_utf8_lossy(&body).contains("Content-Transfer-Encoding: base64\r\n\r\nYWI=\r\n--X-BOUNDARY--"));
+    assert!(!String::from_utf8_lossy(&body).contains("binary"));
+
+    let mut m = Multipart::with_bytes(body, "X-BOUNDARY");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.bytes().await.unwrap(), data);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "cd");
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "ab");
+}
+
+#[tokio::test]
+async fn test_multipart_writer_content_length() {
+    fn chunks(data: &'static str) -> impl Stream<Item = Result<&'static str, std::convert::Infallible>> + Send {
+        stream::iter(data.split_inclusive(' ').map(Ok))
+    }
+
+    async fn collect(writer: multer::MultipartWriter) -> multer::Result<Vec<u8>> {
+        let chunks: Vec<Bytes> = futures_util::TryStreamExt::try_collect(writer.into_stream()).await?;
+        Ok(chunks.concat())
+    }
+
+    let empty = multer::MultipartWriter::with_boundary("X-BOUNDARY");
+    assert_eq!(empty.content_length(), Some(collect(empty).await.unwrap().len() as u64));
+
+    let writer = || {
+        multer::MultipartWriter::with_boundary("X-BOUNDARY")
+            .text("a", "abcd")
+            .part(multer::Part::stream("b", chunks("some streamed data")).length(18))
+            .part(multer::Part::bytes("c", vec![0; 100]).file_name("c.bin").base64())
+            .part(multer::Part::nested(
+                "d",
+                multer::MultipartWriter::mixed().part(multer::Part::bytes("", "efgh").file_name("d.txt")),
+            ))
+    };
+    let content_length = writer().content_length().unwrap();
+    assert_eq!(content_length, collect(writer()).await.unwrap().len() as u64);
+
+    let unknown = multer::MultipartWriter::new().part(multer::Part::stream("b", chunks("a b")));
+    assert_eq!(unknown.content_length(), None);
+
+    let short = multer::MultipartWriter::new().part(multer::Part::stream("b", chunks("a b")).length(4));
+    assert!(matches!(collect(short).await, Err(multer::Error::StreamReadFailed(_))));
+    let long = multer::MultipartWriter::new().part(multer::Part::stream("b", chunks("a b")).length(2));
+    assert!(matches!(collect(long).await, Err(multer::Error::StreamReadFailed(_))));
+}
+
+#[tokio::test]
+async fn test_multipart_writer_non_ascii_file_name() {
+    let writer =
+        multer::MultipartWriter::new().part(multer::Part::bytes("file", "abcd").file_name("résumé \"final\".pdf"));
+
+    let boundary = writer.boundary().to_owned();
+    let mut m = Multipart::new(writer.into_stream(), boundary);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.file_name(), Some("résumé \"final\".pdf"));
+}
+
+#[tokio::test]
+async fn test_multipart_writer_progress() {
+    use std::sync::{Arc, Mutex};
+
+    let reports = Arc::new(Mutex::new(Vec::new()));
+    let writer = multer::MultipartWriter::with_boundary("X-BOUNDARY")
+        .text("a", "abcd")
+        .part(
+            multer::Part::stream(
+                "b",
+                stream::iter(vec![Ok::<_, std::convert::Infallible>("ef"), Ok("gh")]),
+            )
+            .length(4),
+        )
+        .on_progress({
+            let reports = reports.clone();
+            move |progress| {
+                reports.lock().unwrap().push((
+                    progress.part_name().map(str::to_owned),
+                    progress.part_index(),
+                    progress.part_bytes(),
+                    progress.total_bytes(),
+                    progress.total_length(),
+                ))
+            }
+        });
+    let total_length = writer.content_length();
+
+    let chunks: Vec<Bytes> = futures_util::TryStreamExt::try_collect(writer.into_stream())
+        .await
+        .unwrap();
+    let lens: Vec<u64> = chunks.iter().map(|chunk| chunk.len() as u64).collect();
+
+    let reports = reports.lock().unwrap();
+    let name = |name: &str| Some(name.to_owned());
+    assert_eq!(
+        *reports,
+        [
+            (name("a"), Some(0), 0, lens[0], total_length),
+            (name("a"), Some(0), 4, lens[..2].iter().sum(), total_length),
+            (name("b"), Some(1), 0, lens[..3].iter().sum(), total_length),
+            (name("b"), Some(1), 2, lens[..4].iter().sum(), total_length),
+            (name("b"), Some(1), 4, lens[..5].iter().sum(), total_length),
+            (None, None, 0, lens.iter().sum(), total_length),
+        ]
+    );
+    assert_eq!(total_length, Some(lens.iter().sum()));
+}
+
+#[tokio::test]
+async fn test_multipart_writer_try_clone() {
+    async fn collect(writer: multer::MultipartWriter) -> Vec<u8> {
+        let chunks: Vec<Bytes> = futures_util::TryStreamExt::try_collect(writer.into_stream())
+            .await
+            .unwrap();
+        chunks.concat()
+    }
+
+    let writer = multer::MultipartWriter::new()
+        .text("a", "abcd")
+        .part(
+            multer::Part::reopenable("b", || {
+                stream::iter(vec![Ok::<_, std::convert::Infallible>("ef"), Ok("gh")])
+            })
+            .file_name("b.txt")
+            .length(4),
+        )
+        .part(multer::Part::nested(
+            "c",
+            multer::MultipartWriter::mixed().part(multer::Part::bytes("", "ijkl").file_name("c.txt")),
+        ));
+
+    let retry = writer.try_clone().unwrap();
+    let content_length = retry.content_length();
+    let body = collect(writer).await;
+    assert_eq!(body, collect(retry).await);
+    assert_eq!(content_length, Some(body.len() as u64));
+
+    let once = multer::MultipartWriter::new().part(multer::Part::nested(
+        "c",
+        multer::MultipartWriter::mixed().part(multer::Part::stream("", str_stream("ijkl"))),
+    ));
+    assert!(once.try_clone().is_none());
+}
+
+#[test]
+fn test_multipart_blocking() {
+    use std::io::Read;
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a-text-file.txt\"\r\nContent-Type: text/plain\r\n\r\nHello world\nHello\r\nWorld\rAgain\r\n--X-BOUNDARY--\r\n";
+    let mut m = multer::blocking::Multipart::new(data.as_bytes(), "X-BOUNDARY");
+
+    let field = m.next_field().unwrap().unwrap();
+    assert_eq!(field.name(), Some("my_text_field"));
+    assert_eq!(field.text().unwrap(), "abcd");
+
+    let mut field = m.next_field().unwrap().unwrap();
+    assert_eq!(field.file_name(), Some("a-text-file.txt"));
+    assert_eq!(field.content_type(), Some(&mime::TEXT_PLAIN));
+
+    let mut buf = [0; 5];
+    field.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"Hello");
+    let mut rest = String::new();
+    field.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, " world\nHello\r\nWorld\rAgain");
+    drop(field);
+
+    assert!(m.next_field().unwrap().is_none());
+
+    let constraints = Constraints::new().allowed_fields(vec!["my_text_field"]);
+    let fields: Vec<_> = multer::blocking::Multipart::with_constraints(data.as_bytes(), "X-BOUNDARY", constraints)
+        .map(|field| field.and_then(|field| field.text()))
+        .collect();
+    assert_eq!(fields.len(), 2);
+    assert_eq!(fields[0].as_deref().unwrap(), "abcd");
+    assert!(matches!(fields[1], Err(multer::Error::UnknownField { .. })));
+}
+
+#[cfg(feature = "serde")]
+#[tokio::test]
+async fn test_multipart_deserialize() {
+    use std::collections::HashMap;
+
+    #[derive(Debug, serde::Deserialize)]
+    #[serde(rename_all = "snake_case")]
+    enum Visibility {
+        Public,
+        Private,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Upload {
+        title: String,
+        count: u32,
+        ratio: f64,
+        tags: Vec<String>,
+        visibility: Visibility,
+        draft: Option<bool>,
+        avatar: multer::FormFile,
+        attachments: Vec<multer::FormFile>,
+    }
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nHoliday\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"count\"\r\n\r\n3\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"ratio\"\r\n\r\n0.5\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tags\"\r\n\r\nbeach\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"visibility\"\r\n\r\nprivate\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"draft\"\r\n\r\ntrue\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"a.png\"\r\nContent-Type: image/png\r\n\r\n\x01\x02\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"attachments\"; filename=\"b.txt\"\r\n\r\nbbbb\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"attachments\"; filename=\"c.txt\"\r\n\r\ncccc\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"unknown\"\r\n\r\nignored\r\n\
+        --X-BOUNDARY--\r\n";
+
+    let upload: Upload = Multipart::with_bytes(data, "X-BOUNDARY").deserialize().await.unwrap();
+    assert_eq!(upload.title, "Holiday");
+    assert_eq!(upload.count, 3);
+    assert_eq!(upload.ratio, 0.5);
+    assert_eq!(upload.tags, ["beach"]);
+    assert!(matches!(upload.visibility, Visibility::Private));
+    assert_eq!(upload.draft, Some(true));
+    assert_eq!(upload.avatar.file_name(), Some("a.png"));
+    assert_eq!(upload.avatar.content_type(), Some(&mime::IMAGE_PNG));
+    assert_eq!(upload.avatar.data(), &b"\x01\x02"[..]);
+    assert_eq!(upload.attachments.len(), 2);
+    assert_eq!(upload.attachments[1].file_name(), Some("c.txt"));
+    assert_eq!(upload.attachments[1].data(), "cccc");
+
+    let map: HashMap<String, String> = Multipart::with_bytes(
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n1\r\n--X-BOUNDARY--\r\n",
+        "X-BOUNDARY",
+    )
+    .deserialize()
+    .await
+    .unwrap();
+    assert_eq!(map["a"], "1");
+
+    let err = Multipart::with_bytes(data.replace("\r\n3\r\n", "\r\nthree\r\n"), "X-BOUNDARY")
+        .deserialize::<Upload>()
+        .await
+        .unwrap_err();
+    assert!(matches!(err, multer::Error::DecodeForm { field_name: Some(ref name), .. } if name == "count"));
+
+    let err = Multipart::with_bytes("--X-BOUNDARY--\r\n", "X-BOUNDARY")
+        .deserialize::<Upload>()
+        .await
+        .unwrap_err();
+    assert!(matches!(err, multer::Error::DecodeForm { field_name: None, .. }));
+}
+
+#[cfg(all(feature = "serde", feature = "tokio-fs"))]
+#[tokio::test]
+async fn test_multipart_deserialize_spooled() {
+    use multer::{FormFile, SpooledField, SpooledFormFile};
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Upload {
+        title: String,
+        small: FormFile,
+        large: SpooledFormFile,
+        files: Vec<SpooledFormFile>,
+    }
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nHoliday photos\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"small\"; filename=\"a.txt\"\r\n\r\nab\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"large\"; filename=\"b.txt\"\r\nContent-Type: text/plain\r\n\r\nbbbbbbbb\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"files\"; filename=\"c.txt\"\r\n\r\nc\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"files\"; filename=\"d.txt\"\r\n\r\ndddddddd\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"unknown\"; filename=\"e.txt\"\r\n\r\neeeeeeee\r\n\
+        --X-BOUNDARY--\r\n";
+
+    let upload: Upload = Multipart::with_bytes(data, "X-BOUNDARY")
+        .deserialize_spooled(4)
+        .await
+        .unwrap();
+    assert_eq!(upload.title, "Holiday photos");
+    assert_eq!(upload.small.data(), "ab");
+    assert_eq!(upload.large.file_name(), Some("b.txt"));
+    assert_eq!(upload.large.content_type(), Some(&mime::TEXT_PLAIN));
+    assert_eq!(upload.files.len(), 2);
+    assert_eq!(upload.files[0].data(), &SpooledField::Memory("c".into()));
+
+    let spooled = [
+        (upload.large, "bbbbbbbb"),
+        (upload.files.into_iter().nth(1).unwrap(), "dddddddd"),
+    ];
+    for (file, expected) in spooled {
+        let path = match file.into_data() {
+            SpooledField::File(path) => path,
+            data => panic!("expected a temporary file, got {:?}", data),
+        };
+        assert_eq!(tokio::fs::read_to_string(&path).await.unwrap(), expected);
+        tokio::fs::remove_file(path).await.unwrap();
+    }
+
+    let upload: Upload = Multipart::with_bytes(data, "X-BOUNDARY").deserialize().await.unwrap();
+    assert_eq!(upload.large.data(), &SpooledField::Memory("bbbbbbbb".into()));
+
+    let err = Multipart::with_bytes(data, "X-BOUNDARY")
+        .deserialize_spooled::<Upload>(1)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, multer::Error::DecodeForm { field_name: Some(ref name), .. } if name == "small"));
+}
+
+#[cfg(feature = "derive")]
+#[tokio::test]
+async fn test_multipart_from_multipart() {
+    use multer::{FormFile, FromMultipart};
+
+    #[derive(Debug, FromMultipart)]
+    struct Upload {
+        title: String,
+        count: u32,
+        ratio: f64,
+        #[multer(rename = "tag")]
+        tags: Vec<String>,
+        draft: Option<bool>,
+        note: Option<String>,
+        avatar: FormFile,
+        attachments: Vec<FormFile>,
+    }
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"title\"\r\n\r\nHoliday\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"count\"\r\n\r\n3\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"ratio\"\r\n\r\n0.5\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tag\"\r\n\r\nbeach\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tag\"\r\n\r\nsun\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"draft\"\r\n\r\ntrue\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"avatar\"; filename=\"a.png\"\r\nContent-Type: image/png\r\n\r\n\x01\x02\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"attachments\"; filename=\"b.txt\"\r\n\r\nb\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"attachments\"; filename=\"c.txt\"\r\n\r\nc\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"unknown\"\r\n\r\nignored\r\n\
+        --X-BOUNDARY--\r\n";
+
+    let mut multipart = Multipart::with_bytes(data, "X-BOUNDARY");
+    let upload = Upload::from_multipart(&mut multipart).await.unwrap();
+    assert_eq!(upload.title, "Holiday");
+    assert_eq!(upload.count, 3);
+    assert_eq!(upload.ratio, 0.5);
+    assert_eq!(upload.tags, ["beach", "sun"]);
+    assert_eq!(upload.draft, Some(true));
+    assert_eq!(upload.note, None);
+    assert_eq!(upload.avatar.file_name(), Some("a.png"));
+    assert_eq!(upload.avatar.content_type(), Some(&mime::IMAGE_PNG));
+    assert_eq!(upload.avatar.data(), &b"\x01\x02"[..]);
+    assert_eq!(upload.attachments.len(), 2);
+    assert_eq!(upload.attachments[1].file_name(), Some("c.txt"));
+    assert_eq!(upload.attachments[1].data(), "c");
+    assert!(multipart.next_field().await.unwrap().is_none());
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"count\"\r\n\r\nthree\r\n--X-BOUNDARY--\r\n";
+    let err = Upload::from_multipart(&mut Multipart::with_bytes(data, "X-BOUNDARY"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, multer::Error::DecodeForm { field_name: Some(ref name), .. } if name == "count"));
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"count\"\r\n\r\n3\r\n--X-BOUNDARY--\r\n";
+    let err = Upload::from_multipart(&mut Multipart::with_bytes(data, "X-BOUNDARY"))
+        .await
+        .unwrap_err();
+    assert!(matches!(err, multer::Error::DecodeForm { field_name: Some(ref name), .. } if name == "title"));
+}
+
+#[cfg(all(feature = "derive", feature = "tokio-fs"))]
+#[tokio::test]
+async fn test_multipart_from_multipart_spooled() {
+    use multer::{FromMultipart, SpooledField, SpooledFormFile};
+
+    #[derive(Debug, FromMultipart)]
+    struct Upload {
+        #[multer(spool = 4)]
+        small: SpooledFormFile,
+        #[multer(spool = 4)]
+        large: SpooledFormFile,
+    }
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"small\"; filename=\"a.txt\"\r\n\r\nab\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"large\"; filename=\"b.txt\"\r\nContent-Type: text/plain\r\n\r\nbbbbbbbb\r\n\
+        --X-BOUNDARY--\r\n";
+
+    let upload = Upload::from_multipart(&mut Multipart::with_bytes(data, "X-BOUNDARY"))
+        .await
+        .unwrap();
+    assert_eq!(upload.small.data(), &SpooledField::Memory("ab".into()));
+    assert_eq!(upload.large.file_name(), Some("b.txt"));
+    assert_eq!(upload.large.content_type(), Some(&mime::TEXT_PLAIN));
+
+    let path = match upload.large.into_data() {
+        SpooledField::File(path) => path,
+        data => panic!("expected a temporary file, got {:?}", data),
+    };
+    assert_eq!(tokio::fs::read_to_string(&path).await.unwrap(), "bbbbbbbb");
+    tokio::fs::remove_file(path).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_multipart_collect_form() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n1\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\n2\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\n3\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"files\"; filename=\"a.txt\"\r\nContent-Type: text/plain\r\n\r\nabcd\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"files\"; filename=\"b.txt\"\r\n\r\nefgh\r\n\
+        --X-BOUNDARY--\r\n";
+
+    let form = Multipart::with_bytes(data, "X-BOUNDARY").collect_form().await.unwrap();
+    assert_eq!(form.texts.len(), 2);
+    assert_eq!(form.texts["a"], "2");
+    assert_eq!(form.texts["b"], "3");
+    assert_eq!(form.files.len(), 2);
+    assert_eq!(form.files[0].name.as_deref(), Some("files"));
+    assert_eq!(form.files[0].file_name, "a.txt");
+    assert_eq!(form.files[0].content_type, Some(mime::TEXT_PLAIN));
+    assert_eq!(form.files[0].data, "abcd");
+    assert_eq!(form.files[1].content_type, None);
+    assert_eq!(form.file("files").unwrap().file_name, "a.txt");
+
+    let constraints = Constraints::new().size_limit(SizeLimit::new().whole_stream(100));
+    let err = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints)
+        .collect_form()
+        .await
+        .unwrap_err();
+    assert!(matches!(err, multer::Error::StreamSizeExceeded { limit: 100 }));
+}
+
+#[cfg(feature = "tokio-io")]
+#[tokio::test]
+async fn test_multipart_field_into_async_read() {
+    use tokio::io::AsyncReadExt;
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.txt\"\r\n\r\nabcdef\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::with_bytes(data, "X-BOUNDARY");
+
+    let mut reader = m.next_field().await.unwrap().unwrap().into_async_read();
+    assert_eq!(reader.field().name(), Some("my_file_field"));
+
+    let mut buf = [0; 4];
+    assert_eq!(reader.read(&mut buf).await.unwrap(), 4);
+    assert_eq!(&buf, b"abcd");
+
+    let mut rest = String::new();
+    reader.read_to_string(&mut rest).await.unwrap();
+    assert_eq!(rest, "ef");
+    drop(reader);
+
+    assert!(m.next_field().await.unwrap().is_none());
+}
+
+#[cfg(feature = "futures-io")]
+#[tokio::test]
+async fn test_multipart_field_into_futures_async_read() {
+    use futures_util::io::AsyncReadExt;
+
+    let data =
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let constraints = Constraints::new().size_limit(SizeLimit::new().per_field(2));
+    let mut m = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+
+    let mut reader = m.next_field().await.unwrap().unwrap().into_async_read();
+    let mut buf = Vec::new();
+    let err = reader.read_to_end(&mut buf).await.unwrap_err();
+    let err = err.into_inner().unwrap().downcast::<multer::Error>().unwrap();
+    assert!(matches!(*err, multer::Error::FieldSizeExceeded { .. }));
+}
+
+#[cfg(feature = "tokio-io")]
+#[tokio::test]
+async fn test_multipart_field_reader_lines() {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt};
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.csv\"\r\n\r\na,b\nc,d\ne\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+
+    let mut reader = m.next_field().await.unwrap().unwrap().into_async_read();
+    let mut line = String::new();
+    reader.read_line(&mut line).await.unwrap();
+    assert_eq!(line, "a,b\n");
+
+    let mut lines = reader.lines();
+    assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("c,d"));
+    assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("e"));
+    assert_eq!(lines.next_line().await.unwrap(), None);
+
+    let mut reader = lines.into_inner();
+    assert_eq!(reader.read(&mut [0; 4]).await.unwrap(), 0);
+}
+
+#[cfg(feature = "futures-io")]
+#[tokio::test]
+async fn test_multipart_with_futures_reader() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.bin\"\r\n\r\n";
+    let body = [data.as_bytes(), &[7; 10_000][..], b"\r\n--X-BOUNDARY--\r\n"].concat();
+    let mut m = Multipart::with_futures_reader(futures_util::io::Cursor::new(body), "X-BOUNDARY");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcd");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.bytes().await.unwrap(), vec![7; 10_000]);
+
+    assert!(m.next_field().await.unwrap().is_none());
+}
+
+#[cfg(feature = "http-body")]
+#[tokio::test]
+async fn test_multipart_from_body() {
+    use http_body_util::StreamBody;
+    use hyper::body::Frame;
+
+    let data =
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let mut trailers = http::HeaderMap::new();
+    trailers.insert("x-checksum", http::HeaderValue::from_static("abc"));
+    let frames = data
+        .split_inclusive('\n')
+        .map(|chunk| Frame::data(Bytes::from(chunk)))
+        .chain(std::iter::once(Frame::trailers(trailers)))
+        .map(Ok::<_, std::convert::Infallible>);
+    let mut m = Multipart::from_body(StreamBody::new(stream::iter(frames)), "X-BOUNDARY");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("my_text_field"));
+    assert_eq!(field.text().await.unwrap(), "abcd");
+    assert!(m.next_field().await.unwrap().is_none());
+}
+
+#[cfg(feature = "axum")]
+#[tokio::test]
+async fn test_axum_multipart_extractor() {
+    use axum_core::body::Body;
+    use axum_core::extract::FromRequest;
+    use axum_core::response::IntoResponse;
+
+    let data =
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let req = http::Request::builder()
+        .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+        .extension(Constraints::new().size_limit(SizeLimit::new().per_field(2)))
+        .body(Body::from(data))
+        .unwrap();
+
+    let mut m = multer::axum::Multipart::from_request(req, &()).await.unwrap();
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("my_text_field"));
+    let err = field.text().await.unwrap_err();
+    assert!(matches!(err, multer::Error::FieldSizeExceeded { limit: 2, .. }));
+
+    let req = http::Request::builder()
+        .header("content-type", "application/json")
+        .body(Body::from(data))
+        .unwrap();
+
+    let rejection = multer::axum::Multipart::from_request(req, &()).await.unwrap_err();
+    assert!(matches!(rejection.into_error(), multer::Error::NoMultipart));
+
+    let req = http::Request::builder().body(Body::from(data)).unwrap();
+    let rejection = multer::axum::Multipart::from_request(req, &()).await.unwrap_err();
+    assert_eq!(rejection.into_response().status(), http::StatusCode::BAD_REQUEST);
+}
+
+#[cfg(feature = "warp")]
+#[tokio::test]
+async fn test_warp_multipart_filter() {
+    use warp::Filter;
+
+    async fn handle(mut m: Multipart<'static>) -> Result<String, warp::Rejection> {
+        let field = m.next_field().await.map_err(multer::warp::reject)?.unwrap();
+        field.text().await.map_err(multer::warp::reject)
+    }
+
+    let constraints = Constraints::new().size_limit(SizeLimit::new().for_field("large", 2));
+    let route = multer::warp::multipart(constraints)
+        .and_then(handle)
+        .recover(multer::warp::recover);
+
+    let data =
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let res = warp::test::request()
+        .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+        .body(data)
+        .reply(&route)
+        .await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(res.body(), "abcd");
+
+    let res = warp::test::request()
+        .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+        .body(data.replace("my_text_field", "large"))
+        .reply(&route)
+        .await;
+    assert_eq!(res.status(), 413);
+
+    let res = warp::test::request().body(data).reply(&route).await;
+    assert_eq!(res.status(), 400);
+}
+
+#[cfg(feature = "http-body")]
+#[tokio::test]
+async fn test_request_into_multipart() {
+    use http_body_util::Full;
+    use multer::RequestExt;
+
+    let data =
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let req = http::Request::builder()
+        .header("content-type", "multipart/form-data; boundary=X-BOUNDARY")
+        .body(Full::new(Bytes::from(data)))
+        .unwrap();
+
+    let constraints = Constraints::new().allowed_fields(vec!["my_text_field"]);
+    let mut m = req.into_multipart(constraints).unwrap();
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcd");
+    assert!(m.next_field().await.unwrap().is_none());
+
+    let req = http::Request::new(Full::new(Bytes::from(data)));
+    let err = req.into_multipart(Constraints::new()).unwrap_err();
+    assert!(matches!(err, multer::Error::NoMultipart));
+
+    let req = http::Request::builder()
+        .header("content-type", "multipart/form-data")
+        .body(Full::new(Bytes::from(data)))
+        .unwrap();
+    let err = req.into_multipart(Constraints::new()).unwrap_err();
+    assert!(matches!(err, multer::Error::NoBoundary));
+}
+
+#[tokio::test]
+async fn test_multipart_stream() {
+    use futures_util::{StreamExt, TryStreamExt};
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nefgh\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"c\"\r\n\r\nijkl\r\n--X-BOUNDARY--\r\n";
+
+    let m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let names: Vec<_> = m
+        .take(2)
+        .map_ok(|field| field.name().unwrap().to_owned())
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(names, ["a", "b"]);
+
+    let m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let texts = std::sync::Mutex::new(Vec::new());
+    m.try_for_each_concurrent(4, |field| async {
+        let text = field.text().await?;
+        texts.lock().unwrap().push(text);
+        Ok(())
+    })
+    .await
+    .unwrap();
+    assert_eq!(texts.into_inner().unwrap(), ["abcd", "efgh", "ijkl"]);
+
+    // The stream waits for a field dropped on another task.
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+    let a = m.next().await.unwrap().unwrap();
+    let dropped = tokio::spawn(async move {
+        tokio::task::yield_now().await;
+        drop(a);
+    });
+    let b = tokio::time::timeout(std::time::Duration::from_secs(5), m.next()).await;
+    assert_eq!(b.unwrap().unwrap().unwrap().name(), Some("b"));
+    dropped.await.unwrap();
+}
+
+#[tokio::test]
+async fn test_multipart_owned_fields() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"; filename=\"b.txt\"\r\n\r\nefgh\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"c\"\r\n\r\nijkl\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"d\"\r\n\r\nmnop\r\n--X-BOUNDARY--\r\n";
+    let mut fields = Multipart::new(str_stream(data), "X-BOUNDARY").into_owned_fields();
+
+    let mut a = fields.next_field().await.unwrap().unwrap();
+    let first = a.chunk().await.unwrap().unwrap();
+
+    let b = fields.next_field().await.unwrap().unwrap();
+    assert_eq!(b.name(), Some("b"));
+    assert_eq!(b.file_name(), Some("b.txt"));
+    let b = tokio::spawn(b.bytes());
+
+    let c = fields.next_field().await.unwrap().unwrap();
+    drop(c);
+
+    let d = fields.next_field().await.unwrap().unwrap();
+    assert_eq!(d.index(), 3);
+    assert!(fields.next_field().await.unwrap().is_none());
+
+    assert_eq!(d.text().await.unwrap(), "mnop");
+    assert_eq!(b.await.unwrap().unwrap(), "efgh");
+    assert_eq!([first, a.bytes().await.unwrap()].concat(), b"abcd");
+}
+
+#[tokio::test]
+async fn test_multipart_peek_field() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.exe\"\r\n\r\nMZ...\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::new(str_stream(data), "X-BOUNDARY");
+
+    let field = m.peek_field().await.unwrap().unwrap();
+    assert_eq!(field.file_name(), Some("a.exe"));
+    assert_eq!(m.peek_field().await.unwrap().unwrap().index(), 0);
+    drop(m.next_field().await.unwrap().unwrap());
+
+    assert_eq!(m.peek_field().await.unwrap().unwrap().name(), Some("my_text_field"));
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcd");
+
+    assert!(m.peek_field().await.unwrap().is_none());
+    assert!(m.next_field().await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_multipart_skip_field() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"large\"; filename=\"a.bin\"\r\n\r\nsome large data\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"empty\"\r\n\r\n\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"last\"\r\n\r\nefgh\r\n--X-BOUNDARY--\r\n";
+    let constraints = Constraints::new().size_limit(SizeLimit::new().for_field("large", 4));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    assert!(m.skip_field().await.unwrap());
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("empty"));
+    field.skip();
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("my_text_field"));
+    assert_eq!(field.text().await.unwrap(), "abcd");
+
+    let mut field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.chunk().await.unwrap().unwrap(), "efgh");
+    field.skip();
+    assert!(!m.skip_field().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_multipart_form_groups() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"files[]\"; filename=\"a.txt\"\r\n\r\na\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"files[]\"; filename=\"b.txt\"\r\n\r\nb\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"filesize\"; filename=\"c.txt\"\r\n\r\nc\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tags[10]\"\r\n\r\nz\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tags[2]\"\r\n\r\ny\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tags[x]\"\r\n\r\nx\r\n--X-BOUNDARY--\r\n";
+    let form = Multipart::with_bytes(data, "X-BOUNDARY").collect_form().await.unwrap();
+
+    let files: Vec<_> = form.file_group("files").iter().map(|file| &file.data[..]).collect();
+    assert_eq!(files, [b"a", b"b"]);
+    assert!(form.file_group("tags").is_empty());
+    assert_eq!(form.text_group("tags"), ["y", "z"]);
+}
+
+#[tokio::test]
+async fn test_multipart_part_positions() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"unknown\"\r\n\r\nefgh\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nijkl\r\n--X-BOUNDARY--\r\n";
+    let constraints = Constraints::new()
+        .allowed_fields(vec!["a", "b"])
+        .unknown_fields(UnknownFieldPolicy::Ignore);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let mut field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.is_last(), None);
+    while field.chunk().await.unwrap().is_some() {}
+    assert_eq!(field.is_last(), Some(false));
+    drop(field);
+
+    let mut field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("b"));
+    while field.chunk().await.unwrap().is_some() {}
+    assert_eq!(field.is_last(), Some(true));
+    assert_eq!(m.parts_seen(), None);
+    drop(field);
+
+    assert!(m.next_field().await.unwrap().is_none());
+    assert_eq!(m.parts_seen(), Some(3));
+}
+
+#[cfg(feature = "tokio-io")]
+#[tokio::test]
+async fn test_field_copy_to() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.txt\"\r\n\r\nHello world\nHello\r\nWorld\rAgain\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY--\r\n";
+    let constraints = Constraints::new().size_limit(SizeLimit::new().for_field("my_text_field", 2));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let (mut writer, mut reader) = tokio::io::duplex(8);
+    let field = m.next_field().await.unwrap().unwrap();
+    let (written, read) = tokio::join!(field.copy_to(&mut writer), async {
+        let mut buf = Vec::new();
+        while buf.len() < 30 {
+            let mut chunk = [0; 4];
+            let n = tokio::io::AsyncReadExt::read(&mut reader, &mut chunk).await.unwrap();
+            buf.extend_from_slice(&chunk[..n]);
+        }
+        buf
+    });
+    assert_eq!(written.unwrap(), 30);
+    assert_eq!(read, b"Hello world\nHello\r\nWorld\rAgain");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    let err = field.copy_to(&mut Vec::new()).await.unwrap_err();
+    assert!(matches!(err, multer::Error::FieldSizeExceeded { limit: 2, .. }));
+}
+
+#[test]
+fn test_multipart_parse_full() {
+    let data = Bytes::from_static(b"preamble\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n--X-BOUNDARY \r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.txt\"\r\nContent-Type: text/plain\r\n\r\nHello world\nHello\r\nWorld\rAgain\r\n--X-BOUNDARY\r\n\r\n\r\n--X-BOUNDARY--\r\nepilogue");
+    let range = data.as_ptr_range();
+
+    let parts = Multipart::parse_full(data.clone(), "X-BOUNDARY").unwrap();
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[0].name(), Some("my_text_field"));
+    assert_eq!(parts[0].data(), "abcd");
+    assert_eq!(parts[1].file_name(), Some("a.txt"));
+    assert_eq!(parts[1].content_type(), Some(&mime::TEXT_PLAIN));
+    assert_eq!(parts[1].data(), "Hello world\nHello\r\nWorld\rAgain");
+    assert!(range.contains(&parts[1].data().as_ptr()));
+    assert!(parts[2].headers().is_empty());
+    assert!(parts[2].data().is_empty());
+
+    let end = data.len() - "\r\n--X-BOUNDARY\r\n\r\n\r\n--X-BOUNDARY--\r\nepilogue".len();
+    let err = Multipart::parse_full(data.slice(..end), "X-BOUNDARY").unwrap_err();
+    assert!(matches!(err, multer::Error::IncompleteFieldData { .. }));
+    assert!(Multipart::parse_full("abcd", "X-BOUNDARY").is_err());
+}
+
+#[tokio::test]
+async fn test_multipart_field_size_hint() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\nContent-Length: 10\r\n\r\nabcdefghij\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\nContent-Length: 100\r\n\r\nabcd\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"c\"\r\nContent-Transfer-Encoding: base64\r\n\
+        Content-Length: 8\r\n\r\nYWJjZA==\r\n--X-BOUNDARY--\r\n";
+    let constraints = Constraints::new().size_limit(SizeLimit::new().per_field(50));
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let mut field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.size_hint(), (10, Some(10)));
+    while field.chunk().await.unwrap().is_some() {}
+    assert_eq!(field.size_hint(), (0, Some(0)));
+    drop(field);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.size_hint(), (50, Some(50)));
+    assert_eq!(field.bytes().await.unwrap(), "abcd");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.size_hint(), (0, Some(8)));
+    assert_eq!(field.bytes().await.unwrap(), "abcd");
+}
+
+#[tokio::test]
+async fn test_multipart_on_progress() {
+    use std::sync::{Arc, Mutex};
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nefghij\r\n--X-BOUNDARY--\r\n";
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let constraints = Constraints::new().on_progress({
+        let progress = progress.clone();
+        move |field_name, field_bytes, total_bytes| {
+            progress
+                .lock()
+                .unwrap()
+                .push((field_name.map(str::to_owned), field_bytes, total_bytes))
+        }
+    });
+    let mut m = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+
+    while let Some(field) = m.next_field().await.unwrap() {
+        field.bytes().await.unwrap();
+    }
+
+    // The line ending before the boundary is consumed with the last chunk.
+    let first_end = data.find("abcd\r\n").unwrap() as u64 + 6;
+    let second_end = data.find("efghij\r\n").unwrap() as u64 + 8;
+    assert_eq!(
+        *progress.lock().unwrap(),
+        [
+            (Some("a".to_owned()), 4, first_end),
+            (Some("b".to_owned()), 6, second_end)
+        ]
+    );
+
+    // Skipped fields, ignored fields and drained data are reported as well.
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"x\"\r\n\r\nxyz\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nefghij\r\n--X-BOUNDARY--\r\n";
+    progress.lock().unwrap().clear();
+    let constraints = Constraints::new()
+        .allowed_fields(vec!["a", "b"])
+        .unknown_fields(UnknownFieldPolicy::Ignore)
+        .on_progress({
+            let progress = progress.clone();
+            move |field_name, field_bytes, total_bytes| {
+                progress
+                    .lock()
+                    .unwrap()
+                    .push((field_name.map(str::to_owned), field_bytes, total_bytes))
+            }
+        });
+    let mut m = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+
+    assert!(m.skip_field().await.unwrap());
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.name(), Some("b"));
+    drop(field);
+    m.drain().await.unwrap();
+
+    let first_end = data.find("abcd\r\n").unwrap() as u64 + 6;
+    let second_end = data.find("xyz\r\n").unwrap() as u64 + 5;
+    assert_eq!(
+        *progress.lock().unwrap(),
+        [
+            (Some("a".to_owned()), 4, first_end),
+            (Some("x".to_owned()), 3, second_end),
+            (None, 0, data.len() as u64),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_multipart_field_inspector() {
+    use std::sync::Mutex;
+
+    use multer::{Constraint, Field, FieldInspector, Veto};
+
+    #[derive(Default)]
+    struct Inspector {
+        ended: Mutex<Vec<(String, usize)>>,
+        sizes: Mutex<Vec<usize>>,
+    }
+
+    impl FieldInspector for Inspector {
+        fn inspect_chunk(&self, field: &Field<'_>, chunk: &[u8]) -> Result<(), Veto> {
+            match chunk {
+                b"bad" => Err(Veto::field("bad field")),
+                b"evil" => Err(Veto::request("evil request")),
+                _ => {
+                    self.sizes.lock().unwrap().push(chunk.len());
+                    assert!(field.name().is_some());
+                    Ok(())
+                }
+            }
+        }
+
+        fn end_field(&self, field: &Field<'_>) -> Result<(), Veto> {
+            let size = self.sizes.lock().unwrap().drain(..).sum();
+            let name = field.name().unwrap().to_owned();
+            self.ended.lock().unwrap().push((name, size));
+            Ok(())
+        }
+    }
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nbad\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"c\"\r\n\r\nefg\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"d\"\r\n\r\nevil\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"e\"\r\n\r\nhij\r\n--X-BOUNDARY--\r\n";
+    let inspector = std::sync::Arc::new(Inspector::default());
+
+    let constraints = Constraints::new().required_fields(vec!["e"]).inspect(inspector.clone());
+    let mut m = Multipart::with_bytes_with_constraints(data, "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "abcd");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    let err = field.bytes().await.unwrap_err();
+    assert!(matches!(err, multer::Error::FieldRejected { .. }));
+    assert_eq!(err.violation().unwrap().constraint(), Constraint::Inspector);
+    assert_eq!(err.violation().unwrap().field_name(), Some("b"));
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.text().await.unwrap(), "efg");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    let err = field.bytes().await.unwrap_err();
+    assert!(matches!(err, multer::Error::RequestRejected { .. }));
+    assert_eq!(err.to_string(), "request was rejected at field \"d\": evil request");
+
+    // The rest of the request is dropped, including its required fields.
+    assert!(m.next_field().await.unwrap().is_none());
+
+    let ended = inspector.ended.lock().unwrap();
+    assert_eq!(*ended, [("a".to_owned(), 4), ("c".to_owned(), 3)]);
+}
+
+#[cfg(feature = "sink")]
+#[tokio::test]
+async fn test_multipart_field_forward_to() {
+    use std::io;
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"b\"\r\n\r\nefghij\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"c\"\r\n\r\nklm\r\n--X-BOUNDARY--\r\n";
+    let mut m = Multipart::with_bytes(data, "X-BOUNDARY");
+
+    // The sink isn't closed, so several fields may be forwarded into it.
+    let mut chunks: Vec<Bytes> = Vec::new();
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.forward_to(&mut chunks).await.unwrap(), 4);
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.forward_to(&mut chunks).await.unwrap(), 6);
+    assert_eq!(chunks.concat(), b"abcdefghij");
+
+    let mut failing = futures_util::sink::unfold((), |(), _: Bytes| {
+        futures_util::future::ready(Err::<(), _>(io::Error::new(io::ErrorKind::BrokenPipe, "closed")))
+    });
+    let field = m.next_field().await.unwrap().unwrap();
+    let err = field.forward_to(&mut failing).await.unwrap_err();
+    assert!(matches!(err, multer::Error::ForwardFailed(_)));
+    assert_eq!(std::error::Error::source(&err).unwrap().to_string(), "closed");
+}
+
+#[cfg(feature = "cancellation")]
+#[tokio::test]
+async fn test_multipart_cancellation_token() {
+    use tokio_util::sync::CancellationToken;
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd";
+    let stream = futures_util::StreamExt::chain(str_stream(data), stream::pending());
+    let token = CancellationToken::new();
+    let constraints = Constraints::new().cancellation_token(token.clone());
+    let mut m = Multipart::with_constraints(stream, "X-BOUNDARY", constraints);
+
+    let mut field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.chunk().await.unwrap().unwrap(), "abcd");
+
+    let cancel = tokio::spawn(async move {
+        tokio::task::yield_now().await;
+        token.cancel();
+    });
+    assert!(matches!(field.chunk().await, Err(multer::Error::Cancelled)));
+    cancel.await.unwrap();
+
+    drop(field);
+    assert!(matches!(m.next_field().await, Err(multer::Error::Cancelled)));
+}
+
+#[tokio::test]
+async fn test_multipart_drain() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_text_field\"\r\n\r\nabcd\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"my_file_field\"; filename=\"a.txt\"\r\n\r\nHello world\r\n\
+        --X-BOUNDARY--\r\nepilogue";
+    let consumed = Arc::new(AtomicUsize::new(0));
+    let stream = futures_util::StreamExt::inspect(str_stream(data), {
+        let consumed = consumed.clone();
+        move |_| {
+            consumed.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+    let constraints = Constraints::new().required_fields(vec!["missing"]);
+    let mut m = Multipart::with_constraints(stream, "X-BOUNDARY", constraints);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(matches!(m.drain().await, Err(multer::Error::LockFailure)));
+    drop(field);
+
+    m.drain().await.unwrap();
+    assert_eq!(consumed.load(Ordering::SeqCst), data.len());
+    assert!(m.next_field().await.unwrap().is_none());
+    m.drain().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_multipart_form_get_all() {
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tags\"\r\n\r\na\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"other\"\r\n\r\nb\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tags\"\r\n\r\nc\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tags[]\"\r\n\r\nd\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"tags[]\"\r\n\r\ne\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"docs\"; filename=\"a.txt\"\r\n\r\nf\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"docs\"; filename=\"b.txt\"\r\n\r\ng\r\n--X-BOUNDARY--\r\n";
+    let form = Multipart::with_bytes(data, "X-BOUNDARY").collect_form().await.unwrap();
+
+    assert_eq!(form.get_all("tags"), ["a", "c"]);
+    assert_eq!(form.get_all("other"), ["b"]);
+    assert!(form.get_all("docs").is_empty());
+    assert_eq!(form.text_group("tags"), ["d", "e"]);
+
+    let docs: Vec<_> = form
+        .files_all("docs")
+        .iter()
+        .map(|file| file.file_name.as_str())
+        .collect();
+    assert_eq!(docs, ["a.txt", "b.txt"]);
+}
+
+#[tokio::test]
+async fn test_multipart_field_parse() {
+    let data = format!(
+        "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"count\"\r\n\r\n42\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"ratio\"\r\n\r\n0.5\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"count\"\r\n\r\n-1\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"big\"\r\n\r\n{}1\r\n--X-BOUNDARY--\r\n",
+        "0".repeat(1024)
+    );
+    let mut m = Multipart::with_bytes(data, "X-BOUNDARY");
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.parse::<u32>().await.unwrap(), 42);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert_eq!(field.parse::<f64>().await.unwrap(), 0.5);
+
+    let field = m.next_field().await.unwrap().unwrap();
+    let err = field.parse::<u32>().await.unwrap_err();
+    assert!(matches!(&err, multer::Error::ParseFailed { field_name: Some(name), .. } if name == "count"));
+    assert!(std::error::Error::source(&err).unwrap().is::<std::num::ParseIntError>());
+
+    let field = m.next_field().await.unwrap().unwrap();
+    assert!(matches!(
+        field.parse::<u64>().await,
+        Err(multer::Error::FieldSizeExceeded { limit: 1024, .. })
+    ));
+}
+
+#[cfg(feature = "urlencoded")]
+#[tokio::test]
+async fn test_form_data() {
+    use multer::FormData;
+
+    let data = "--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n\
+        --X-BOUNDARY\r\nContent-Disposition: form-data; name=\"f\"; filename=\"a.txt\"\r\n\r\nefgh\r\n--X-BOUNDARY--\r\n";
+    let mut form = FormData::new(str_stream(data), "multipart/form-data; boundary=X-BOUNDARY").unwrap();
+    assert!(form.is_multipart());
+
+    let field = form.next_field().await.unwrap().unwrap();
+    assert_eq!((field.name(), field.index()), (Some("a"), 0));
+    assert_eq!(field.text().await.unwrap(), "abcd");
+    let field = form.next_field().await.unwrap().unwrap();
+    assert_eq!(field.file_name(), Some("a.txt"));
+    assert!(field.into_multipart_field().is_some());
+    assert!(form.next_field().await.unwrap().is_none());
+
+    let data = "a=ab%20cd&tags=x&unknown=1&tags=y+z&empty=";
+    let constraints = Constraints::new()
+        .allowed_fields(vec!["a", "tags", "empty"])
+        .unknown_fields(UnknownFieldPolicy::Ignore)
+        .required_fields(vec!["a"]);
+    let mut form =
+        FormData::with_constraints(str_stream(data), "application/x-www-form-urlencoded", constraints).unwrap();
+    assert!(!form.is_multipart());
+
+    let mut fields = Vec::new();
+    while let Some(mut field) = form.next_field().await.unwrap() {
+        assert!(field.file_name().is_none() && field.content_type().is_none() && field.headers().is_empty());
+        let name = field.name().unwrap().to_owned();
+        let index = field.index();
+        let chunk = field.chunk().await.unwrap();
+        assert!(field.chunk().await.unwrap().is_none());
+        fields.push((name, index, chunk));
+    }
+    assert_eq!(
+        fields,
+        [
+            ("a".to_owned(), 0, Some(Bytes::from("ab cd"))),
+            ("tags".to_owned(), 1, Some(Bytes::from("x"))),
+            ("tags".to_owned(), 3, Some(Bytes::from("y z"))),
+            ("empty".to_owned(), 4, Some(Bytes::new())),
+        ]
+    );
+
+    let constraints = Constraints::new().size_limit(SizeLimit::new().for_field("a", 2));
+    let mut form =
+        FormData::with_constraints(str_stream(data), "application/x-www-form-urlencoded", constraints).unwrap();
+    assert!(matches!(
+        form.next_field().await,
+        Err(multer::Error::FieldSizeExceeded { limit: 2, .. })
+    ));
+
+    let constraints = Constraints::new().required_fields(vec!["missing"]);
+    let mut form =
+        FormData::with_constraints(str_stream(""), "application/x-www-form-urlencoded", constraints).unwrap();
+    assert!(matches!(
+        form.next_field().await,
+        Err(multer::Error::MissingRequiredFields { .. })
+    ));
+
+    assert!(matches!(
+        FormData::new(str_stream(""), "text/plain"),
+        Err(multer::Error::NoMultipart)
+    ));
+    assert!(matches!(
+        FormData::new(str_stream(""), "multipart/form-data"),
+        Err(multer::Error::NoBoundary)
+    ));
+}
+
+#[tokio::test]
+async fn test_multipart_capture_raw() {
+    let data = "preamble\r\n--X-BOUNDARY  \r\nContent-Disposition: form-data; name=\"a\"\r\n\r\nabcd\r\n\
+        --X-BOUNDARY\nContent-Disposition: form-data; name=\"b\"\nContent-Transfer-Encoding: quoted-printable\n\n\
+        caf=C3=A9\r\n--X-BOUNDARY\r\nContent-Disposition: form-data; name=\"skipped\"\r\n\r\nefgh\r\n\
+        --X-BOUNDARY--\r\nepilogue";
+    let constraints = Constraints::new().capture_raw(true).allow_lf_line_endings(true);
+    let mut m = Multipart::with_constraints(str_stream(data), "X-BOUNDARY", constraints);
+
+    let mut raw = Vec::new();
+    let mut texts = Vec::new();
+    while let Some(mut field) = m.next_field().await.unwrap() {
+        raw.extend_from_slice(&m.take_raw());
+        if field.name() == Some("skipped") {
+            continue;
+        }
+
+        let mut text = Vec::new();
+        while let Some(chunk) = field.chunk().await.unwrap() {
+            text.extend_from_slice(&chunk);
+            raw.extend_from_slice(&field.take_raw());
+        }
+        texts.push(String::from_utf8(text).unwrap());
+    }
+
+    m.epilogue().await.unwrap();
+    raw.extend_from_slice(&m.take_raw());
+    assert_eq!(texts, ["abcd", "café"]);
+    assert_eq!(String::from_utf8(raw).unwrap(), data);
+    assert!(m.take_raw().is_empty());
+
+    let mut m = Multipart::with_bytes(data, "X-BOUNDARY");
+    assert!(m.next_field().await.unwrap().is_some());
+    assert!(m.take_raw().is_empty());
+}